@@ -1,8 +1,9 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use oats_framework::{Object, Trait, TraitData, Action, ActionContext, ActionResult, System, SystemManager, Priority, OatsError};
+use oats_framework::{Object, ObjectFilter, Trait, TraitData, Action, ActionContext, ActionResult, System, SystemManager, Priority, OatsError};
+use oats_framework::{StreamingAction, StreamingSystem};
+use oats_framework::actions::ActionStream;
 use std::collections::HashMap;
 use async_trait::async_trait;
-use tokio::runtime::Runtime;
 
 // Benchmark increment action
 #[derive(Clone)]
@@ -30,7 +31,7 @@ impl Action for BenchmarkIncrementAction {
         "Benchmark increment action"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, OatsError> {
         let target = context.get_object("target").unwrap();
         let current_value = target.get_trait(&self.trait_name)
             .and_then(|t| t.data().as_number())
@@ -45,6 +46,59 @@ impl Action for BenchmarkIncrementAction {
     }
 }
 
+// Increment action that overrides execute_batch to handle all contexts in one call
+struct BatchedIncrementAction(BenchmarkIncrementAction);
+
+#[async_trait]
+impl Action for BatchedIncrementAction {
+    fn name(&self) -> &str {
+        "batched_increment"
+    }
+
+    fn description(&self) -> &str {
+        "Benchmark increment action with batch support"
+    }
+
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, OatsError> {
+        self.0.execute(context).await
+    }
+
+    async fn execute_batch(&self, contexts: Vec<ActionContext<'_>>) -> Result<Vec<ActionResult>, OatsError> {
+        let mut results = Vec::with_capacity(contexts.len());
+        for context in &contexts {
+            let target = context.get_object("target").unwrap();
+            let current_value = target.get_trait_data(&self.0.trait_name)
+                .and_then(|d| d.as_number())
+                .unwrap_or(0.0);
+
+            let mut result = ActionResult::success_with_capacity(1, 0, 0);
+            result.add_trait_update(Trait::new(&self.0.trait_name, TraitData::Number(current_value + self.0.increment)));
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    fn supports_batch(&self) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl StreamingAction for BenchmarkIncrementAction {
+    fn name(&self) -> &str {
+        "benchmark_increment_stream"
+    }
+
+    fn description(&self) -> &str {
+        "Benchmark streaming increment action"
+    }
+
+    async fn stream_execute(&self, context: ActionContext<'_>) -> Result<ActionStream, OatsError> {
+        let result = Action::execute(self, context).await;
+        Ok(Box::pin(futures::stream::once(async move { result })))
+    }
+}
+
 // Benchmark system
 struct BenchmarkSystem {
     stats: oats_framework::systems::SystemStats,
@@ -68,7 +122,7 @@ impl System for BenchmarkSystem {
         "Benchmark system"
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
         let mut results = Vec::with_capacity(objects.len());
         let start_time = std::time::Instant::now();
 
@@ -100,6 +154,133 @@ impl System for BenchmarkSystem {
     }
 }
 
+// Benchmark system that only acts on "relevant" objects, filtering either early or in process
+struct RelevantObjectsSystem {
+    early_filter: bool,
+}
+
+#[async_trait]
+impl System for RelevantObjectsSystem {
+    fn name(&self) -> &str {
+        "relevant_objects_system"
+    }
+
+    fn description(&self) -> &str {
+        "Processes only relevant objects"
+    }
+
+    fn object_filter(&self) -> Option<ObjectFilter> {
+        self.early_filter.then(|| ObjectFilter::object_type("relevant"))
+    }
+
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+        let mut results = Vec::new();
+        let action = BenchmarkIncrementAction::new("health", 1.0);
+
+        for object in objects {
+            if object.object_type() != "relevant" {
+                continue;
+            }
+            let mut context = ActionContext::new();
+            context.add_object("target", object);
+            results.push(action.execute(context).await?);
+        }
+
+        Ok(results)
+    }
+}
+
+// CPU-bound benchmark system whose per-object cost is configurable
+struct WeightedSystem {
+    name: String,
+    work_per_object: usize,
+    thread_safe: bool,
+}
+
+impl WeightedSystem {
+    fn new(name: impl Into<String>, work_per_object: usize, thread_safe: bool) -> Self {
+        Self { name: name.into(), work_per_object, thread_safe }
+    }
+}
+
+#[async_trait]
+impl System for WeightedSystem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "Spends a fixed amount of CPU per object"
+    }
+
+    fn is_thread_safe(&self) -> bool {
+        self.thread_safe
+    }
+
+    async fn process(&mut self, objects: &[Object], priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+        let mut results = Vec::with_capacity(objects.len());
+        for object in objects {
+            results.extend(self.process_object(object, priority)?);
+        }
+        Ok(results)
+    }
+
+    fn process_object(&self, object: &Object, _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+        let health = object.get_trait_data("health").and_then(|d| d.as_number()).unwrap_or(1.0);
+        let score: f64 = (0..self.work_per_object).map(|i| (health + i as f64).sqrt()).sum();
+        let mut result = ActionResult::success();
+        result.add_data("score", serde_json::json!(score));
+        Ok(vec![result])
+    }
+}
+
+/// Ten systems over the same objects, one of them ten times slower than the rest
+fn weighted_systems(thread_safe: bool) -> Vec<WeightedSystem> {
+    let mut systems = vec![WeightedSystem::new("pricing", 2000, thread_safe)];
+    systems.extend((0..9).map(|i| WeightedSystem::new(format!("weighted_{}", i), 200, thread_safe)));
+    systems
+}
+
+// Benchmark system with an expensive rate table loaded lazily or during warm-up
+#[derive(Default)]
+struct ColdStartSystem {
+    rates: Option<Vec<f64>>,
+}
+
+impl ColdStartSystem {
+    fn load_rates() -> Vec<f64> {
+        (0..200_000).map(|i| (i as f64).sqrt().sin().abs()).collect()
+    }
+}
+
+#[async_trait]
+impl System for ColdStartSystem {
+    fn name(&self) -> &str {
+        "cold_start_system"
+    }
+
+    fn description(&self) -> &str {
+        "Prices objects from an expensive rate table"
+    }
+
+    async fn warm_up(&mut self, _sample_objects: &[Object]) -> Result<(), OatsError> {
+        self.rates = Some(Self::load_rates());
+        Ok(())
+    }
+
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+        let rates = self.rates.get_or_insert_with(Self::load_rates);
+        let mut results = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            let health = object.get_trait_data("health").and_then(|d| d.as_number()).unwrap_or(0.0);
+            let mut result = ActionResult::success();
+            result.add_data("price", serde_json::json!(health * rates[i % rates.len()]));
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
 fn create_test_objects(count: usize) -> Vec<Object> {
     let mut objects = Vec::with_capacity(count);
     
@@ -315,6 +496,33 @@ fn benchmark_action_execution(c: &mut Criterion) {
         });
     });
 
+    let batch_contexts: Vec<ActionContext<'_>> = create_test_objects(1000)
+        .into_iter()
+        .map(|obj| ActionContext::from_object_pairs([("target", obj)]))
+        .collect();
+
+    group.bench_function("execute_loop_1000", |b| {
+        let action: Box<dyn Action> = Box::new(BenchmarkIncrementAction::new("health", 1.0));
+        b.iter(|| {
+            rt.block_on(async {
+                let mut results = Vec::with_capacity(batch_contexts.len());
+                for context in batch_contexts.iter().cloned() {
+                    results.push(action.execute(context).await.unwrap());
+                }
+                black_box(results);
+            });
+        });
+    });
+
+    group.bench_function("execute_batch_1000", |b| {
+        let action: Box<dyn Action> = Box::new(BatchedIncrementAction(BenchmarkIncrementAction::new("health", 1.0)));
+        b.iter(|| {
+            rt.block_on(async {
+                black_box(action.execute_batch(batch_contexts.clone()).await.unwrap());
+            });
+        });
+    });
+
     group.finish();
 }
 
@@ -328,7 +536,7 @@ fn benchmark_system_processing(c: &mut Criterion) {
                 let mut system = BenchmarkSystem::new();
                 let objects = create_test_objects(100);
                 
-                black_box(system.process(objects, Priority::Normal).await.unwrap());
+                black_box(system.process(&objects, Priority::Normal).await.unwrap());
             });
         });
     });
@@ -339,7 +547,7 @@ fn benchmark_system_processing(c: &mut Criterion) {
                 let mut system = BenchmarkSystem::new();
                 let objects = create_test_objects(1000);
                 
-                black_box(system.process(objects, Priority::Normal).await.unwrap());
+                black_box(system.process(&objects, Priority::Normal).await.unwrap());
             });
         });
     });
@@ -350,7 +558,50 @@ fn benchmark_system_processing(c: &mut Criterion) {
                 let mut system = BenchmarkSystem::new();
                 let objects = create_test_objects(10000);
                 
-                black_box(system.process(objects, Priority::Normal).await.unwrap());
+                black_box(system.process(&objects, Priority::Normal).await.unwrap());
+            });
+        });
+    });
+
+    // Only the first batch is timed; the warm variant loads its rate table in setup
+    group.bench_function("first_batch_cold", |b| {
+        b.iter_batched(
+            || (ColdStartSystem::default(), create_test_objects(100)),
+            |(mut system, objects)| {
+                rt.block_on(async { black_box(system.process(&objects, Priority::Normal).await.unwrap()) })
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("first_batch_warm", |b| {
+        b.iter_batched(
+            || {
+                let mut system = ColdStartSystem::default();
+                let objects = create_test_objects(100);
+                rt.block_on(system.warm_up(&objects[..1])).unwrap();
+                (system, objects)
+            },
+            |(mut system, objects)| {
+                rt.block_on(async { black_box(system.process(&objects, Priority::Normal).await.unwrap()) })
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    group.bench_function("stream_execute_1000_objects", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let manager = SystemManager::with_capacity(1000);
+                let objects = create_test_objects(1000);
+                for obj in objects.iter().cloned() {
+                    manager.register_object(obj).await.unwrap();
+                }
+
+                let mut system = StreamingSystem::new("benchmark_stream", "Benchmark streaming system", manager.registry());
+                system.add_action(Box::new(BenchmarkIncrementAction::new("health", 1.0)));
+
+                black_box(system.process(&objects, Priority::Normal).await.unwrap());
             });
         });
     });
@@ -369,7 +620,7 @@ fn benchmark_system_manager(c: &mut Criterion) {
                 let objects = create_test_objects(100);
                 
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 manager.add_system(Box::new(BenchmarkSystem::new()));
@@ -385,7 +636,7 @@ fn benchmark_system_manager(c: &mut Criterion) {
                 let objects = create_test_objects(100);
                 
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 // Add multiple systems
@@ -398,6 +649,79 @@ fn benchmark_system_manager(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("register_1000_objects", |b| {
+        let objects = create_test_objects(1000);
+        b.iter(|| {
+            rt.block_on(async {
+                let manager = SystemManager::with_capacity(objects.len());
+                for obj in objects.iter().cloned() {
+                    manager.register_object(obj).await.unwrap();
+                }
+                black_box(manager);
+            });
+        });
+    });
+
+    // Baseline for the per-type counting overhead: the same inserts without counting
+    group.bench_function("register_1000_objects_uncounted", |b| {
+        let objects = create_test_objects(1000);
+        b.iter(|| {
+            rt.block_on(async {
+                let manager = SystemManager::with_capacity(objects.len());
+                let registry = manager.registry();
+                for obj in objects.iter().cloned() {
+                    registry.write().await.insert(obj.id().to_string(), obj);
+                }
+                black_box(manager);
+            });
+        });
+    });
+
+    let counted_manager = SystemManager::with_capacity(1000);
+    rt.block_on(async {
+        for obj in create_test_objects(1000) {
+            counted_manager.register_object(obj).await.unwrap();
+        }
+    });
+
+    group.bench_function("object_count_by_type", |b| {
+        b.iter(|| black_box(counted_manager.object_count_by_type("test_type")));
+    });
+
+    group.bench_function("object_count_by_type_locked", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let registry = counted_manager.registry();
+                let registry = registry.read().await;
+                black_box(registry.values().filter(|o| o.object_type() == "test_type").count());
+            });
+        });
+    });
+
+    // 10% of objects are relevant to the system
+    let mut mostly_irrelevant = create_test_objects(1000);
+    for obj in mostly_irrelevant.iter_mut().step_by(10) {
+        obj.retype("relevant").unwrap();
+    }
+
+    for (label, early_filter) in [("filter_in_process_90pct_irrelevant", false), ("object_filter_90pct_irrelevant", true)] {
+        let mut manager = SystemManager::with_capacity(mostly_irrelevant.len());
+        rt.block_on(async {
+            for obj in mostly_irrelevant.iter().cloned() {
+                manager.register_object(obj).await.unwrap();
+            }
+        });
+        manager.add_system(Box::new(RelevantObjectsSystem { early_filter }));
+
+        group.bench_function(label, |b| {
+            b.iter(|| {
+                rt.block_on(async {
+                    black_box(manager.process_all(Priority::Normal).await.unwrap());
+                });
+            });
+        });
+    }
+
     group.finish();
 }
 
@@ -429,6 +753,25 @@ fn benchmark_serialization(c: &mut Criterion) {
         });
     });
 
+    #[cfg(feature = "proto")]
+    group.bench_function("proto_serialize_object", |b| {
+        let obj = create_test_objects(1)[0].clone();
+
+        b.iter(|| {
+            black_box(obj.to_proto_bytes().unwrap());
+        });
+    });
+
+    #[cfg(feature = "proto")]
+    group.bench_function("proto_deserialize_object", |b| {
+        let obj = create_test_objects(1)[0].clone();
+        let bytes = obj.to_proto_bytes().unwrap();
+
+        b.iter(|| {
+            black_box(Object::from_proto_bytes(&bytes).unwrap());
+        });
+    });
+
     group.finish();
 }
 
@@ -439,12 +782,12 @@ fn benchmark_concurrent_operations(c: &mut Criterion) {
     group.bench_function("concurrent_object_registration", |b| {
         b.iter(|| {
             rt.block_on(async {
-                let mut manager = SystemManager::with_capacity(1000);
+                let manager = SystemManager::with_capacity(1000);
                 let objects = create_test_objects(100);
                 
                 // Register objects sequentially to avoid cloning issues
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 black_box(manager);
@@ -452,6 +795,19 @@ fn benchmark_concurrent_operations(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("bulk_object_registration", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let manager = SystemManager::with_capacity(1000);
+                let objects = create_test_objects(100);
+
+                manager.register_objects_bulk(objects).await.unwrap();
+
+                black_box(manager);
+            });
+        });
+    });
+
     group.bench_function("concurrent_action_execution", |b| {
         b.iter(|| {
             rt.block_on(async {
@@ -533,7 +889,7 @@ fn benchmark_stress_tests(c: &mut Criterion) {
                 // Register objects in chunks to avoid memory issues
                 for chunk in objects.chunks(1000) {
                     for obj in chunk {
-                        manager.register_object(obj.clone()).await;
+                        manager.register_object(obj.clone()).await.unwrap();
                     }
                 }
                 
@@ -544,6 +900,51 @@ fn benchmark_stress_tests(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("stress_100k_objects_with_progress", |b| {
+        let objects = create_test_objects(100_000);
+        b.iter(|| {
+            rt.block_on(async {
+                let mut manager = SystemManager::with_capacity(objects.len());
+                for obj in objects.iter().cloned() {
+                    manager.register_object(obj).await.unwrap();
+                }
+                manager.add_system(Box::new(BenchmarkSystem::new()));
+
+                // Hidden so benchmark output stays readable; swap for `ProgressBar::new` to watch
+                let bar = indicatif::ProgressBar::hidden();
+                bar.set_length(100);
+                let progress = bar.clone();
+                let results = manager
+                    .run_with_progress(Priority::Normal, move |event| progress.set_position(event.percentage as u64))
+                    .await
+                    .unwrap();
+                bar.finish();
+                black_box(results.len());
+            });
+        });
+    });
+
+    group.bench_function("stress_100k_objects_at_limit", |b| {
+        let objects = create_test_objects(100_000);
+        let overflow = create_test_objects(1000);
+        b.iter(|| {
+            rt.block_on(async {
+                let manager = SystemManager::with_capacity(100_000).with_max_objects(100_000);
+                for obj in objects.iter().cloned() {
+                    manager.register_object(obj).await.unwrap();
+                }
+
+                // At the limit new objects are rejected until room is made
+                assert!(manager.register_object(overflow[0].clone()).await.is_err());
+                black_box(manager.evict_oldest_n(overflow.len()).await);
+                for obj in overflow.iter().cloned() {
+                    manager.register_object(obj).await.unwrap();
+                }
+                black_box(manager.object_count().await);
+            });
+        });
+    });
+
     group.bench_function("stress_many_systems", |b| {
         b.iter(|| {
             rt.block_on(async {
@@ -551,11 +952,11 @@ fn benchmark_stress_tests(c: &mut Criterion) {
                 let objects = create_test_objects(1000);
                 
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 // Add many systems to test system management
-                for i in 0..50 {
+                for _ in 0..50 {
                     let system = BenchmarkSystem::new();
                     manager.add_system(Box::new(system));
                 }
@@ -566,6 +967,34 @@ fn benchmark_stress_tests(c: &mut Criterion) {
         });
     });
 
+    // Ten systems over 2000 objects, set up once so only processing is measured
+    let weighted_manager = |thread_safe: bool| {
+        let mut manager = SystemManager::with_capacity(2000);
+        rt.block_on(async {
+            for obj in create_test_objects(2000) {
+                manager.register_object(obj).await.unwrap();
+            }
+        });
+        for system in weighted_systems(thread_safe) {
+            manager.add_system(Box::new(system));
+        }
+        manager
+    };
+
+    let mut sequential = weighted_manager(false);
+    group.bench_function("stress_many_systems_sequential", |b| {
+        b.iter(|| black_box(rt.block_on(sequential.process_all(Priority::Normal)).unwrap().len()));
+    });
+
+    // Compare with the sequential run above; the gap grows with the core count
+    #[cfg(feature = "work_stealing")]
+    {
+        let mut work_stealing = oats_framework::WorkStealingSystemManager::new(weighted_manager(true), 0).unwrap();
+        group.bench_function("stress_many_systems_work_stealing", |b| {
+            b.iter(|| black_box(rt.block_on(work_stealing.process_all(Priority::Normal)).unwrap().len()));
+        });
+    }
+
     group.bench_function("stress_concurrent_actions", |b| {
         b.iter(|| {
             rt.block_on(async {
@@ -625,7 +1054,7 @@ fn benchmark_throughput_analysis(c: &mut Criterion) {
                 let objects = create_test_objects(1000);
                 
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 manager.add_system(Box::new(BenchmarkSystem::new()));
@@ -646,7 +1075,7 @@ fn benchmark_throughput_analysis(c: &mut Criterion) {
                 let objects = create_test_objects(10000);
                 
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 manager.add_system(Box::new(BenchmarkSystem::new()));
@@ -1,8 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use oats_framework::{Object, Trait, TraitData, Action, ActionContext, ActionResult, System, SystemManager, Priority, OatsError};
-use std::collections::HashMap;
 use async_trait::async_trait;
-use tokio::runtime::Runtime;
 
 // Simple benchmark action
 struct SimpleBenchmarkAction;
@@ -17,7 +15,7 @@ impl Action for SimpleBenchmarkAction {
         "Simple benchmark action"
     }
 
-    async fn execute(&self, _context: ActionContext) -> Result<ActionResult, OatsError> {
+    async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult, OatsError> {
         let mut result = ActionResult::success();
         result.add_message("Benchmark action executed");
         Ok(result)
@@ -47,7 +45,7 @@ impl System for SimpleBenchmarkSystem {
         "Simple benchmark system"
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
         let mut results = Vec::with_capacity(objects.len());
         let start_time = std::time::Instant::now();
 
@@ -134,7 +132,7 @@ fn simple_benchmarks(c: &mut Criterion) {
             rt.block_on(async {
                 let mut system = SimpleBenchmarkSystem::new();
                 let objects = create_simple_objects(100);
-                black_box(system.process(objects, Priority::Normal).await.unwrap());
+                black_box(system.process(&objects, Priority::Normal).await.unwrap());
             });
         });
     });
@@ -148,7 +146,7 @@ fn simple_benchmarks(c: &mut Criterion) {
                 let objects = create_simple_objects(100);
                 
                 for obj in objects {
-                    manager.register_object(obj).await;
+                    manager.register_object(obj).await.unwrap();
                 }
                 
                 manager.add_system(Box::new(SimpleBenchmarkSystem::new()));
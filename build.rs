@@ -0,0 +1,13 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/proto/oats.proto");
+
+    #[cfg(feature = "proto")]
+    {
+        if std::env::var_os("PROTOC").is_none() {
+            let protoc = protoc_bin_vendored::protoc_bin_path().expect("no vendored protoc for this platform");
+            std::env::set_var("PROTOC", protoc);
+        }
+        prost_build::compile_protos(&["src/proto/oats.proto"], &["src/proto"]).expect("failed to compile oats.proto");
+    }
+}
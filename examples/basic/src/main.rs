@@ -15,7 +15,7 @@ impl Action for HealAction {
         "Restores health"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, oats_framework::OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, oats_framework::OatsError> {
         let target = context.get_object("target").unwrap();
         let current_health = target.get_trait("health")
             .and_then(|t| t.data().as_number())
@@ -44,7 +44,7 @@ impl Action for DamageAction {
         "Inflicts damage"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, oats_framework::OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, oats_framework::OatsError> {
         let target = context.get_object("target").unwrap();
         let current_health = target.get_trait("health")
             .and_then(|t| t.data().as_number())
@@ -73,7 +73,7 @@ impl Action for SetPositionAction {
         "Sets position"
     }
 
-    async fn execute(&self, _context: ActionContext) -> Result<ActionResult, oats_framework::OatsError> {
+    async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult, oats_framework::OatsError> {
         let mut position_data = HashMap::new();
         position_data.insert("x".to_string(), serde_json::json!(10.0));
         position_data.insert("y".to_string(), serde_json::json!(20.0));
@@ -110,7 +110,7 @@ impl System for HealthSystem {
         "Manages health-related operations"
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
@@ -120,7 +120,7 @@ impl System for HealthSystem {
                 let mut context = ActionContext::new();
                 context.add_object("target", object);
                 
-                match heal_action.execute(context).await {
+                match heal_action.execute_scoped(context).await {
                     Ok(result) => {
                         results.push(result);
                         self.stats.actions_executed += 1;
@@ -169,7 +169,7 @@ impl System for PositionSystem {
         "Manages position-related operations"
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
@@ -178,7 +178,7 @@ impl System for PositionSystem {
                 let set_position_action = SetPositionAction;
                 let context = ActionContext::new();
                 
-                match set_position_action.execute(context).await {
+                match set_position_action.execute_scoped(context).await {
                     Ok(result) => {
                         results.push(result);
                         self.stats.actions_executed += 1;
@@ -267,8 +267,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     manager.add_system(Box::new(position_system));
 
     // Register objects
-    manager.register_object(player).await;
-    manager.register_object(enemy).await;
+    manager.register_object(player).await?;
+    manager.register_object(enemy).await?;
 
     println!("   Registered {} systems", manager.systems().len());
     println!("   Registered objects: player_1, enemy_1");
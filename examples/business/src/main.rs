@@ -1,7 +1,19 @@
-use oats_framework::{Object, Trait, TraitData, Action, ActionContext, ActionResult, System, SystemManager, Priority, OatsError};
+use oats_framework::{Object, ObjectChange, ObjectFilter, Trait, TraitData, Action, ActionContext, ActionResult, ConditionalAction, ServiceLocator, System, SystemManager, Priority, OatsError};
 use std::collections::HashMap;
 use async_trait::async_trait;
 
+// Payment backend shared with actions through the manager's service locator
+struct PaymentService {
+    provider: String,
+}
+
+impl PaymentService {
+    fn charge(&self, balance: f64, amount: f64) -> f64 {
+        println!("     💳 Charging ${:.2} via {}", amount, self.provider);
+        balance - amount
+    }
+}
+
 // Custom business actions
 struct ProcessOrderAction {
     order_id: String,
@@ -29,9 +41,24 @@ impl Action for ProcessOrderAction {
         "Processes a customer order"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, OatsError> {
+    fn parameter_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "order_id": { "type": "string", "minLength": 1 },
+                "total_amount": { "type": "number", "exclusiveMinimum": 0 }
+            },
+            "required": ["order_id", "total_amount"]
+        }))
+    }
+
+    async fn execute(&self, mut context: ActionContext<'_>) -> Result<ActionResult, OatsError> {
+        let payments = context
+            .services
+            .get::<PaymentService>()
+            .ok_or_else(|| OatsError::action_failed("Payment service not available"))?;
         let customer = context
-            .get_object("customer")
+            .get_object_mut("customer")
             .ok_or_else(|| OatsError::action_failed("Customer not found"))?;
 
         // Create order trait
@@ -49,12 +76,18 @@ impl Action for ProcessOrderAction {
             .and_then(|t| t.data().as_number())
             .unwrap_or(0.0);
 
-        let new_balance = current_balance - self.total_amount;
+        let new_balance = payments.charge(current_balance, self.total_amount);
         let balance_trait = Trait::new("balance", TraitData::Number(new_balance));
 
+        // Record the order in the customer's history
+        customer.push_trait_array_item("order_history", serde_json::json!(self.order_id.clone()))?;
+
         let mut result = ActionResult::success();
         result.add_trait_update(order_trait);
         result.add_trait_update(balance_trait);
+        if let Some(history_trait) = customer.get_trait("order_history") {
+            result.add_trait_update(history_trait.clone());
+        }
         result.add_message(format!(
             "Processed order {} for ${:.2}. New balance: ${:.2}",
             self.order_id, self.total_amount, new_balance
@@ -65,16 +98,12 @@ impl Action for ProcessOrderAction {
 }
 
 struct UpdateInventoryAction {
-    product_id: String,
     quantity_change: f64,
 }
 
 impl UpdateInventoryAction {
-    fn new(product_id: String, quantity_change: f64) -> Self {
-        Self {
-            product_id,
-            quantity_change,
-        }
+    fn new(quantity_change: f64) -> Self {
+        Self { quantity_change }
     }
 }
 
@@ -88,7 +117,7 @@ impl Action for UpdateInventoryAction {
         "Updates product inventory levels"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, OatsError> {
         let product = context
             .get_object("product")
             .ok_or_else(|| OatsError::action_failed("Product not found"))?;
@@ -105,11 +134,11 @@ impl Action for UpdateInventoryAction {
         result.add_trait_update(stock_trait);
         result.add_message(format!(
             "Updated {} stock: {:.0} -> {:.0}",
-            self.product_id, current_stock, new_stock
+            product.name(), current_stock, new_stock
         ));
 
         if new_stock < 10.0 {
-            result.add_message(format!("⚠️  Low stock alert for {}!", self.product_id));
+            result.add_message(format!("⚠️  Low stock alert for {}!", product.name()));
         }
 
         Ok(result)
@@ -138,7 +167,7 @@ impl Action for ApplyDiscountAction {
         "Applies a discount to product pricing"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, OatsError> {
         let product = context
             .get_object("product")
             .ok_or_else(|| OatsError::action_failed("Product not found"))?;
@@ -168,6 +197,7 @@ struct OrderProcessingSystem {
     name: String,
     description: String,
     stats: oats_framework::systems::SystemStats,
+    order_journal: Vec<String>,
 }
 
 impl OrderProcessingSystem {
@@ -176,6 +206,7 @@ impl OrderProcessingSystem {
             name: "order_processing_system".to_string(),
             description: "Handles customer order processing".to_string(),
             stats: oats_framework::systems::SystemStats::default(),
+            order_journal: Vec::new(),
         }
     }
 }
@@ -190,39 +221,34 @@ impl System for OrderProcessingSystem {
         &self.description
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
         // Process customers with pending orders
         for customer in objects {
-            if customer.has_trait("current_order") {
-                let order_trait = customer.get_trait("current_order").unwrap();
-                if let Some(order_data) = order_trait.data().as_object() {
-                    if let Some(status) = order_data.get("status") {
-                        if status.as_str() == Some("processing") {
-                            // Simulate order processing
-                            let order_action = ProcessOrderAction::new(
-                                "ORD-001".to_string(),
-                                vec!["item1".to_string(), "item2".to_string()],
-                                99.99,
-                            );
-
-                            let mut context = ActionContext::new();
-                            context.add_object("customer", customer);
-
-                            match order_action.execute(context).await {
-                                Ok(result) => {
-                                    results.push(result);
-                                    self.stats.actions_executed += 1;
-                                }
-                                Err(e) => {
-                                    self.stats.errors += 1;
-                                    let error_result = ActionResult::failure(format!("Order processing failed: {}", e));
-                                    results.push(error_result);
-                                }
-                            }
-                        }
+            if customer.get_trait_path("current_order", "status").and_then(|status| status.as_str()) == Some("processing") {
+                // Simulate order processing
+                let order_action = ProcessOrderAction::new(
+                    "ORD-001".to_string(),
+                    vec!["item1".to_string(), "item2".to_string()],
+                    99.99,
+                );
+
+                let customer_name = customer.name().to_string();
+                let mut context = ActionContext::new();
+                context.add_object("customer", customer);
+
+                match order_action.execute_scoped(context).await {
+                    Ok(result) => {
+                        self.order_journal.push(format!("{} -> ORD-001", customer_name));
+                        results.push(result);
+                        self.stats.actions_executed += 1;
+                    }
+                    Err(e) => {
+                        self.stats.errors += 1;
+                        let error_result = ActionResult::failure(format!("Order processing failed: {}", e));
+                        results.push(error_result);
                     }
                 }
             }
@@ -235,6 +261,20 @@ impl System for OrderProcessingSystem {
         Ok(results)
     }
 
+    async fn after_process(&mut self, _results: &[ActionResult], _priority: Priority) -> Result<(), OatsError> {
+        // Flush the order journal once the batch has been processed
+        for entry in self.order_journal.drain(..) {
+            println!("     📒 Journaled order: {}", entry);
+        }
+        Ok(())
+    }
+
+    async fn drain(&mut self) -> Result<(), OatsError> {
+        self.stats.reset();
+        self.order_journal.clear();
+        Ok(())
+    }
+
     fn get_stats(&self) -> oats_framework::systems::SystemStats {
         self.stats.clone()
     }
@@ -244,6 +284,7 @@ struct InventoryManagementSystem {
     name: String,
     description: String,
     stats: oats_framework::systems::SystemStats,
+    restock_action: ConditionalAction,
 }
 
 impl InventoryManagementSystem {
@@ -252,6 +293,12 @@ impl InventoryManagementSystem {
             name: "inventory_management_system".to_string(),
             description: "Manages product inventory levels".to_string(),
             stats: oats_framework::systems::SystemStats::default(),
+            // Restock products with low stock
+            restock_action: ConditionalAction::new(
+                ObjectFilter::trait_number_lt("stock", 20.0),
+                Box::new(UpdateInventoryAction::new(50.0)),
+            )
+            .on_object("product"),
         }
     }
 }
@@ -266,37 +313,24 @@ impl System for InventoryManagementSystem {
         &self.description
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
-        // Process products with low stock
         for product in objects {
-            if product.has_trait("stock") {
-                let stock_trait = product.get_trait("stock").unwrap();
-                if let Some(stock) = stock_trait.data().as_number() {
-                    if stock < 20.0 {
-                        // Restock action
-                        let restock_action = UpdateInventoryAction::new(
-                            product.name().to_string(),
-                            50.0, // Restock amount
-                        );
-
-                        let mut context = ActionContext::new();
-                        context.add_object("product", product);
-
-                        match restock_action.execute(context).await {
-                            Ok(result) => {
-                                results.push(result);
-                                self.stats.actions_executed += 1;
-                            }
-                            Err(e) => {
-                                self.stats.errors += 1;
-                                let error_result = ActionResult::failure(format!("Inventory update failed: {}", e));
-                                results.push(error_result);
-                            }
-                        }
-                    }
+            let mut context = ActionContext::new();
+            context.add_object("product", product);
+
+            match self.restock_action.execute_scoped(context).await {
+                Ok(result) if result.data.get("condition_met") == Some(&serde_json::json!(true)) => {
+                    results.push(result);
+                    self.stats.actions_executed += 1;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    self.stats.errors += 1;
+                    let error_result = ActionResult::failure(format!("Inventory update failed: {}", e));
+                    results.push(error_result);
                 }
             }
             self.stats.objects_processed += 1;
@@ -317,14 +351,22 @@ struct PricingSystem {
     name: String,
     description: String,
     stats: oats_framework::systems::SystemStats,
+    discount_percentage: f64,
 }
 
 impl PricingSystem {
     fn new() -> Self {
+        // Deployments can override the electronics discount via DISCOUNT_PCT
+        let discount_percentage = Trait::from_env("discount_percentage", "DISCOUNT_PCT")
+            .ok()
+            .and_then(|discount| discount.data().as_number())
+            .unwrap_or(10.0);
+
         Self {
             name: "pricing_system".to_string(),
             description: "Manages product pricing and discounts".to_string(),
             stats: oats_framework::systems::SystemStats::default(),
+            discount_percentage,
         }
     }
 }
@@ -339,39 +381,39 @@ impl System for PricingSystem {
         &self.description
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
-        // Apply seasonal discounts to products
-        for product in objects {
-            if product.has_trait("price") && product.has_trait("category") {
-                if let Some(category_trait) = product.get_trait("category") {
-                    if let Some(category) = category_trait.data().as_string() {
-                        if category == "electronics" {
-                            // Apply 10% discount to electronics
-                            let discount_action = ApplyDiscountAction::new(10.0);
-
-                            let mut context = ActionContext::new();
-                            context.add_object("product", product);
-
-                            match discount_action.execute(context).await {
-                                Ok(result) => {
-                                    results.push(result);
-                                    self.stats.actions_executed += 1;
-                                }
-                                Err(e) => {
-                                    self.stats.errors += 1;
-                                    let error_result = ActionResult::failure(format!("Pricing update failed: {}", e));
-                                    results.push(error_result);
-                                }
-                            }
-                        }
-                    }
+        // Apply the configured discount to every electronics product at once; the manager's
+        // concurrency limit caps how many discount actions are in flight
+        let discount_action = ApplyDiscountAction::new(self.discount_percentage);
+        let discounts = objects
+            .iter()
+            .filter(|product| product.has_trait("price"))
+            .filter(|product| {
+                product.get_trait_data("category").and_then(|data| data.as_string()).map(String::as_str) == Some("electronics")
+            })
+            .map(|product| {
+                let mut context = ActionContext::new();
+                context.add_object("product", product);
+                discount_action.execute_scoped(context)
+            });
+
+        for outcome in futures::future::join_all(discounts).await {
+            match outcome {
+                Ok(result) => {
+                    results.push(result);
+                    self.stats.actions_executed += 1;
+                }
+                Err(e) => {
+                    self.stats.errors += 1;
+                    let error_result = ActionResult::failure(format!("Pricing update failed: {}", e));
+                    results.push(error_result);
                 }
             }
-            self.stats.objects_processed += 1;
         }
+        self.stats.objects_processed += objects.len() as u64;
 
         self.stats.total_processing_time_ms += start_time.elapsed().as_millis() as u64;
         self.stats.last_processed = Some(chrono::Utc::now());
@@ -394,10 +436,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Customer
     let mut customer = Object::new("john_doe", "customer");
-    let balance_trait = Trait::new("balance", TraitData::Number(500.0));
+    let mut balance_trait = Trait::with_localization("balance", TraitData::Number(500.0), "en", "Balance");
+    balance_trait.set_localized_name("es", "Saldo");
+    println!(
+        "   Balance trait labels: en=\"{}\", es=\"{}\"",
+        balance_trait.display_name_for("en"),
+        balance_trait.display_name_for("es")
+    );
     let loyalty_trait = Trait::new("loyalty_points", TraitData::Number(150.0));
+    let order_history_trait = Trait::new("order_history", TraitData::Array(Vec::new()));
     customer.add_trait(balance_trait);
     customer.add_trait(loyalty_trait);
+    customer.add_trait(order_history_trait);
 
     // Products
     let mut laptop = Object::new("laptop_pro", "product");
@@ -416,9 +466,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     book.add_trait(book_stock);
     book.add_trait(book_category);
 
-    println!("   Created customer: {} (balance: ${:.2})", customer.name(), 500.0);
-    println!("   Created laptop: {} (price: ${:.2}, stock: {:.0})", laptop.name(), 999.99, 15.0);
-    println!("   Created book: {} (price: ${:.2}, stock: {:.0})", book.name(), 49.99, 45.0);
+    for entity in [&customer, &laptop, &book] {
+        println!("   Created {}", entity);
+        let mut traits: Vec<_> = entity.traits().values().collect();
+        traits.sort_by(|a, b| a.name().cmp(b.name()));
+        for trait_obj in traits {
+            println!("     {}", trait_obj);
+        }
+    }
 
     // Create business systems
     println!("\n2. Creating business systems...");
@@ -434,18 +489,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Set up business operations
     println!("\n3. Setting up business operations...");
     
-    let mut business_ops = SystemManager::new();
+    let services = ServiceLocator::new().with_service(PaymentService { provider: "demo_gateway".to_string() });
+    let mut business_ops = SystemManager::new().with_strict_type_checking(true).with_services(services);
+    business_ops.register_object_type("customer");
+    business_ops.register_object_type("product");
     business_ops.add_system(Box::new(order_system));
     business_ops.add_system(Box::new(inventory_system));
     business_ops.add_system(Box::new(pricing_system));
 
+    // Route each system to the objects it cares about
+    business_ops.assign_system_filter("order_processing_system", ObjectFilter::object_type("customer"));
+    business_ops.assign_system_filter("inventory_management_system", ObjectFilter::object_type("product"));
+
+    // Pricing calls out to an external API, so cap how many discount actions run at once
+    business_ops.set_system_concurrency_limit("pricing_system", 10)?;
+
+    // The notification service only cares about customers
+    let mut customer_notifications = business_ops.subscribe_to_object_type("customer");
+
     // Register entities
-    business_ops.register_object(customer).await;
-    business_ops.register_object(laptop).await;
-    business_ops.register_object(book).await;
+    let book_id = book.id().to_string();
+    business_ops.register_object(customer).await?;
+    business_ops.register_object(laptop).await?;
+    business_ops.register_object(book).await?;
 
     println!("   Registered {} systems", business_ops.systems().len());
     println!("   Registered {} entities", 3);
+    while let Ok(change) = customer_notifications.try_recv() {
+        let kind = match change {
+            ObjectChange::Added(_) => "added",
+            ObjectChange::Updated(_) => "updated",
+            ObjectChange::Removed(_) => "removed",
+        };
+        println!("   📣 Customer notification: {} {}", change.object().name(), kind);
+    }
 
     // Simulate business operations
     println!("\n4. Simulating business operations...");
@@ -453,7 +530,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     for day in 1..=3 {
         println!("\n   --- Business Day {} ---", day);
         
-        let results = business_ops.process_all(Priority::Normal).await?;
+        // The first day processes every entity; later days only the ones changed since the last run
+        let results = if day == 1 {
+            business_ops.process_all_parallel(Priority::Normal).await?
+        } else {
+            let since = business_ops.last_process_time().expect("day one has been processed");
+            let changed = business_ops.get_objects_updated_since(since).await;
+            println!("     Reprocessing {} changed entities", changed.len());
+            business_ops.process_since_last(Priority::Normal).await?
+        };
         
         for result in results {
             if result.is_success() {
@@ -487,12 +572,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        // An overnight delivery restocks the book, so only it is reprocessed the next day
+        let mut delivery = ActionResult::success();
+        delivery.add_trait_update(Trait::new("stock", TraitData::Number(45.0 + 10.0 * day as f64)));
+        business_ops.apply_action_result(&book_id, &delivery).await?;
+
         tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
     }
 
     // Business analytics
     println!("\n5. Business analytics:");
     
+    for line in business_ops.object_stats().await.to_report().lines() {
+        println!("   {}", line);
+    }
+
     let stats = business_ops.get_all_stats();
     for (system_name, stat) in stats {
         println!("   {}:", system_name);
@@ -500,6 +594,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("     Actions executed: {}", stat.actions_executed);
         println!("     Errors: {}", stat.errors);
         println!("     Total processing time: {}ms", stat.total_processing_time_ms);
+        println!("     Success rate: {:.1}%", stat.success_rate());
+        println!("     Actions per object: {:.2}", stat.actions_per_object());
+        println!("     Efficiency score: {:.2}", stat.efficiency_score());
     }
 
     println!("\n💼 Business simulation completed!");
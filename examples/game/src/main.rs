@@ -1,20 +1,35 @@
-use oats_framework::{Object, Trait, TraitData, Action, ActionContext, ActionResult, System, SystemManager, Priority};
+use oats_framework::{BoolExpr, Object, ObjectFilter, ObjectSchema, TraitSchema, Trait, TraitData, Action, ActionContext, ActionResult, System, SystemManager, Priority};
 use std::collections::HashMap;
+use oats_framework::objects::{filter_objects_by_tag, tag_objects};
+use oats_framework::systems::ObjectRegistry;
+use oats_framework::SpatialIndex2D;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use rand::Rng;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 
-// Custom game actions
-struct CombatAction {
-    damage: f64,
+// Typed view of any object with health
+struct Character {
+    name: String,
+    health: f64,
 }
 
-impl CombatAction {
-    fn new(damage: f64) -> Self {
-        Self { damage }
+impl TryFrom<&Object> for Character {
+    type Error = oats_framework::OatsError;
+
+    fn try_from(object: &Object) -> Result<Self, Self::Error> {
+        let health = object
+            .get_trait_data("health")
+            .and_then(|d| d.as_number())
+            .ok_or_else(|| oats_framework::OatsError::trait_not_found("health"))?;
+        Ok(Character { name: object.name().to_string(), health })
     }
 }
 
+// Custom game actions; rolled values travel as context parameters so journal replay reproduces them
+struct CombatAction;
+
 #[async_trait::async_trait]
 impl Action for CombatAction {
     fn name(&self) -> &str {
@@ -25,28 +40,35 @@ impl Action for CombatAction {
         "Deals damage to target"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, oats_framework::OatsError> {
+    fn required_objects(&self) -> Vec<String> {
+        vec!["target".to_string()]
+    }
+
+    fn required_traits(&self) -> Vec<String> {
+        vec!["health".to_string()]
+    }
+
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, oats_framework::OatsError> {
         let target = context
             .get_object("target")
             .ok_or_else(|| oats_framework::OatsError::action_failed("Target not found"))?;
-
-        let health_trait = target
-            .get_trait("health")
+        let damage = context
+            .get_parameter("damage")
+            .and_then(|value| value.as_f64())
+            .ok_or_else(|| oats_framework::OatsError::action_failed("Damage not given"))?;
+
+        let current_health = target
+            .get_trait_data("health")
+            .and_then(|data| data.as_number())
             .ok_or_else(|| oats_framework::OatsError::trait_not_found("health"))?;
-
-        let current_health = health_trait
-            .data()
-            .as_number()
-            .ok_or_else(|| oats_framework::OatsError::action_failed("Health trait is not numeric"))?;
-
-        let new_health = (current_health - self.damage).max(0.0);
-        let new_health_trait = Trait::new("health", TraitData::Number(new_health));
+        let new_health = (current_health - damage).max(0.0);
 
         let mut result = ActionResult::success();
-        result.add_trait_update(new_health_trait);
+        result.set_target(target.id());
+        result.add_trait_update(Trait::new("health", TraitData::Number(new_health)));
         result.add_message(format!(
             "Dealt {:.1} damage to {}. Health: {:.1} -> {:.1}",
-            self.damage, target.name(), current_health, new_health
+            damage, target.name(), current_health, new_health
         ));
 
         if new_health <= 0.0 {
@@ -57,16 +79,7 @@ impl Action for CombatAction {
     }
 }
 
-struct MovementAction {
-    new_x: f64,
-    new_y: f64,
-}
-
-impl MovementAction {
-    fn new(x: f64, y: f64) -> Self {
-        Self { new_x: x, new_y: y }
-    }
-}
+struct MovementAction;
 
 #[async_trait::async_trait]
 impl Action for MovementAction {
@@ -78,22 +91,32 @@ impl Action for MovementAction {
         "Moves character to new position"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, oats_framework::OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, oats_framework::OatsError> {
         let target = context
             .get_object("target")
             .ok_or_else(|| oats_framework::OatsError::action_failed("Target not found"))?;
-
-        let mut position_data = HashMap::new();
-        position_data.insert("x".to_string(), serde_json::json!(self.new_x));
-        position_data.insert("y".to_string(), serde_json::json!(self.new_y));
-
-        let new_position_trait = Trait::new("position", TraitData::Object(position_data));
+        let coordinate = |name: &str| {
+            context
+                .get_parameter(name)
+                .and_then(|value| value.as_f64())
+                .ok_or_else(|| oats_framework::OatsError::action_failed(format!("Coordinate {} not given", name)))
+        };
+        let (new_x, new_y) = (coordinate("x")?, coordinate("y")?);
+
+        // Merge the new coordinates into the existing position, keeping any other fields
+        let mut moved = target.clone();
+        moved.update_trait_fields("position", HashMap::from([
+            ("x".to_string(), serde_json::json!(new_x)),
+            ("y".to_string(), serde_json::json!(new_y)),
+        ]))?;
+        let new_position_trait = moved.get_trait("position").cloned().expect("position was just updated");
 
         let mut result = ActionResult::success();
+        result.set_target(target.id());
         result.add_trait_update(new_position_trait);
         result.add_message(format!(
             "{} moved to position ({:.1}, {:.1})",
-            target.name(), self.new_x, self.new_y
+            target.name(), new_x, new_y
         ));
 
         Ok(result)
@@ -105,14 +128,29 @@ struct CombatSystem {
     name: String,
     description: String,
     stats: oats_framework::systems::SystemStats,
+    registry: ObjectRegistry,
+    spatial_index: Arc<RwLock<SpatialIndex2D>>,
 }
 
+/// Characters closer than this can attack each other
+const ATTACK_RANGE: f64 = 3.0;
+
 impl CombatSystem {
-    fn new() -> Self {
+    fn new(registry: ObjectRegistry, spatial_index: Arc<RwLock<SpatialIndex2D>>) -> Self {
         Self {
             name: "combat_system".to_string(),
             description: "Handles combat between characters".to_string(),
             stats: oats_framework::systems::SystemStats::default(),
+            registry,
+            spatial_index,
+        }
+    }
+
+    /// Soft-delete a defeated character so it stays in the registry for auditing
+    async fn mark_defeated(&self, object_id: &str) {
+        let mut registry = self.registry.write().await;
+        if let Some(character) = registry.get_mut(object_id) {
+            character.soft_delete();
         }
     }
 }
@@ -127,35 +165,58 @@ impl System for CombatSystem {
         &self.description
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
         // Find all characters with health and position traits
+        let combatants = ObjectFilter::has_trait("health") & ObjectFilter::has_trait("position");
         let characters: Vec<_> = objects
-            .into_iter()
-            .filter(|obj| obj.has_trait("health") && obj.has_trait("position"))
+            .iter()
+            .filter(|obj| combatants.matches(obj))
             .collect();
 
+        // Look up who is within attack range of each character
+        let neighbors: Vec<Vec<_>> = {
+            let index = self.spatial_index.read().await;
+            characters
+                .iter()
+                .map(|character| index.nearest_neighbors(character.id(), ATTACK_RANGE))
+                .collect()
+        };
+
         // Simple combat logic: characters attack each other if they're close
         for i in 0..characters.len() {
             for j in (i + 1)..characters.len() {
-                let _char1 = &characters[i];
-                let char2 = &characters[j];
+                let char1 = characters[i];
+                let char2 = characters[j];
 
-                // Check if characters are close enough to fight (simplified)
                 let mut rng = StdRng::from_entropy();
-                let distance = rng.gen_range(0.0..10.0); // Random distance for demo
                 let damage = rng.gen_range(5.0..15.0);
-                
-                if distance < 3.0 {
-                    let combat_action = CombatAction::new(damage);
 
-                    let mut context = ActionContext::new();
-                    context.add_object("target", char2.clone());
+                if neighbors[i].contains(&char2.id()) {
+                    let mut context = ActionContext::from_refs(HashMap::from([
+                        ("attacker".to_string(), char1),
+                        ("target".to_string(), char2),
+                    ]));
+                    context.add_parameter("damage", serde_json::json!(damage));
+
+                    // Validation failures count as errors but not as executed actions
+                    if let Err(e) = CombatAction.validate_context(&context).await {
+                        self.stats.errors += 1;
+                        results.push(ActionResult::failure(format!("Combat skipped: {}", e)));
+                        continue;
+                    }
 
-                    match combat_action.execute(context).await {
+                    match CombatAction.execute_scoped(context).await {
                         Ok(result) => {
+                            let defeated = result
+                                .trait_updates
+                                .iter()
+                                .any(|t| t.name() == "health" && t.data().as_number() == Some(0.0));
+                            if defeated {
+                                self.mark_defeated(&char2.id.to_string()).await;
+                            }
                             results.push(result);
                             self.stats.actions_executed += 1;
                         }
@@ -207,22 +268,23 @@ impl System for MovementSystem {
         &self.description
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
         for object in objects {
-            if object.has_trait("position") {
+            if object.evaluate_trait_condition(&BoolExpr::flag("active")) && object.has_trait("position") {
                 // Random movement for demo
                 let mut rng = StdRng::from_entropy();
                 let new_x = rng.gen_range(-10.0..10.0);
                 let new_y = rng.gen_range(-10.0..10.0);
 
-                let movement_action = MovementAction::new(new_x, new_y);
                 let mut context = ActionContext::new();
                 context.add_object("target", object);
+                context.add_parameter("x", serde_json::json!(new_x));
+                context.add_parameter("y", serde_json::json!(new_y));
 
-                match movement_action.execute(context).await {
+                match MovementAction.execute_scoped(context).await {
                     Ok(result) => {
                         results.push(result);
                         self.stats.actions_executed += 1;
@@ -264,15 +326,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let position_trait = Trait::new("position", TraitData::Object(position_data));
     player.add_trait(health_trait);
     player.add_trait(position_trait);
-
-    let mut enemy1 = Object::new("goblin", "enemy");
-    let enemy_health = Trait::new("health", TraitData::Number(30.0));
-    let mut enemy_pos_data = HashMap::new();
-    enemy_pos_data.insert("x".to_string(), serde_json::json!(2.0));
-    enemy_pos_data.insert("y".to_string(), serde_json::json!(2.0));
-    let enemy_position = Trait::new("position", TraitData::Object(enemy_pos_data));
-    enemy1.add_trait(enemy_health);
-    enemy1.add_trait(enemy_position);
+    player.add_trait(Trait::new("active", TraitData::Boolean(true)));
+
+    let mut goblin = Object::new("goblin", "enemy");
+    let goblin_health = Trait::new("health", TraitData::Number(30.0));
+    let goblin_damage = Trait::new("damage", TraitData::Number(5.0));
+    let mut goblin_pos_data = HashMap::new();
+    goblin_pos_data.insert("x".to_string(), serde_json::json!(2.0));
+    goblin_pos_data.insert("y".to_string(), serde_json::json!(2.0));
+    let goblin_position = Trait::new("position", TraitData::Object(goblin_pos_data));
+    goblin.add_trait(goblin_health);
+    goblin.add_trait(goblin_damage);
+    goblin.add_trait(goblin_position);
+    goblin.add_trait(Trait::new("active", TraitData::Boolean(true)));
+
+    // Spawn a pack of goblins sharing the same starting traits
+    let goblin_template = goblin.as_template();
+    let mut goblins = goblin_template.instantiate_many(&["goblin_1", "goblin_2", "goblin_3", "goblin_4", "goblin_5"]);
+    tag_objects(&mut goblins, "aggressive");
 
     let mut enemy2 = Object::new("orc", "enemy");
     let orc_health = Trait::new("health", TraitData::Number(60.0));
@@ -282,15 +353,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let orc_position = Trait::new("position", TraitData::Object(orc_pos_data));
     enemy2.add_trait(orc_health);
     enemy2.add_trait(orc_position);
-
-    println!("   Created hero: {} (health: 100)", player.name());
-    println!("   Created goblin: {} (health: 30)", enemy1.name());
-    println!("   Created orc: {} (health: 60)", enemy2.name());
+    enemy2.add_trait(Trait::new("active", TraitData::Boolean(true)));
+
+    println!("   Created {}", player);
+    println!("   Spawned {} goblins from template, e.g. {}", goblins.len(), goblins[0]);
+    println!("   {} goblins are aggressive", filter_objects_by_tag(&goblins, "aggressive").count());
+    println!("   Created {}", enemy2);
+
+    // Every character needs bounded health and a position
+    let character_schema = ObjectSchema::builder()
+        .with_trait("health", TraitSchema::number().with_range(0.0, 1000.0))
+        .with_trait("position", TraitSchema::object())
+        .build();
+    for character in std::iter::once(&player).chain(&goblins).chain(std::iter::once(&enemy2)) {
+        for violation in character.validate_with_schema(&character_schema) {
+            println!("   ❌ {} failed validation: {}", character.name(), violation);
+        }
+    }
+    println!("   Validated {} characters against the character schema", goblins.len() + 2);
 
     // Create game systems
     println!("\n2. Creating game systems...");
     
-    let combat_system = CombatSystem::new();
+    let mut game_world = SystemManager::new().with_spatial_index().with_strict_type_checking(true);
+    game_world.register_object_type("player");
+    game_world.register_object_type("enemy");
+    let spatial_index = game_world.spatial_index().expect("spatial index is enabled");
+    let combat_system = CombatSystem::new(game_world.registry(), spatial_index);
     let movement_system = MovementSystem::new();
 
     println!("   Created combat system: {}", combat_system.name());
@@ -299,17 +388,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Set up system manager
     println!("\n3. Setting up game world...");
     
-    let mut game_world = SystemManager::new();
     game_world.add_system(Box::new(combat_system));
     game_world.add_system(Box::new(movement_system));
 
+    // Clear out defeated characters at the end of each round
+    let defeated = ObjectFilter::has_trait("health") & !ObjectFilter::trait_number_gt("health", 0.0);
+    let cleanup_system = game_world
+        .garbage_collection_system("cleanup_system", std::time::Duration::ZERO, defeated)
+        .with_hard_delete(true);
+    game_world.add_system(Box::new(cleanup_system));
+
     // Register characters
-    game_world.register_object(player).await;
-    game_world.register_object(enemy1).await;
-    game_world.register_object(enemy2).await;
+    game_world.register_object(player).await?;
+    for goblin in goblins {
+        game_world.register_object(goblin).await?;
+    }
+    game_world.register_object(enemy2).await?;
 
     println!("   Registered {} systems", game_world.systems().len());
-    println!("   Registered {} characters", 3);
+    println!("   Registered {} characters", game_world.object_count().await);
 
     // Simulate game rounds
     println!("\n4. Simulating game rounds...");
@@ -329,10 +426,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("     ❌ {}", message);
                 }
             }
+
+            // Persist health and position changes into the game world
+            if let (true, Some(target_id)) = (result.is_success(), result.target_id) {
+                game_world.apply_action_result(&target_id.to_string(), &result).await?;
+            }
         }
 
-        // Check character status
-        let all_objects = game_world.get_all_objects().await;
+        // Check character status, healthiest first
+        let mut all_objects = game_world.get_all_objects().await;
+        Object::sort_by_trait(&mut all_objects, "health", false);
         for obj in all_objects {
             if let Some(health_trait) = obj.get_trait("health") {
                 if let Some(health) = health_trait.data().as_number() {
@@ -347,6 +450,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Final statistics
     println!("\n5. Game statistics:");
     
+    for line in game_world.object_stats().await.to_report().lines() {
+        println!("   {}", line);
+    }
+
     let stats = game_world.get_all_stats();
     for (system_name, stat) in stats {
         println!("   {}:", system_name);
@@ -354,8 +461,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("     Actions executed: {}", stat.actions_executed);
         println!("     Errors: {}", stat.errors);
         println!("     Total processing time: {}ms", stat.total_processing_time_ms);
+        if stat.gc_collected > 0 {
+            println!("     Objects collected: {}", stat.gc_collected);
+        }
     }
 
+    let characters = game_world.get_all_as::<Character>().await;
+    let standing: Vec<String> = characters.iter().filter(|c| c.health > 0.0).map(|c| c.name.clone()).collect();
+    println!("   Characters standing: {} of {} ({})", standing.len(), characters.count(), standing.join(", "));
+
     println!("\n🎉 Game simulation completed!");
     Ok(())
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character(name: &str, health: f64, x: f64, y: f64) -> Object {
+        let mut character = Object::new(name, "player");
+        character.add_trait(Trait::new("health", TraitData::Number(health)));
+        character.add_trait(Trait::new("position", TraitData::Object(HashMap::from([
+            ("x".to_string(), serde_json::json!(x)),
+            ("y".to_string(), serde_json::json!(y)),
+        ]))));
+        character.add_trait(Trait::new("active", TraitData::Boolean(true)));
+        character
+    }
+
+    #[tokio::test]
+    async fn test_journal_replays_game_rounds() {
+        let mut game_world = SystemManager::new().with_spatial_index();
+        let spatial_index = game_world.spatial_index().expect("spatial index is enabled");
+        game_world.add_system(Box::new(CombatSystem::new(game_world.registry(), spatial_index)));
+        game_world.add_system(Box::new(MovementSystem::new()));
+        let hero = character("hero", 1000.0, 0.0, 0.0);
+        let orc = character("orc", 1000.0, 1.0, 1.0);
+        game_world.register_object(hero).await.unwrap();
+        game_world.register_object(orc).await.unwrap();
+
+        game_world.journal_mode(true);
+        let results = game_world.process_all(Priority::Normal).await.unwrap();
+        for result in &results {
+            if let Some(target_id) = result.target_id {
+                game_world.apply_action_result(&target_id.to_string(), result).await.unwrap();
+            }
+        }
+
+        let journal = game_world.export_journal();
+        assert_eq!(journal.len(), results.len());
+        let combat = journal.iter().position(|entry| entry.action_name == "combat").expect("characters are in range");
+        assert_eq!(journal.iter().filter(|entry| entry.action_name == "movement").count(), 2);
+
+        let mut replayer = SystemManager::new();
+        replayer.register_replay_action(Box::new(CombatAction));
+        replayer.register_replay_action(Box::new(MovementAction));
+        let replayed = replayer.replay(&journal).await.unwrap();
+        for (entry, result) in journal.iter().zip(&replayed) {
+            assert!(result.is_success());
+            for (recorded, replayed) in entry.result.trait_updates.iter().zip(&result.trait_updates) {
+                assert_eq!(recorded.data(), replayed.data());
+            }
+        }
+
+        let target_id = journal[combat].result.target_id.unwrap().to_string();
+        let target = game_world.get_object(&target_id).await.unwrap();
+        let health = target.get_trait_data("health").and_then(|data| data.as_number()).unwrap();
+        assert!(health < 1000.0);
+        assert_eq!(replayed[combat].trait_updates[0].data().as_number(), Some(health));
+    }
+}
@@ -0,0 +1,110 @@
+//! Procedural macros for the OATS framework
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Generate a strongly-typed view over an `Object`, reading one trait per named field
+///
+/// Each field is read from and written to the trait with the same name, converted
+/// through `oats_framework::TraitValue`. The annotated struct gains `from_object`,
+/// `to_traits` and `update_object`, plus `TryFrom<&Object>` and `TryFrom<Object>`.
+#[proc_macro_attribute]
+pub fn oats_object(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        let args = proc_macro2::TokenStream::from(args);
+        return syn::Error::new_spanned(args, "#[oats_object] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_oats_object(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_oats_object(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "#[oats_object] requires a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[oats_object] can only be applied to structs",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let type_name = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let idents: Vec<_> = fields.iter().filter_map(|f| f.ident.as_ref()).collect();
+    let trait_names: Vec<_> = idents.iter().map(|ident| ident.to_string()).collect();
+
+    Ok(quote! {
+        #input
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Read every field from the object's traits, returning `None` if any is missing or mistyped
+            pub fn from_object(object: &::oats_framework::Object) -> ::std::option::Option<Self> {
+                ::std::option::Option::Some(Self {
+                    #(
+                        #idents: ::oats_framework::TraitValue::from_trait_data(
+                            object.get_trait_data(#trait_names)?,
+                        )?,
+                    )*
+                })
+            }
+
+            /// Convert every field into a trait named after it, failing on NaN or infinite numbers
+            pub fn to_traits(&self) -> ::oats_framework::Result<::std::vec::Vec<::oats_framework::Trait>> {
+                ::std::result::Result::Ok(::std::vec![
+                    #(
+                        ::oats_framework::Trait::try_new(
+                            #trait_names,
+                            ::oats_framework::TraitValue::to_trait_data(&self.#idents),
+                        )?,
+                    )*
+                ])
+            }
+
+            /// Write every field into the object, replacing traits with the same names
+            ///
+            /// The object is left unchanged if any field cannot be converted.
+            pub fn update_object(&self, object: &mut ::oats_framework::Object) -> ::oats_framework::Result<()> {
+                object.add_traits(self.to_traits()?);
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<&::oats_framework::Object> for #name #ty_generics #where_clause {
+            type Error = ::oats_framework::OatsError;
+
+            fn try_from(object: &::oats_framework::Object) -> ::std::result::Result<Self, Self::Error> {
+                Self::from_object(object).ok_or_else(|| {
+                    ::oats_framework::OatsError::validation_error(::std::format!(
+                        "Object '{}' does not have the traits of {}",
+                        object.name(),
+                        #type_name
+                    ))
+                })
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<::oats_framework::Object> for #name #ty_generics #where_clause {
+            type Error = ::oats_framework::OatsError;
+
+            fn try_from(object: ::oats_framework::Object) -> ::std::result::Result<Self, Self::Error> {
+                Self::try_from(&object)
+            }
+        }
+    })
+}
@@ -1,23 +1,262 @@
 use async_trait::async_trait;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use crate::{Result, Object, Trait};
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::Arc;
+use crate::{Result, Object, ObjectFilter, Trait};
+use crate::objects::ObjectId;
 
 /// Action identifier
 pub type ActionId = uuid::Uuid;
 
+tokio::task_local! {
+    /// Task ID of the `SystemManager` processing run currently executing
+    static PROCESS_TASK_ID: String;
+
+    /// Name of the action currently executing through `Action::execute_scoped`
+    static CURRENT_ACTION_NAME: String;
+
+    /// Name of the system currently processing objects
+    static CURRENT_SYSTEM_NAME: String;
+
+    /// Services of the `SystemManager` processing run currently executing
+    static PROCESS_SERVICES: ServiceLocator;
+
+    /// Per-system action concurrency limits of the processing run currently executing
+    static CONCURRENCY_LIMITS: ConcurrencyLimits;
+
+    /// Set while an outer `Action::execute_scoped` holds the dispatch permit and journal slot
+    static IN_DISPATCH: ();
+}
+
+/// Semaphores limiting concurrent action dispatches, keyed by system name
+pub(crate) type ConcurrencyLimits = std::sync::Arc<HashMap<String, std::sync::Arc<tokio::sync::Semaphore>>>;
+
+/// Run `f` with `limits` applied to every `execute_scoped` call inside it
+pub(crate) async fn with_concurrency_limits<F: std::future::Future>(limits: ConcurrencyLimits, f: F) -> F::Output {
+    CONCURRENCY_LIMITS.scope(limits, f).await
+}
+
+/// Wait for a permit from the current system's concurrency limit, if it has one
+async fn acquire_dispatch_permit() -> Result<Option<tokio::sync::OwnedSemaphorePermit>> {
+    let Some(limiter) = CONCURRENCY_LIMITS
+        .try_with(|limits| current_system_name().and_then(|name| limits.get(&name).cloned()))
+        .ok()
+        .flatten()
+    else {
+        return Ok(None);
+    };
+    Ok(Some(limiter.acquire_owned().await?))
+}
+
+#[cfg(feature = "snapshot")]
+tokio::task_local! {
+    /// Journal recording actions executed through `Action::execute_scoped`
+    static ACTION_JOURNAL: ActionJournal;
+}
+
+/// Shared buffer of recorded action executions
+#[cfg(feature = "snapshot")]
+pub(crate) type ActionJournal = std::sync::Arc<std::sync::Mutex<JournalBuffer>>;
+
+/// Journal entries a `SystemManager` keeps unless configured otherwise
+#[cfg(feature = "snapshot")]
+pub const DEFAULT_JOURNAL_CAPACITY: usize = 10_000;
+
+/// Recorded action executions, keeping only the newest `capacity` entries
+#[cfg(feature = "snapshot")]
+#[derive(Debug)]
+pub(crate) struct JournalBuffer {
+    pub(crate) entries: std::collections::VecDeque<JournalEntry>,
+    capacity: usize,
+}
+
+#[cfg(feature = "snapshot")]
+impl Default for JournalBuffer {
+    fn default() -> Self {
+        Self { entries: std::collections::VecDeque::new(), capacity: DEFAULT_JOURNAL_CAPACITY }
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl JournalBuffer {
+    /// Change the capacity, dropping the oldest entries past it
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        let excess = self.entries.len().saturating_sub(capacity);
+        self.entries.drain(..excess);
+    }
+
+    /// Record an entry, dropping the oldest one when full
+    fn push(&mut self, entry: JournalEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+/// Run `f` with every `execute_scoped` call inside it recorded in `journal`
+#[cfg(feature = "snapshot")]
+pub(crate) async fn with_action_journal<F: std::future::Future>(journal: ActionJournal, f: F) -> F::Output {
+    ACTION_JOURNAL.scope(journal, f).await
+}
+
+/// Run `f` with `task_id` recorded as the processing task for new contexts
+pub(crate) async fn with_process_task_id<F: std::future::Future>(task_id: String, f: F) -> F::Output {
+    PROCESS_TASK_ID.scope(task_id, f).await
+}
+
+/// Get the task ID of the processing run this code executes in, if any
+fn current_task_id() -> Option<String> {
+    PROCESS_TASK_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `f` with `services` given to every context created inside it
+pub(crate) async fn with_process_services<F: std::future::Future>(services: ServiceLocator, f: F) -> F::Output {
+    PROCESS_SERVICES.scope(services, f).await
+}
+
+/// Get the services of the processing run this code executes in, or none
+fn current_services() -> ServiceLocator {
+    PROCESS_SERVICES.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Run `f` with `name` recorded as the system currently processing objects
+pub(crate) async fn with_current_system_name<F: std::future::Future>(name: String, f: F) -> F::Output {
+    CURRENT_SYSTEM_NAME.scope(name, f).await
+}
+
+/// Run `f` synchronously with the services and task ID of a processing run in scope
+#[cfg(feature = "work_stealing")]
+pub(crate) fn with_process_scope_sync<R>(services: ServiceLocator, task_id: String, f: impl FnOnce() -> R) -> R {
+    PROCESS_TASK_ID.sync_scope(task_id, || PROCESS_SERVICES.sync_scope(services, f))
+}
+
+/// Run `f` synchronously with `name` recorded as the system currently processing objects
+#[cfg(feature = "work_stealing")]
+pub(crate) fn with_current_system_name_sync<R>(name: String, f: impl FnOnce() -> R) -> R {
+    CURRENT_SYSTEM_NAME.sync_scope(name, f)
+}
+
+/// Get the name of the action this code executes in, if any
+pub fn current_action_name() -> Option<String> {
+    CURRENT_ACTION_NAME.try_with(Clone::clone).ok()
+}
+
+/// Get the name of the system this code executes in, if any
+pub fn current_system_name() -> Option<String> {
+    CURRENT_SYSTEM_NAME.try_with(Clone::clone).ok()
+}
+
+/// An object in an `ActionContext`, borrowed from its owner until first mutated
+///
+/// Reads go through `Deref`; `DerefMut` and `to_mut` clone a borrowed object once and
+/// keep the copy, so cloning a context of borrowed objects never copies trait data.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum CowObject<'a> {
+    /// Object still shared with its owner, e.g. the `SystemManager` registry
+    Borrowed(&'a Object),
+    /// Object owned by the context
+    Owned(Object),
+}
+
+impl<'a> CowObject<'a> {
+    /// Get a mutable reference, cloning a borrowed object first
+    pub fn to_mut(&mut self) -> &mut Object {
+        if let CowObject::Borrowed(object) = *self {
+            *self = CowObject::Owned(object.clone());
+        }
+        match self {
+            CowObject::Owned(object) => object,
+            CowObject::Borrowed(_) => unreachable!("borrowed object was just cloned"),
+        }
+    }
+
+    /// Take the object, cloning it if it is borrowed
+    pub fn into_owned(self) -> Object {
+        match self {
+            CowObject::Borrowed(object) => object.clone(),
+            CowObject::Owned(object) => object,
+        }
+    }
+
+    /// Check whether the object is still borrowed
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, CowObject::Borrowed(_))
+    }
+}
+
+impl Deref for CowObject<'_> {
+    type Target = Object;
+
+    fn deref(&self) -> &Object {
+        match self {
+            CowObject::Borrowed(object) => object,
+            CowObject::Owned(object) => object,
+        }
+    }
+}
+
+impl DerefMut for CowObject<'_> {
+    fn deref_mut(&mut self) -> &mut Object {
+        self.to_mut()
+    }
+}
+
+impl From<Object> for CowObject<'_> {
+    fn from(object: Object) -> Self {
+        CowObject::Owned(object)
+    }
+}
+
+impl<'a> From<&'a Object> for CowObject<'a> {
+    fn from(object: &'a Object) -> Self {
+        CowObject::Borrowed(object)
+    }
+}
+
+impl Serialize for CowObject<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Object::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CowObject<'_> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Object::deserialize(deserializer).map(CowObject::Owned)
+    }
+}
+
 /// Context passed to actions containing relevant objects and traits
+///
+/// Objects may be borrowed for `'a` (see `from_refs`), so building and cloning contexts
+/// over registry objects copies no trait data until an action mutates an object.
 #[derive(Debug, Clone)]
-pub struct ActionContext {
+pub struct ActionContext<'a> {
     /// Objects relevant to this action
-    pub objects: HashMap<String, Object>,
+    pub objects: HashMap<String, CowObject<'a>>,
     /// Additional parameters for the action
     pub parameters: HashMap<String, serde_json::Value>,
     /// Metadata about the action execution
     pub metadata: HashMap<String, String>,
+    /// ID of the `SystemManager` run that created this context, for correlation
+    ///
+    /// This is the tokio task ID when the run executes in a spawned task, or an ID generated
+    /// for the run otherwise.
+    pub task_id: Option<String>,
+    /// Services available to actions, taken from `SystemManager::with_services` during processing
+    pub services: ServiceLocator,
 }
 
-impl ActionContext {
+impl<'a> ActionContext<'a> {
     /// Create a new action context
     #[inline]
     pub fn new() -> Self {
@@ -25,6 +264,8 @@ impl ActionContext {
             objects: HashMap::new(),
             parameters: HashMap::new(),
             metadata: HashMap::new(),
+            task_id: current_task_id(),
+            services: current_services(),
         }
     }
 
@@ -35,26 +276,131 @@ impl ActionContext {
             objects: HashMap::with_capacity(expected_objects),
             parameters: HashMap::with_capacity(expected_parameters),
             metadata: HashMap::new(),
+            task_id: current_task_id(),
+            services: current_services(),
         }
     }
 
-    /// Add an object to the context
+    /// Create a context from a map of named objects
+    pub fn from_objects(objects: HashMap<String, Object>) -> Self {
+        Self::from_cow_objects(objects.into_iter().map(|(name, object)| (name, object.into())).collect())
+    }
+
+    /// Create a context borrowing a map of named objects, cloning none of them
+    pub fn from_refs(objects: HashMap<String, &'a Object>) -> Self {
+        Self::from_cow_objects(objects.into_iter().map(|(name, object)| (name, object.into())).collect())
+    }
+
+    /// Create a context from already wrapped objects
+    fn from_cow_objects(objects: HashMap<String, CowObject<'a>>) -> Self {
+        Self {
+            objects,
+            parameters: HashMap::new(),
+            metadata: HashMap::new(),
+            task_id: current_task_id(),
+            services: current_services(),
+        }
+    }
+
+    /// Create a context from `(name, object)` pairs
+    pub fn from_object_pairs<K: Into<String>>(pairs: impl IntoIterator<Item = (K, Object)>) -> Self {
+        Self::from_objects(pairs.into_iter().map(|(name, object)| (name.into(), object)).collect())
+    }
+
+    /// Merge additional named objects into the context
+    pub fn extend_objects(&mut self, objects: HashMap<String, Object>) -> &mut Self {
+        self.objects.extend(objects.into_iter().map(|(name, object)| (name, object.into())));
+        self
+    }
+
+    /// Get a copy of this context without the named object
+    pub fn without_object(&self, name: &str) -> ActionContext<'a> {
+        self.filtered(|key| key != name)
+    }
+
+    /// Get a copy of this context containing only the listed objects
+    pub fn only_objects(&self, names: &[&str]) -> ActionContext<'a> {
+        self.filtered(|key| names.contains(&key))
+    }
+
+    /// Get a copy of this context with an object moved to a new name
+    ///
+    /// An object already stored under `new_name` is replaced.
+    pub fn renamed_object(&self, old_name: &str, new_name: &str) -> ActionContext<'a> {
+        let mut context = self.clone();
+        if let Some(object) = context.objects.remove(old_name) {
+            context.objects.insert(new_name.to_string(), object);
+        }
+        context
+    }
+
+    /// Copy parameters and metadata, keeping only the objects whose name passes `keep`
+    fn filtered(&self, keep: impl Fn(&str) -> bool) -> ActionContext<'a> {
+        ActionContext {
+            objects: self
+                .objects
+                .iter()
+                .filter(|(name, _)| keep(name))
+                .map(|(name, object)| (name.clone(), object.clone()))
+                .collect(),
+            parameters: self.parameters.clone(),
+            metadata: self.metadata.clone(),
+            task_id: self.task_id.clone(),
+            services: self.services.clone(),
+        }
+    }
+
+    /// Clone this context and add `object`, owned or borrowed, as `"target"`
+    pub fn for_target<'b>(&self, object: impl Into<CowObject<'b>>) -> ActionContext<'b>
+    where
+        'a: 'b,
+    {
+        let mut context: ActionContext<'b> = self.clone();
+        context.add_object("target", object);
+        context
+    }
+
+    /// Take ownership of every borrowed object so the context can outlive its sources
+    pub fn into_owned(self) -> ActionContext<'static> {
+        ActionContext {
+            objects: self
+                .objects
+                .into_iter()
+                .map(|(name, object)| (name, CowObject::Owned(object.into_owned())))
+                .collect(),
+            parameters: self.parameters,
+            metadata: self.metadata,
+            task_id: self.task_id,
+            services: self.services,
+        }
+    }
+
+    /// Add an object, owned or borrowed, to the context
     #[inline]
-    pub fn add_object(&mut self, name: impl Into<String>, object: Object) {
-        self.objects.insert(name.into(), object);
+    pub fn add_object(&mut self, name: impl Into<String>, object: impl Into<CowObject<'a>>) {
+        self.objects.insert(name.into(), object.into());
     }
 
     /// Get an object from the context
     #[inline]
     pub fn get_object(&self, name: &str) -> Option<&Object> {
-        self.objects.get(name)
+        self.objects.get(name).map(|object| &**object)
+    }
+
+    /// Get an object from the context for in-place mutation, cloning it first if borrowed
+    ///
+    /// Actions should call `validate_context` before mutating, so a context that fails
+    /// validation is never left half-updated.
+    #[inline]
+    pub fn get_object_mut(&mut self, name: &str) -> Option<&mut Object> {
+        self.objects.get_mut(name).map(CowObject::to_mut)
     }
 
     /// Get multiple objects efficiently
     #[inline]
     pub fn get_objects(&self, names: &[&str]) -> HashMap<String, &Object> {
         names.iter()
-            .filter_map(|name| self.objects.get(*name).map(|obj| (name.to_string(), obj)))
+            .filter_map(|name| self.get_object(name).map(|obj| (name.to_string(), obj)))
             .collect()
     }
 
@@ -70,6 +416,29 @@ impl ActionContext {
         self.parameters.get(name)
     }
 
+    /// Validate the parameters against the action's `parameter_schema`, if it declares one
+    #[cfg(feature = "parameter_schema")]
+    pub fn validate_parameters_for(&self, action: &dyn Action) -> Result<()> {
+        let Some(schema) = action.parameter_schema() else {
+            return Ok(());
+        };
+        let compiled = jsonschema::JSONSchema::compile(&schema).map_err(|e| {
+            crate::OatsError::validation_error(format!("Invalid parameter schema for '{}': {}", action.name(), e))
+        })?;
+
+        let parameters = serde_json::Value::Object(
+            self.parameters.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+        );
+        compiled.validate(&parameters).map_err(|errors| {
+            let errors: Vec<String> = errors.map(|e| e.to_string()).collect();
+            crate::OatsError::validation_error(format!(
+                "Invalid parameters for '{}': {}",
+                action.name(),
+                errors.join("; ")
+            ))
+        })
+    }
+
     /// Add metadata to the context
     #[inline]
     pub fn add_metadata(&mut self, key: impl Into<String>, value: impl Into<String>) {
@@ -129,14 +498,183 @@ impl ActionContext {
     pub fn clear_metadata(&mut self) {
         self.metadata.clear();
     }
+
+    /// Move the objects, parameters and metadata of `other` into this context
+    ///
+    /// With `ConflictMode::Error` nothing is merged if any key already exists; the task ID is kept.
+    pub fn merge(&mut self, other: ActionContext<'a>, on_conflict: ConflictMode) -> Result<()> {
+        if on_conflict == ConflictMode::Error {
+            let conflict = other
+                .objects
+                .keys()
+                .find(|key| self.objects.contains_key(*key))
+                .map(|key| ("object", key))
+                .or_else(|| other.parameters.keys().find(|key| self.parameters.contains_key(*key)).map(|key| ("parameter", key)))
+                .or_else(|| other.metadata.keys().find(|key| self.metadata.contains_key(*key)).map(|key| ("metadata", key)));
+            if let Some((kind, key)) = conflict {
+                return Err(crate::OatsError::invalid_state(format!("{} '{}' already exists in the context", kind, key)));
+            }
+        }
+
+        self.objects.extend(other.objects);
+        self.parameters.extend(other.parameters);
+        self.metadata.extend(other.metadata);
+        Ok(())
+    }
 }
 
-impl Default for ActionContext {
+/// Shared services, such as database pools or HTTP clients, looked up by type
+#[derive(Clone, Default)]
+pub struct ServiceLocator {
+    services: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl ServiceLocator {
+    /// Create an empty locator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `service`, replacing any service of the same type
+    pub fn register<T: Any + Send + Sync>(&mut self, service: T) {
+        self.services.insert(TypeId::of::<T>(), Arc::new(service));
+    }
+
+    /// Register `service` and return the locator
+    pub fn with_service<T: Any + Send + Sync>(mut self, service: T) -> Self {
+        self.register(service);
+        self
+    }
+
+    /// Get the registered service of type `T`
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.services.get(&TypeId::of::<T>()).cloned()?.downcast().ok()
+    }
+
+    /// Get the number of registered services
+    pub fn len(&self) -> usize {
+        self.services.len()
+    }
+
+    /// Check whether no services are registered
+    pub fn is_empty(&self) -> bool {
+        self.services.is_empty()
+    }
+}
+
+impl std::fmt::Debug for ServiceLocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceLocator").field("services", &self.services.len()).finish()
+    }
+}
+
+/// How `ActionContext::merge` handles keys present in both contexts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictMode {
+    /// Fail with `OatsError::InvalidState` and leave the context unchanged
+    #[default]
+    Error,
+    /// Replace existing entries with the incoming ones
+    OverwriteExisting,
+}
+
+/// Serialized copy of an `ActionContext`, e.g. for a dead-letter queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionContextSnapshot {
+    state: serde_json::Value,
+}
+
+impl ActionContextSnapshot {
+    /// Create a snapshot from an already serialized context state
+    pub fn from_value(state: serde_json::Value) -> Self {
+        Self { state }
+    }
+
+    /// Get the serialized context state
+    pub fn as_value(&self) -> &serde_json::Value {
+        &self.state
+    }
+
+    /// Consume the snapshot, returning the serialized context state
+    pub fn into_value(self) -> serde_json::Value {
+        self.state
+    }
+
+    /// Rebuild the action context captured by this snapshot for replay
+    #[cfg(feature = "snapshot")]
+    pub fn restore(&self) -> Result<ActionContext<'static>> {
+        let field = |name: &str| self.state.get(name).cloned().unwrap_or_default();
+        Ok(ActionContext {
+            objects: serde_json::from_value(field("objects"))?,
+            parameters: serde_json::from_value(field("parameters"))?,
+            metadata: serde_json::from_value(field("metadata"))?,
+            task_id: serde_json::from_value(field("task_id"))?,
+            services: current_services(),
+        })
+    }
+}
+
+#[cfg(feature = "snapshot")]
+impl ActionContext<'_> {
+    /// Capture the objects, parameters and metadata of this context
+    pub fn snapshot(&self) -> ActionContextSnapshot {
+        ActionContextSnapshot::from_value(serde_json::json!({
+            "objects": self.objects,
+            "parameters": self.parameters,
+            "metadata": self.metadata,
+            "task_id": self.task_id,
+        }))
+    }
+}
+
+impl Default for ActionContext<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// One action execution recorded while `SystemManager::journal_mode` is enabled
+#[cfg(feature = "snapshot")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the action finished
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// System the action ran in, or empty outside a system
+    pub system_name: String,
+    /// Name of the executed action
+    pub action_name: String,
+    /// Context the action was given
+    pub context: ActionContextSnapshot,
+    /// Result the action returned; errors are recorded as failed results
+    pub result: ActionResult,
+}
+
+/// Trait metadata key naming the context object a trait update belongs to
+///
+/// It is only used for routing and is removed when the update is applied.
+pub const TARGET_OBJECT_METADATA: &str = "target_object";
+
+/// Copy a trait update without its routing metadata
+fn routed_update(update: &Trait) -> Trait {
+    let mut update = update.clone();
+    update.metadata.remove(TARGET_OBJECT_METADATA);
+    update
+}
+
+/// Standard machine-readable codes for failed action results
+pub mod error_codes {
+    /// A required object was missing
+    pub const OBJECT_NOT_FOUND: u32 = 1001;
+    /// A required trait was missing
+    pub const TRAIT_NOT_FOUND: u32 = 1002;
+    /// Input failed validation
+    pub const VALIDATION_FAILED: u32 = 2001;
+    /// A resource limit was hit; retryable
+    pub const RESOURCE_EXHAUSTED: u32 = 3001;
+    /// An operation timed out; retryable
+    pub const TIMEOUT: u32 = 3002;
+}
+
 /// Result of an action execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionResult {
@@ -148,6 +686,20 @@ pub struct ActionResult {
     pub messages: Vec<String>,
     /// Additional data returned by the action
     pub data: HashMap<String, serde_json::Value>,
+    /// Object the trait updates belong to, used by `SystemManager::process_and_apply`
+    #[serde(default)]
+    pub target_id: Option<ObjectId>,
+    /// Machine-readable failure code, see `error_codes`
+    #[serde(default)]
+    pub error_code: Option<u32>,
+    /// HTTP status code for exposing the result over HTTP; missing codes deserialize as `200`
+    #[serde(default = "default_status_code")]
+    pub status_code: u16,
+}
+
+/// Status code for results serialized before `status_code` existed
+fn default_status_code() -> u16 {
+    200
 }
 
 impl ActionResult {
@@ -159,6 +711,9 @@ impl ActionResult {
             trait_updates: Vec::new(),
             messages: Vec::new(),
             data: HashMap::new(),
+            target_id: None,
+            error_code: None,
+            status_code: 200,
         }
     }
 
@@ -170,9 +725,47 @@ impl ActionResult {
             trait_updates: Vec::new(),
             messages: vec![message.into()],
             data: HashMap::new(),
+            target_id: None,
+            error_code: None,
+            status_code: 500,
         }
     }
 
+    /// Create a successful action result with an HTTP status code
+    #[inline]
+    pub fn success_with_status(code: u16) -> Self {
+        let mut result = Self::success();
+        result.status_code = code;
+        result
+    }
+
+    /// Create a failed action result with an HTTP status code
+    #[inline]
+    pub fn failure_with_status(message: impl Into<String>, code: u16) -> Self {
+        let mut result = Self::failure(message);
+        result.status_code = code;
+        result
+    }
+
+    /// Create a failed action result with a machine-readable code
+    #[inline]
+    pub fn failure_with_code(message: impl Into<String>, code: u32) -> Self {
+        Self::failure(message).with_error_code(code)
+    }
+
+    /// Set the machine-readable error code
+    #[inline]
+    pub fn with_error_code(mut self, code: u32) -> Self {
+        self.error_code = Some(code);
+        self
+    }
+
+    /// Check if the failure is worth retrying, i.e. its code is in the `3xxx` range
+    #[inline]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.error_code, Some(3000..=3999))
+    }
+
     /// Create a successful action result with pre-allocated capacity
     pub fn success_with_capacity(trait_capacity: usize, message_capacity: usize, data_capacity: usize) -> Self {
         Self {
@@ -180,6 +773,9 @@ impl ActionResult {
             trait_updates: Vec::with_capacity(trait_capacity),
             messages: Vec::with_capacity(message_capacity),
             data: HashMap::with_capacity(data_capacity),
+            target_id: None,
+            error_code: None,
+            status_code: 200,
         }
     }
 
@@ -195,6 +791,46 @@ impl ActionResult {
         self.trait_updates.extend(trait_updates);
     }
 
+    /// Check whether more than one trait update targets the same trait name
+    pub fn has_duplicate_trait_updates(&self) -> bool {
+        let mut seen = HashSet::with_capacity(self.trait_updates.len());
+        self.trait_updates.iter().any(|t| !seen.insert(t.name()))
+    }
+
+    /// Keep only the last trait update for each trait name, preserving the order of the kept updates
+    pub fn deduplicate_trait_updates(&mut self) {
+        if !self.has_duplicate_trait_updates() {
+            return;
+        }
+        let mut seen = HashSet::with_capacity(self.trait_updates.len());
+        let mut kept: Vec<Trait> = self
+            .trait_updates
+            .drain(..)
+            .rev()
+            .filter(|t| seen.insert(t.name().to_string()))
+            .collect();
+        kept.reverse();
+        self.trait_updates = kept;
+    }
+
+    /// Fold a later result into this one
+    ///
+    /// Trait updates are deduplicated with last-write-wins, messages are appended and
+    /// `other`'s data overrides matching keys. The merged result fails if either failed,
+    /// taking the first failure's error and status codes.
+    pub fn merge(&mut self, other: ActionResult) {
+        if self.success && !other.success {
+            self.error_code = other.error_code;
+            self.status_code = other.status_code;
+        }
+        self.success &= other.success;
+        self.target_id = self.target_id.or(other.target_id);
+        self.trait_updates.extend(other.trait_updates);
+        self.messages.extend(other.messages);
+        self.data.extend(other.data);
+        self.deduplicate_trait_updates();
+    }
+
     /// Add a message to the result
     #[inline]
     pub fn add_message(&mut self, message: impl Into<String>) {
@@ -220,6 +856,34 @@ impl ActionResult {
         self.messages.reserve(messages);
     }
 
+    /// Set the object this result's trait updates belong to
+    #[inline]
+    pub fn set_target(&mut self, id: ObjectId) {
+        self.target_id = Some(id);
+    }
+
+    /// Apply all trait updates to the given object
+    pub fn apply_to(&self, object: &mut Object) {
+        object.add_traits(self.trait_updates.iter().map(routed_update));
+    }
+
+    /// Apply trait updates to the named objects in a context
+    ///
+    /// Each update goes to the context object named by its `TARGET_OBJECT_METADATA`
+    /// metadata entry, or to the `"target"` object if it has none. Updates naming a
+    /// missing object are skipped.
+    pub fn apply_to_context(&self, context: &mut ActionContext<'_>) {
+        for update in &self.trait_updates {
+            let name = update
+                .get_metadata(TARGET_OBJECT_METADATA)
+                .map(String::as_str)
+                .unwrap_or("target");
+            if let Some(object) = context.get_object_mut(name) {
+                object.add_trait(routed_update(update));
+            }
+        }
+    }
+
     /// Check if the action was successful
     #[inline]
     pub fn is_success(&self) -> bool {
@@ -279,7 +943,94 @@ pub trait Action: Send + Sync {
     fn description(&self) -> &str;
 
     /// Execute the action with the given context
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult>;
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult>;
+
+    /// Execute the action with its name available through `current_action_name`
+    ///
+    /// Inside a `SystemManager` run, this waits for a permit if the current system has a
+    /// concurrency limit, and a journaled run also records the context and result.
+    async fn execute_scoped(&self, context: ActionContext<'_>) -> Result<ActionResult> {
+        // Wrapper actions call their inner action's `execute_scoped`; only the outermost call
+        // takes a permit and records a journal entry, or a limit of 1 would deadlock.
+        if IN_DISPATCH.try_with(|_| ()).is_ok() {
+            return CURRENT_ACTION_NAME.scope(self.name().to_string(), self.execute(context)).await;
+        }
+
+        let _permit = acquire_dispatch_permit().await?;
+        #[cfg(feature = "snapshot")]
+        let recording = ACTION_JOURNAL.try_with(Clone::clone).ok().map(|journal| (journal, context.snapshot()));
+        let outcome = IN_DISPATCH
+            .scope((), CURRENT_ACTION_NAME.scope(self.name().to_string(), self.execute(context)))
+            .await;
+
+        #[cfg(feature = "snapshot")]
+        if let Some((journal, snapshot)) = recording {
+            let entry = JournalEntry {
+                timestamp: chrono::Utc::now(),
+                system_name: current_system_name().unwrap_or_default(),
+                action_name: self.name().to_string(),
+                context: snapshot,
+                result: outcome.as_ref().map_or_else(|e| e.to_action_result(), Clone::clone),
+            };
+            journal.lock().unwrap_or_else(|e| e.into_inner()).push(entry);
+        }
+        outcome
+    }
+
+    /// Execute the action once per context
+    ///
+    /// The default calls `execute_scoped` in a loop and stops at the first error. Override it
+    /// together with `supports_batch` to process the whole batch at once.
+    async fn execute_batch(&self, contexts: Vec<ActionContext<'_>>) -> Result<Vec<ActionResult>> {
+        let mut results = Vec::with_capacity(contexts.len());
+        for context in contexts {
+            results.push(self.execute_scoped(context).await?);
+        }
+        Ok(results)
+    }
+
+    /// Check whether this action overrides `execute_batch` with a batched implementation
+    fn supports_batch(&self) -> bool {
+        false
+    }
+
+    /// Check that the context satisfies this action's preconditions without executing it
+    ///
+    /// The default implementation checks that every `required_objects` entry is present
+    /// and that the checked objects carry every `required_traits` entry. The checked
+    /// objects are the required objects, or all context objects if none are declared.
+    async fn validate_context(&self, context: &ActionContext<'_>) -> Result<()> {
+        let required_objects = self.required_objects();
+        for name in &required_objects {
+            if context.get_object(name).is_none() {
+                return Err(crate::OatsError::object_not_found(name.clone()));
+            }
+        }
+
+        let required_traits = self.required_traits();
+        if required_traits.is_empty() {
+            return Ok(());
+        }
+        let required_traits: Vec<&str> = required_traits.iter().map(String::as_str).collect();
+
+        if required_objects.is_empty() {
+            for object in context.objects.values() {
+                object.validate_required_traits(&required_traits)?;
+            }
+        } else {
+            for name in &required_objects {
+                if let Some(object) = context.get_object(name) {
+                    object.validate_required_traits(&required_traits)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the names of context objects this action requires
+    fn required_objects(&self) -> Vec<String> {
+        Vec::new()
+    }
 
     /// Get the required trait names for this action
     fn required_traits(&self) -> Vec<String> {
@@ -290,6 +1041,247 @@ pub trait Action: Send + Sync {
     fn optional_traits(&self) -> Vec<String> {
         Vec::new()
     }
+
+    /// Get the JSON Schema that this action's context parameters must satisfy
+    ///
+    /// The schema describes the parameters as a single JSON object.
+    fn parameter_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Wrap this action so that it only runs when the context's `"target"` object matches `predicate`
+    fn when(self, predicate: ObjectFilter) -> ConditionalAction
+    where
+        Self: Sized + 'static,
+    {
+        ConditionalAction::new(predicate, Box::new(self))
+    }
+
+    /// Wrap this action so that `fallback` runs with the same context if it fails
+    fn with_fallback(self, fallback: Box<dyn Action>) -> FallbackAction
+    where
+        Self: Sized + 'static,
+    {
+        FallbackAction::new(Box::new(self), fallback)
+    }
+
+    /// Wrap this action so that its execution fails with a timeout error after `timeout`
+    fn with_timeout(self, timeout: std::time::Duration) -> TimeoutAction
+    where
+        Self: Sized + 'static,
+    {
+        TimeoutAction::new(Box::new(self), timeout)
+    }
+}
+
+/// Runs an action with a time limit on its `execute` call
+pub struct TimeoutAction {
+    name: String,
+    action: Box<dyn Action>,
+    timeout: std::time::Duration,
+}
+
+impl TimeoutAction {
+    /// Create a timeout action around an action
+    pub fn new(action: Box<dyn Action>, timeout: std::time::Duration) -> Self {
+        Self {
+            name: format!("{}(timeout={}ms)", action.name(), timeout.as_millis()),
+            action,
+            timeout,
+        }
+    }
+
+    /// Get the time limit
+    pub fn timeout(&self) -> std::time::Duration {
+        self.timeout
+    }
+}
+
+#[async_trait]
+impl Action for TimeoutAction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        self.action.description()
+    }
+
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult> {
+        tokio::time::timeout(self.timeout, self.action.execute_scoped(context))
+            .await
+            .map_err(|_| {
+                crate::OatsError::timeout_error(format!(
+                    "Action '{}' did not finish within {}ms",
+                    self.action.name(),
+                    self.timeout.as_millis()
+                ))
+            })?
+    }
+
+    fn required_objects(&self) -> Vec<String> {
+        self.action.required_objects()
+    }
+
+    fn required_traits(&self) -> Vec<String> {
+        self.action.required_traits()
+    }
+
+    fn parameter_schema(&self) -> Option<serde_json::Value> {
+        self.action.parameter_schema()
+    }
+}
+
+/// Runs a primary action and falls back to a second action on error
+///
+/// The result's `fallback_used` data entry records which path ran.
+pub struct FallbackAction {
+    name: String,
+    primary: Box<dyn Action>,
+    fallback: Box<dyn Action>,
+}
+
+impl FallbackAction {
+    /// Create a fallback action from a primary and a fallback action
+    pub fn new(primary: Box<dyn Action>, fallback: Box<dyn Action>) -> Self {
+        Self {
+            name: format!("{} -> {}", primary.name(), fallback.name()),
+            primary,
+            fallback,
+        }
+    }
+}
+
+#[async_trait]
+impl Action for FallbackAction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        self.primary.description()
+    }
+
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult> {
+        let primary_error = match self.primary.execute_scoped(context.clone()).await {
+            Ok(mut result) => {
+                result.add_data("fallback_used", serde_json::json!(false));
+                return Ok(result);
+            }
+            Err(e) => e,
+        };
+
+        match self.fallback.execute_scoped(context).await {
+            Ok(mut result) => {
+                result.add_data("fallback_used", serde_json::json!(true));
+                Ok(result)
+            }
+            Err(fallback_error) => Err(crate::OatsError::FallbackFailed {
+                primary: Box::new(primary_error),
+                fallback: Box::new(fallback_error),
+            }),
+        }
+    }
+
+    fn required_objects(&self) -> Vec<String> {
+        self.primary.required_objects()
+    }
+
+    fn required_traits(&self) -> Vec<String> {
+        self.primary.required_traits()
+    }
+}
+
+/// Runs an action only when a context object matches a predicate
+///
+/// The result's `condition_met` data entry records whether the action ran; when it
+/// did not, the result is an empty success.
+pub struct ConditionalAction {
+    predicate: ObjectFilter,
+    action: Box<dyn Action>,
+    object_name: String,
+}
+
+impl ConditionalAction {
+    /// Create a conditional action checking the context's `"target"` object
+    pub fn new(predicate: ObjectFilter, action: Box<dyn Action>) -> Self {
+        Self {
+            predicate,
+            action,
+            object_name: "target".to_string(),
+        }
+    }
+
+    /// Check the named context object instead of `"target"`
+    pub fn on_object(mut self, name: impl Into<String>) -> Self {
+        self.object_name = name.into();
+        self
+    }
+
+    /// Check whether the action will run for a context
+    pub fn condition_met(&self, context: &ActionContext<'_>) -> bool {
+        context
+            .get_object(&self.object_name)
+            .is_some_and(|object| self.predicate.matches(object))
+    }
+}
+
+#[async_trait]
+impl Action for ConditionalAction {
+    fn name(&self) -> &str {
+        self.action.name()
+    }
+
+    fn description(&self) -> &str {
+        self.action.description()
+    }
+
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult> {
+        let met = self.condition_met(&context);
+        let mut result = if met {
+            self.action.execute_scoped(context).await?
+        } else {
+            ActionResult::success()
+        };
+        result.add_data("condition_met", serde_json::json!(met));
+        Ok(result)
+    }
+
+    fn required_objects(&self) -> Vec<String> {
+        self.action.required_objects()
+    }
+
+    fn required_traits(&self) -> Vec<String> {
+        self.action.required_traits()
+    }
+
+    fn parameter_schema(&self) -> Option<serde_json::Value> {
+        self.action.parameter_schema()
+    }
+}
+
+/// Stream of results produced incrementally by a streaming action
+pub type ActionStream = Pin<Box<dyn Stream<Item = Result<ActionResult>> + Send>>;
+
+/// A streaming action produces results incrementally for long-running work
+#[async_trait]
+pub trait StreamingAction: Send + Sync {
+    /// Get the name of this action
+    fn name(&self) -> &str;
+
+    /// Get the description of this action
+    fn description(&self) -> &str;
+
+    /// Start the action with the given context, returning a stream of results
+    async fn stream_execute(&self, context: ActionContext<'_>) -> Result<ActionStream>;
+
+    /// Check the action's preconditions without starting it
+    ///
+    /// `StreamingSystem` calls this before `stream_execute` and skips the action on error.
+    /// The default accepts every context.
+    async fn validate_context(&self, _context: &ActionContext<'_>) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -310,6 +1302,159 @@ mod tests {
         assert_eq!(context.get_metadata("key"), Some(&"value".to_string()));
     }
 
+    #[test]
+    fn test_merge_deduplicates_trait_updates() {
+        let mut first = ActionResult::success();
+        first.add_trait_update(Trait::new("health", crate::TraitData::Number(90.0)));
+        first.add_trait_update(Trait::new("mana", crate::TraitData::Number(10.0)));
+        first.add_message("poisoned");
+
+        let mut second = ActionResult::success();
+        second.add_trait_update(Trait::new("health", crate::TraitData::Number(95.0)));
+        second.add_message("healed");
+
+        first.merge(second);
+        assert!(!first.has_duplicate_trait_updates());
+        let updates: Vec<_> = first.trait_updates.iter().map(|t| (t.name(), t.data().as_number())).collect();
+        assert_eq!(updates, vec![("mana", Some(10.0)), ("health", Some(95.0))]);
+        assert_eq!(first.messages, vec!["poisoned".to_string(), "healed".to_string()]);
+
+        first.merge(ActionResult::failure_with_status("out of mana", 422));
+        assert!(first.is_failure());
+        assert_eq!(first.status_code, 422);
+    }
+
+    #[test]
+    fn test_get_object_mut() {
+        let mut context = ActionContext::from_object_pairs([("target", Object::new("hero", "player"))]);
+        context
+            .get_object_mut("target")
+            .unwrap()
+            .add_trait(Trait::new("health", crate::TraitData::Number(90.0)));
+
+        assert!(context.get_object("target").unwrap().has_trait("health"));
+        assert!(context.get_object_mut("missing").is_none());
+    }
+
+    #[test]
+    fn test_service_locator() {
+        struct PaymentGateway(&'static str);
+
+        let mut services = ServiceLocator::new();
+        assert!(services.get::<PaymentGateway>().is_none());
+        services.register(PaymentGateway("sandbox"));
+        services.register(PaymentGateway("live"));
+        assert_eq!(services.len(), 1);
+        assert_eq!(services.get::<PaymentGateway>().unwrap().0, "live");
+        assert!(services.get::<String>().is_none());
+    }
+
+    #[test]
+    fn test_borrowed_context_copies_on_write() {
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(Trait::new("health", crate::TraitData::Number(50.0)));
+        let goblin = Object::new("goblin", "enemy");
+
+        let context = ActionContext::from_refs(HashMap::from([("target".to_string(), &hero)]));
+        let mut copy = context.for_target(&goblin);
+        assert!(copy.objects.values().all(CowObject::is_borrowed));
+        assert_eq!(copy.get_object("target").map(|o| o.name()), Some("goblin"));
+
+        let mut healed = context.clone();
+        healed.get_object_mut("target").unwrap().add_trait(Trait::new("health", crate::TraitData::Number(90.0)));
+        assert!(!healed.objects["target"].is_borrowed());
+        assert!(context.objects["target"].is_borrowed());
+        assert_eq!(hero.get_trait_data("health").and_then(|d| d.as_number()), Some(50.0));
+
+        copy.add_object("ally", Object::new("healer", "player"));
+        let owned: ActionContext<'static> = copy.into_owned();
+        assert!(owned.objects.values().all(|object| !object.is_borrowed()));
+    }
+
+    #[test]
+    fn test_merge_contexts() {
+        let mut context = ActionContext::from_object_pairs([("customer", Object::new("alice", "customer"))]);
+        context.add_parameter("quantity", serde_json::json!(1));
+
+        let mut product = ActionContext::from_object_pairs([("product", Object::new("widget", "product"))]);
+        product.add_metadata("source", "catalog");
+        context.merge(product, ConflictMode::default()).unwrap();
+        assert_eq!(context.object_count(), 2);
+        assert_eq!(context.get_metadata("source"), Some(&"catalog".to_string()));
+
+        let mut update = ActionContext::from_object_pairs([("coupon", Object::new("spring", "coupon"))]);
+        update.add_parameter("quantity", serde_json::json!(3));
+        assert!(matches!(
+            context.merge(update.clone(), ConflictMode::Error),
+            Err(crate::OatsError::InvalidState { .. })
+        ));
+        assert!(context.get_object("coupon").is_none());
+
+        context.merge(update, ConflictMode::OverwriteExisting).unwrap();
+        assert_eq!(context.get_parameter("quantity"), Some(&serde_json::json!(3)));
+        assert_eq!(context.object_count(), 3);
+    }
+
+    #[test]
+    fn test_action_context_from_objects() {
+        let mut context = ActionContext::from_object_pairs([
+            ("attacker", Object::new("hero", "player")),
+            ("target", Object::new("goblin", "enemy")),
+        ]);
+        assert_eq!(context.object_count(), 2);
+        assert_eq!(context.parameter_count(), 0);
+
+        let mut extra = HashMap::new();
+        extra.insert("target".to_string(), Object::new("orc", "enemy"));
+        extra.insert("ally".to_string(), Object::new("healer", "player"));
+        context.extend_objects(extra).add_metadata("round", "1");
+
+        assert_eq!(context.object_count(), 3);
+        assert_eq!(context.get_object("target").map(|o| o.name()), Some("orc"));
+    }
+
+    #[test]
+    fn test_narrowed_contexts() {
+        let mut context = ActionContext::from_object_pairs([
+            ("customer", Object::new("john_doe", "customer")),
+            ("product", Object::new("laptop_pro", "product")),
+        ]);
+        context.add_parameter("quantity", serde_json::json!(2));
+        context.add_metadata("order", "ORD-001");
+
+        let narrowed = context.without_object("customer");
+        assert_eq!(narrowed.object_count(), 1);
+        assert!(narrowed.get_object("customer").is_none());
+        assert_eq!(narrowed.parameter_count(), 1);
+        assert_eq!(narrowed.get_metadata("order").map(String::as_str), Some("ORD-001"));
+
+        let only = context.only_objects(&["customer", "warehouse"]);
+        assert_eq!(only.object_count(), 1);
+        assert!(only.get_object("customer").is_some());
+
+        let renamed = context.renamed_object("product", "target");
+        assert_eq!(renamed.get_object("target").map(|o| o.name()), Some("laptop_pro"));
+        assert!(renamed.get_object("product").is_none());
+        assert_eq!(context.object_count(), 2);
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[test]
+    fn test_context_snapshot() {
+        let mut context = ActionContext::from_object_pairs([("customer", Object::new("john_doe", "customer"))]);
+        context.add_parameter("order_id", serde_json::json!("ORD-001"));
+        context.add_metadata("attempt", "1");
+
+        let snapshot = context.snapshot();
+        assert_eq!(snapshot.as_value()["parameters"]["order_id"], "ORD-001");
+
+        let error = crate::OatsError::action_failed_with_snapshot("balance too low", snapshot.clone());
+        assert_eq!(error.to_string(), "Action failed: balance too low");
+        let restored = error.context_snapshot().unwrap().restore().unwrap();
+        assert_eq!(restored.get_object("customer").map(|o| o.name()), Some("john_doe"));
+        assert_eq!(restored.get_metadata("attempt"), Some(&"1".to_string()));
+    }
+
     #[test]
     fn test_action_result() {
         let mut result = ActionResult::success();
@@ -320,4 +1465,306 @@ mod tests {
         assert_eq!(result.messages.len(), 1);
         assert_eq!(result.data.len(), 1);
     }
+
+    #[test]
+    fn test_failure_with_code() {
+        let result = ActionResult::failure_with_code("Out of stock", error_codes::RESOURCE_EXHAUSTED);
+        assert!(!result.is_success());
+        assert_eq!(result.error_code, Some(3001));
+        assert!(result.is_retryable());
+        assert!(!ActionResult::failure("Bad input").with_error_code(error_codes::VALIDATION_FAILED).is_retryable());
+
+        let decoded: ActionResult = serde_json::from_str(r#"{"success":true,"trait_updates":[],"messages":[],"data":{}}"#).unwrap();
+        assert_eq!(decoded.error_code, None);
+    }
+
+    #[test]
+    fn test_apply_result() {
+        let mut result = ActionResult::success();
+        result.add_trait_update(Trait::new("health", crate::TraitData::Number(90.0)));
+        let mut attacker_stamina = Trait::new("stamina", crate::TraitData::Number(4.0));
+        attacker_stamina.set_metadata(TARGET_OBJECT_METADATA, "attacker");
+        result.add_trait_update(attacker_stamina);
+
+        let mut hero = Object::new("hero", "player");
+        result.apply_to(&mut hero);
+        assert_eq!(hero.trait_count(), 2);
+        assert!(hero.get_trait("stamina").unwrap().get_metadata(TARGET_OBJECT_METADATA).is_none());
+
+        let mut context = ActionContext::from_object_pairs([
+            ("attacker", Object::new("hero", "player")),
+            ("target", Object::new("goblin", "enemy")),
+        ]);
+        result.apply_to_context(&mut context);
+        assert!(context.get_object("target").unwrap().has_trait("health"));
+        assert!(!context.get_object("target").unwrap().has_trait("stamina"));
+        let attacker = context.get_object("attacker").unwrap();
+        assert!(attacker.get_trait("stamina").unwrap().get_metadata(TARGET_OBJECT_METADATA).is_none());
+        assert_eq!(result.trait_updates[1].get_metadata(TARGET_OBJECT_METADATA), Some(&"attacker".to_string()));
+    }
+
+    struct HealAction;
+
+    #[async_trait]
+    impl Action for HealAction {
+        fn name(&self) -> &str {
+            "heal"
+        }
+
+        fn description(&self) -> &str {
+            "Heals the target"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            Ok(ActionResult::success())
+        }
+
+        fn required_objects(&self) -> Vec<String> {
+            vec!["target".to_string()]
+        }
+
+        fn required_traits(&self) -> Vec<String> {
+            vec!["health".to_string()]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_default() {
+        assert!(!HealAction.supports_batch());
+        let contexts = vec![ActionContext::new(), ActionContext::new(), ActionContext::new()];
+        let results = HealAction.execute_batch(contexts).await.unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert!(FailingAction("refund").execute_batch(vec![ActionContext::new()]).await.is_err());
+    }
+
+    #[cfg(feature = "parameter_schema")]
+    struct OrderAction;
+
+    #[cfg(feature = "parameter_schema")]
+    #[async_trait]
+    impl Action for OrderAction {
+        fn name(&self) -> &str {
+            "order"
+        }
+
+        fn description(&self) -> &str {
+            "Places an order"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            Ok(ActionResult::success())
+        }
+
+        fn parameter_schema(&self) -> Option<serde_json::Value> {
+            Some(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "order_id": { "type": "string", "minLength": 1 },
+                    "total_amount": { "type": "number", "exclusiveMinimum": 0 }
+                },
+                "required": ["order_id", "total_amount"]
+            }))
+        }
+    }
+
+    #[cfg(feature = "parameter_schema")]
+    #[test]
+    fn test_validate_parameters_for() {
+        let mut context = ActionContext::new();
+        assert!(matches!(
+            context.validate_parameters_for(&OrderAction),
+            Err(crate::OatsError::ValidationError { .. })
+        ));
+
+        context.add_parameter("order_id", serde_json::json!("ORD-001"));
+        context.add_parameter("total_amount", serde_json::json!(-5.0));
+        assert!(context.validate_parameters_for(&OrderAction).is_err());
+
+        context.add_parameter("total_amount", serde_json::json!(99.5));
+        context.validate_parameters_for(&OrderAction).unwrap();
+        context.validate_parameters_for(&HealAction).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_context() {
+        let mut context = ActionContext::new();
+        assert!(matches!(
+            HealAction.validate_context(&context).await,
+            Err(crate::OatsError::ObjectNotFound { .. })
+        ));
+
+        context.add_object("target", Object::new("hero", "player"));
+        assert!(matches!(
+            HealAction.validate_context(&context).await,
+            Err(crate::OatsError::TraitNotFound { .. })
+        ));
+
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(Trait::new("health", crate::TraitData::Number(50.0)));
+        context.add_object("target", hero);
+        assert!(HealAction.validate_context(&context).await.is_ok());
+    }
+
+    struct FailingAction(&'static str);
+
+    #[async_trait]
+    impl Action for FailingAction {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Always fails"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            Err(crate::OatsError::trait_not_found("balance"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_action() {
+        let action = FailingAction("process_order").with_fallback(Box::new(HealAction));
+        assert_eq!(action.name(), "process_order -> heal");
+        assert!(action.required_objects().is_empty());
+        assert_eq!(HealAction.with_fallback(Box::new(WhoAmIAction)).required_objects(), vec!["target".to_string()]);
+
+        let result = action.execute(ActionContext::new()).await.unwrap();
+        assert_eq!(result.data["fallback_used"], serde_json::json!(true));
+
+        let action = HealAction.with_fallback(Box::new(FailingAction("refund")));
+        let result = action.execute(ActionContext::new()).await.unwrap();
+        assert_eq!(result.data["fallback_used"], serde_json::json!(false));
+
+        let action = FailingAction("process_order").with_fallback(Box::new(FailingAction("refund")));
+        match action.execute(ActionContext::new()).await {
+            Err(crate::OatsError::FallbackFailed { primary, .. }) => {
+                assert!(matches!(*primary, crate::OatsError::TraitNotFound { .. }));
+            }
+            other => panic!("expected FallbackFailed, got {:?}", other.map(|r| r.success)),
+        }
+    }
+
+    struct SleepyAction(std::time::Duration);
+
+    #[async_trait]
+    impl Action for SleepyAction {
+        fn name(&self) -> &str {
+            "price_lookup"
+        }
+
+        fn description(&self) -> &str {
+            "Waits before succeeding"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            tokio::time::sleep(self.0).await;
+            Ok(ActionResult::success())
+        }
+    }
+
+    struct WhoAmIAction;
+
+    #[async_trait]
+    impl Action for WhoAmIAction {
+        fn name(&self) -> &str {
+            "who_am_i"
+        }
+
+        fn description(&self) -> &str {
+            "Reports the action and system it runs in"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            let mut result = ActionResult::success();
+            result.add_data("action", serde_json::json!(current_action_name()));
+            result.add_data("system", serde_json::json!(current_system_name()));
+            Ok(result)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_scoped_sets_current_action_name() {
+        assert_eq!(current_action_name(), None);
+
+        let result = WhoAmIAction.execute(ActionContext::new()).await.unwrap();
+        assert_eq!(result.data.get("action"), Some(&serde_json::Value::Null));
+
+        let result = WhoAmIAction.execute_scoped(ActionContext::new()).await.unwrap();
+        assert_eq!(result.data.get("action"), Some(&serde_json::json!("who_am_i")));
+
+        let result = with_current_system_name("combat".to_string(), WhoAmIAction.execute_scoped(ActionContext::new()))
+            .await
+            .unwrap();
+        assert_eq!(result.data.get("system"), Some(&serde_json::json!("combat")));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_action() {
+        let slow = SleepyAction(std::time::Duration::from_millis(200)).with_timeout(std::time::Duration::from_millis(10));
+        assert_eq!(slow.name(), "price_lookup(timeout=10ms)");
+        assert!(matches!(
+            slow.execute(ActionContext::new()).await,
+            Err(crate::OatsError::TimeoutError { .. })
+        ));
+
+        let fast = SleepyAction(std::time::Duration::ZERO).with_timeout(std::time::Duration::from_secs(5));
+        assert!(fast.execute(ActionContext::new()).await.unwrap().is_success());
+    }
+
+    struct CountdownAction;
+
+    #[async_trait]
+    impl StreamingAction for CountdownAction {
+        fn name(&self) -> &str {
+            "countdown"
+        }
+
+        fn description(&self) -> &str {
+            "Emits one result per step"
+        }
+
+        async fn stream_execute(&self, _context: ActionContext<'_>) -> Result<ActionStream> {
+            let steps = (1..=3).map(|step| {
+                let mut result = ActionResult::success();
+                result.add_message(format!("step {}", step));
+                Ok(result)
+            });
+            Ok(Box::pin(futures::stream::iter(steps)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conditional_action() {
+        let action = HealAction.when(ObjectFilter::trait_number_lt("health", 50.0));
+        assert_eq!(action.name(), "heal");
+        assert_eq!(action.required_objects(), vec!["target".to_string()]);
+
+        let mut wounded = Object::new("hero", "player");
+        wounded.add_trait(Trait::new("health", crate::TraitData::Number(20.0)));
+        let result = action.execute(ActionContext::from_object_pairs([("target", wounded)])).await.unwrap();
+        assert_eq!(result.data["condition_met"], serde_json::json!(true));
+
+        let mut healthy = Object::new("hero", "player");
+        healthy.add_trait(Trait::new("health", crate::TraitData::Number(90.0)));
+        let context = ActionContext::from_object_pairs([("target", healthy)]);
+        let result = action.execute(context.clone()).await.unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.data["condition_met"], serde_json::json!(false));
+
+        let on_ally = ConditionalAction::new(ObjectFilter::always(), Box::new(HealAction)).on_object("ally");
+        assert!(!on_ally.condition_met(&context));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_action() {
+        use futures::StreamExt;
+
+        let stream = CountdownAction.stream_execute(ActionContext::new()).await.unwrap();
+        let results: Vec<_> = stream.collect().await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].as_ref().unwrap().messages[0], "step 3");
+    }
 } 
\ No newline at end of file
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use thiserror::Error;
+use crate::actions::{error_codes, ActionContextSnapshot, ActionResult};
 
 /// Error types for OATS operations
 #[derive(Error, Debug)]
@@ -10,7 +12,10 @@ pub enum OatsError {
     TraitNotFound { trait_name: String },
 
     #[error("Action failed: {message}")]
-    ActionFailed { message: String },
+    ActionFailed {
+        message: String,
+        context_snapshot: Option<ActionContextSnapshot>,
+    },
 
     #[error("System error: {message}")]
     SystemError { message: String },
@@ -33,6 +38,13 @@ pub enum OatsError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("{primary} (fallback failed: {fallback})")]
+    FallbackFailed {
+        #[source]
+        primary: Box<OatsError>,
+        fallback: Box<OatsError>,
+    },
+
     #[error("Unknown error: {message}")]
     Unknown { message: String },
 }
@@ -50,7 +62,20 @@ impl OatsError {
 
     /// Create a new action failed error
     pub fn action_failed(message: impl Into<String>) -> Self {
-        Self::ActionFailed { message: message.into() }
+        Self::ActionFailed { message: message.into(), context_snapshot: None }
+    }
+
+    /// Create a new action failed error carrying the context it failed with
+    pub fn action_failed_with_snapshot(message: impl Into<String>, snapshot: ActionContextSnapshot) -> Self {
+        Self::ActionFailed { message: message.into(), context_snapshot: Some(snapshot) }
+    }
+
+    /// Get the context snapshot attached to an action failure, if any
+    pub fn context_snapshot(&self) -> Option<&ActionContextSnapshot> {
+        match self {
+            Self::ActionFailed { context_snapshot, .. } => context_snapshot.as_ref(),
+            _ => None,
+        }
     }
 
     /// Create a new system error
@@ -84,21 +109,219 @@ impl OatsError {
     }
 
     /// Check if this error is recoverable
+    ///
+    /// A failed fallback is classified by the fallback's error, the last one raised.
     pub fn is_recoverable(&self) -> bool {
-        matches!(self, 
-            OatsError::ObjectNotFound { .. } |
-            OatsError::TraitNotFound { .. } |
-            OatsError::ValidationError { .. } |
-            OatsError::TimeoutError { .. }
-        )
+        match self {
+            OatsError::FallbackFailed { fallback, .. } => fallback.is_recoverable(),
+            _ => matches!(self,
+                OatsError::ObjectNotFound { .. } |
+                OatsError::TraitNotFound { .. } |
+                OatsError::ValidationError { .. } |
+                OatsError::TimeoutError { .. }
+            ),
+        }
+    }
+
+    /// Convert this error into a failed action result carrying the matching error code
+    pub fn to_action_result(&self) -> ActionResult {
+        let code = match self {
+            OatsError::ObjectNotFound { .. } => Some(error_codes::OBJECT_NOT_FOUND),
+            OatsError::TraitNotFound { .. } => Some(error_codes::TRAIT_NOT_FOUND),
+            OatsError::ValidationError { .. } => Some(error_codes::VALIDATION_FAILED),
+            OatsError::ResourceExhausted { .. } => Some(error_codes::RESOURCE_EXHAUSTED),
+            OatsError::TimeoutError { .. } => Some(error_codes::TIMEOUT),
+            _ => None,
+        };
+        let mut result = ActionResult::failure_with_status(self.to_string(), self.to_http_status());
+        result.error_code = code;
+        result
+    }
+
+    /// Get the HTTP status code matching this error
+    pub fn to_http_status(&self) -> u16 {
+        match self {
+            OatsError::ObjectNotFound { .. } | OatsError::TraitNotFound { .. } => 404,
+            OatsError::ValidationError { .. } => 422,
+            OatsError::ResourceExhausted { .. } => 429,
+            OatsError::TimeoutError { .. } => 408,
+            _ => 500,
+        }
+    }
+
+    /// Get the name of this error's variant
+    pub fn error_type(&self) -> &'static str {
+        match self {
+            OatsError::ObjectNotFound { .. } => "ObjectNotFound",
+            OatsError::TraitNotFound { .. } => "TraitNotFound",
+            OatsError::ActionFailed { .. } => "ActionFailed",
+            OatsError::SystemError { .. } => "SystemError",
+            OatsError::InvalidState { .. } => "InvalidState",
+            OatsError::ValidationError { .. } => "ValidationError",
+            OatsError::ResourceExhausted { .. } => "ResourceExhausted",
+            OatsError::TimeoutError { .. } => "TimeoutError",
+            OatsError::SerializationError(_) => "SerializationError",
+            OatsError::IoError(_) => "IoError",
+            OatsError::FallbackFailed { .. } => "FallbackFailed",
+            OatsError::Unknown { .. } => "Unknown",
+        }
+    }
+
+    /// Get this error as key-value pairs, with the variant name under `"error"`
+    pub fn to_structured(&self) -> HashMap<&'static str, String> {
+        let mut fields = HashMap::with_capacity(2);
+        fields.insert("error", self.error_type().to_string());
+        match self {
+            OatsError::ObjectNotFound { id } => {
+                fields.insert("id", id.clone());
+            }
+            OatsError::TraitNotFound { trait_name } => {
+                fields.insert("trait_name", trait_name.clone());
+            }
+            OatsError::ActionFailed { message, context_snapshot } => {
+                fields.insert("message", message.clone());
+                if let Some(snapshot) = context_snapshot {
+                    fields.insert("context_snapshot", snapshot.as_value().to_string());
+                }
+            }
+            OatsError::SystemError { message }
+            | OatsError::InvalidState { message }
+            | OatsError::ValidationError { message }
+            | OatsError::ResourceExhausted { message }
+            | OatsError::TimeoutError { message }
+            | OatsError::Unknown { message } => {
+                fields.insert("message", message.clone());
+            }
+            OatsError::SerializationError(e) => {
+                fields.insert("message", e.to_string());
+            }
+            OatsError::IoError(e) => {
+                fields.insert("message", e.to_string());
+            }
+            OatsError::FallbackFailed { primary, fallback } => {
+                fields.insert("primary", primary.to_string());
+                fields.insert("fallback", fallback.to_string());
+            }
+        }
+        fields
+    }
+
+    /// Serialize the structured form of this error as a JSON object string
+    pub fn to_json_string(&self) -> String {
+        serde_json::json!(self.to_structured()).to_string()
     }
 
     /// Check if this error is fatal
+    ///
+    /// A failed fallback is classified by the fallback's error, the last one raised.
     pub fn is_fatal(&self) -> bool {
-        matches!(self,
-            OatsError::ResourceExhausted { .. } |
-            OatsError::InvalidState { .. } |
-            OatsError::SystemError { .. }
-        )
+        match self {
+            OatsError::FallbackFailed { fallback, .. } => fallback.is_fatal(),
+            _ => matches!(self,
+                OatsError::ResourceExhausted { .. } |
+                OatsError::InvalidState { .. } |
+                OatsError::SystemError { .. }
+            ),
+        }
+    }
+}
+
+impl From<tokio::task::JoinError> for OatsError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        if e.is_panic() {
+            OatsError::system_error(format!("task panicked: {}", e))
+        } else {
+            OatsError::system_error(format!("task cancelled: {}", e))
+        }
+    }
+}
+
+impl From<tokio::sync::AcquireError> for OatsError {
+    fn from(e: tokio::sync::AcquireError) -> Self {
+        OatsError::system_error(format!("semaphore closed: {}", e))
     }
-} 
\ No newline at end of file
+}
+
+impl From<tokio::time::error::Elapsed> for OatsError {
+    fn from(e: tokio::time::error::Elapsed) -> Self {
+        OatsError::timeout_error(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_error() {
+        let error = OatsError::trait_not_found("health");
+        let fields = error.to_structured();
+        assert_eq!(fields["error"], "TraitNotFound");
+        assert_eq!(fields["trait_name"], "health");
+        assert_eq!(error.to_json_string(), r#"{"error":"TraitNotFound","trait_name":"health"}"#);
+        assert_eq!(error.to_string(), "Trait not found: health");
+    }
+
+    #[test]
+    fn test_to_action_result() {
+        let result = OatsError::trait_not_found("health").to_action_result();
+        assert!(!result.is_success());
+        assert_eq!(result.error_code, Some(error_codes::TRAIT_NOT_FOUND));
+        assert!(!result.is_retryable());
+
+        assert!(OatsError::resource_exhausted("pool empty").to_action_result().is_retryable());
+        assert_eq!(OatsError::unknown("oops").to_action_result().error_code, None);
+    }
+
+    #[test]
+    fn test_to_http_status() {
+        assert_eq!(OatsError::object_not_found("hero").to_http_status(), 404);
+        assert_eq!(OatsError::trait_not_found("health").to_http_status(), 404);
+        assert_eq!(OatsError::validation_error("bad price").to_http_status(), 422);
+        assert_eq!(OatsError::resource_exhausted("rate limited").to_http_status(), 429);
+        assert_eq!(OatsError::timeout_error("slow").to_http_status(), 408);
+        assert_eq!(OatsError::system_error("boom").to_http_status(), 500);
+
+        assert_eq!(OatsError::validation_error("bad price").to_action_result().status_code, 422);
+        assert_eq!(ActionResult::success().status_code, 200);
+        assert_eq!(ActionResult::failure("boom").status_code, 500);
+        assert_eq!(ActionResult::success_with_status(201).status_code, 201);
+        assert_eq!(ActionResult::failure_with_status("gone", 410).status_code, 410);
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(OatsError::object_not_found("hero").is_recoverable());
+        assert!(OatsError::timeout_error("slow").is_recoverable());
+        assert!(!OatsError::timeout_error("slow").is_fatal());
+        assert!(OatsError::system_error("boom").is_fatal());
+        assert!(!OatsError::system_error("boom").is_recoverable());
+
+        let fallback_failed = |fallback: OatsError| OatsError::FallbackFailed {
+            primary: Box::new(OatsError::system_error("primary down")),
+            fallback: Box::new(fallback),
+        };
+        let retry_later = fallback_failed(OatsError::timeout_error("fallback slow"));
+        assert!(retry_later.is_recoverable());
+        assert!(!retry_later.is_fatal());
+        let broken = fallback_failed(OatsError::invalid_state("fallback misconfigured"));
+        assert!(broken.is_fatal());
+        assert!(!broken.is_recoverable());
+    }
+
+    #[tokio::test]
+    async fn test_from_tokio_errors() {
+        let join_error = tokio::spawn(async { panic!("boom") }).await.unwrap_err();
+        let error = OatsError::from(join_error);
+        assert!(matches!(error, OatsError::SystemError { .. }));
+        assert!(error.to_string().contains("task panicked"));
+
+        let semaphore = tokio::sync::Semaphore::new(1);
+        semaphore.close();
+        let error = OatsError::from(semaphore.acquire().await.unwrap_err());
+        assert!(matches!(error, OatsError::SystemError { .. }));
+
+        let elapsed = tokio::time::timeout(std::time::Duration::ZERO, std::future::pending::<()>()).await.unwrap_err();
+        assert!(matches!(OatsError::from(elapsed), OatsError::TimeoutError { .. }));
+    }
+}
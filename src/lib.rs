@@ -12,24 +12,116 @@ pub mod objects;
 pub mod actions;
 pub mod traits;
 pub mod systems;
+pub mod spatial;
+pub mod serialization;
 pub mod error;
+pub mod validation;
+mod store;
+#[cfg(feature = "proto")]
+pub mod proto;
+#[cfg(feature = "oats_schema")]
+pub mod schema;
+#[cfg(feature = "lua")]
+pub mod lua;
+#[cfg(feature = "reactive")]
+pub mod observable;
+
+// Lets `#[oats_object]` expansions resolve `::oats_framework` inside this crate's tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as oats_framework;
 
 // Re-export main types for convenience
-pub use objects::Object;
-pub use actions::{Action, ActionContext, ActionResult};
-pub use traits::{Trait, TraitData};
-pub use systems::{System, SystemManager, Priority};
+pub use objects::{BoolExpr, Object, ObjectDiff, ObjectFilter, ObjectFullDiff, ObjectTemplate, ObjectPatch, MetadataDiff, PatchOp, TypedObjectCollection};
+pub use actions::{current_action_name, current_system_name, Action, ActionContext, ActionContextSnapshot, ActionResult, ConditionalAction, ConflictMode, CowObject, FallbackAction, ServiceLocator, StreamingAction, TimeoutAction};
+pub use traits::{Trait, TraitData, TraitMigration, TraitMigrationRegistry, TraitValue};
+pub use systems::{System, SystemManager, StreamingSystem, GarbageCollectionSystem, Priority, DryRunReport, ObjectChange, ObjectStats, ProcessingSummary, ProgressEvent, HotSwapable};
+#[cfg(feature = "snapshot")]
+pub use actions::{JournalEntry, DEFAULT_JOURNAL_CAPACITY};
+#[cfg(feature = "work_stealing")]
+pub use systems::WorkStealingSystemManager;
+#[cfg(feature = "lua")]
+pub use lua::LuaSystem;
+#[cfg(feature = "reactive")]
+pub use observable::{Observable, SystemEvent};
+pub use spatial::SpatialIndex2D;
+pub use serialization::{ObjectSerializer, SerializationRegistry, JsonObjectSerializer};
+#[cfg(feature = "msgpack")]
+pub use serialization::MsgPackObjectSerializer;
 pub use error::OatsError;
+pub use validation::{ObjectSchema, TraitSchema, ValidationError};
+#[cfg(feature = "derive")]
+pub use oats_derive::oats_object;
+pub use tokio_util::sync::CancellationToken;
+
+use std::marker::PhantomData;
+use std::time::Duration;
 
 /// Result type for OATS operations
 pub type Result<T> = std::result::Result<T, OatsError>;
 
+/// Default interval between ticks of `OatsSystem::run_forever`
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_millis(100);
+
+/// Per-lane tick rates for `OatsSystem::run_forever`
+///
+/// The critical lane runs `Critical` and `High` systems, the normal lane `Normal`
+/// systems and the low lane `Low` systems. A rate of zero disables that lane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LanedSchedule {
+    /// Ticks per second of the critical lane
+    pub critical_hz: u32,
+    /// Ticks per second of the normal lane
+    pub normal_hz: u32,
+    /// Ticks per second of the low lane
+    pub low_hz: u32,
+}
+
+impl LanedSchedule {
+    /// Priorities run on each tick of the critical lane
+    pub const CRITICAL_LANE: &'static [Priority] = &[Priority::Critical, Priority::High];
+    /// Priorities run on each tick of the normal lane
+    pub const NORMAL_LANE: &'static [Priority] = &[Priority::Normal];
+    /// Priorities run on each tick of the low lane
+    pub const LOW_LANE: &'static [Priority] = &[Priority::Low];
+
+    fn interval(hz: u32) -> Option<tokio::time::Interval> {
+        (hz > 0).then(|| tokio::time::interval(Duration::from_secs(1) / hz))
+    }
+}
+
+/// Wait for the next tick of an optional interval, never completing if it is disabled
+async fn next_tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Type-state marker for an `OatsSystem` that has no systems yet
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoSystems;
+
+/// Type-state marker for an `OatsSystem` with at least one system
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HasSystems;
+
 /// Core OATS system that orchestrates all components
-#[derive(Default)]
-pub struct OatsSystem {
+pub struct OatsSystem<S = NoSystems> {
     objects: Vec<Object>,
     actions: Vec<Box<dyn Action>>,
     systems: Vec<Box<dyn System>>,
+    manager: Option<SystemManager>,
+    tick_rate: Duration,
+    schedule: Option<LanedSchedule>,
+    _state: PhantomData<S>,
+}
+
+impl Default for OatsSystem {
+    fn default() -> Self {
+        Self::with_capacity(0, 0, 0)
+    }
 }
 
 impl OatsSystem {
@@ -45,9 +137,25 @@ impl OatsSystem {
             objects: Vec::with_capacity(objects),
             actions: Vec::with_capacity(actions),
             systems: Vec::with_capacity(systems),
+            manager: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            schedule: None,
+            _state: PhantomData,
         }
     }
 
+    /// Start building an OATS system
+    pub fn builder() -> OatsSystemBuilder {
+        OatsSystemBuilder::new()
+    }
+}
+
+impl<S> OatsSystem<S> {
+    /// Create an OATS system from a configured builder
+    pub fn from_builder(builder: OatsSystemBuilder<S>) -> Self {
+        builder.build()
+    }
+
     /// Add an object to the system
     #[inline]
     pub fn add_object(&mut self, object: Object) {
@@ -60,9 +168,39 @@ impl OatsSystem {
     }
 
     /// Add an action to the system
+    ///
+    /// An action whose name is already registered is skipped with a warning.
+    #[deprecated(note = "these actions are not run by any system; use `add_action_to_system`")]
     #[inline]
     pub fn add_action(&mut self, action: Box<dyn Action>) {
+        if let Err(e) = self.add_action_unique(action) {
+            tracing::warn!("Skipping action: {}", e);
+        }
+    }
+
+    /// Add an action, failing if an action with the same name is already registered
+    pub fn add_action_unique(&mut self, action: Box<dyn Action>) -> Result<()> {
+        if self.actions.iter().any(|existing| existing.name() == action.name()) {
+            return Err(OatsError::invalid_state(format!("action '{}' already registered", action.name())));
+        }
         self.actions.push(action);
+        Ok(())
+    }
+
+    /// Replace the action with the same name, returning it, or add the action if there is none
+    pub fn replace_action(&mut self, action: Box<dyn Action>) -> Option<Box<dyn Action>> {
+        match self.actions.iter_mut().find(|existing| existing.name() == action.name()) {
+            Some(existing) => Some(std::mem::replace(existing, action)),
+            None => {
+                self.actions.push(action);
+                None
+            }
+        }
+    }
+
+    /// Get the names of the registered actions, in registration order
+    pub fn get_action_names(&self) -> Vec<&str> {
+        self.actions.iter().map(|action| action.name()).collect()
     }
 
     /// Add a system to the system
@@ -71,6 +209,26 @@ impl OatsSystem {
         self.systems.push(system);
     }
 
+    /// Attach an action to the named system, downcasting it to its concrete type `T`
+    ///
+    /// Fails with `ObjectNotFound` if no system has that name, or `InvalidState` if it is not a `T`.
+    pub fn add_action_to_system<T>(&mut self, system_name: &str, action: Box<dyn Action>) -> Result<()>
+    where
+        T: System + Extend<Box<dyn Action>>,
+    {
+        let system = self
+            .systems
+            .iter_mut()
+            .find(|system| system.name() == system_name)
+            .ok_or_else(|| OatsError::object_not_found(system_name))?;
+        let system: &mut dyn std::any::Any = &mut **system;
+        let system = system.downcast_mut::<T>().ok_or_else(|| {
+            OatsError::invalid_state(format!("System '{}' is not a {}", system_name, std::any::type_name::<T>()))
+        })?;
+        system.extend(std::iter::once(action));
+        Ok(())
+    }
+
     /// Get all objects in the system
     #[inline]
     pub fn objects(&self) -> &[Object] {
@@ -119,10 +277,19 @@ impl OatsSystem {
         self.actions.clear();
     }
 
-    /// Clear all systems
-    #[inline]
-    pub fn clear_systems(&mut self) {
-        self.systems.clear();
+    /// Clear all systems, moving back to the `NoSystems` state
+    pub fn clear_systems(self) -> OatsSystem<NoSystems> {
+        let mut systems = self.systems;
+        systems.clear();
+        OatsSystem {
+            objects: self.objects,
+            actions: self.actions,
+            systems,
+            manager: self.manager,
+            tick_rate: self.tick_rate,
+            schedule: self.schedule,
+            _state: PhantomData,
+        }
     }
 
     /// Reserve capacity for objects
@@ -142,6 +309,224 @@ impl OatsSystem {
     pub fn reserve_systems(&mut self, additional: usize) {
         self.systems.reserve(additional);
     }
+
+    /// Get the attached system manager, if any
+    #[inline]
+    pub fn manager(&self) -> Option<&SystemManager> {
+        self.manager.as_ref()
+    }
+
+    /// Get the attached system manager mutably, if any
+    #[inline]
+    pub fn manager_mut(&mut self) -> Option<&mut SystemManager> {
+        self.manager.as_mut()
+    }
+
+    /// Get the interval between ticks of `run_forever`
+    #[inline]
+    pub fn tick_rate(&self) -> Duration {
+        self.tick_rate
+    }
+
+    /// Get the laned schedule used by `run_forever`, if configured
+    #[inline]
+    pub fn schedule(&self) -> Option<LanedSchedule> {
+        self.schedule
+    }
+
+    /// Run every system once, highest priority first, followed by the attached manager
+    pub async fn run_once(&mut self) -> Result<Vec<ActionResult>> {
+        let mut all_results = self.run_own_systems(None).await;
+
+        if let Some(manager) = self.manager.as_mut() {
+            all_results.extend(manager.process_all(Priority::Normal).await?);
+        }
+
+        Ok(all_results)
+    }
+
+    /// Run only the systems, including the manager's, whose priority is in `lane`
+    pub async fn run_lane(&mut self, lane: &[Priority]) -> Result<Vec<ActionResult>> {
+        let mut all_results = self.run_own_systems(Some(lane)).await;
+
+        if let Some(manager) = self.manager.as_mut() {
+            for &priority in lane {
+                all_results.extend(manager.process_priority_lane(priority).await?);
+            }
+        }
+
+        Ok(all_results)
+    }
+
+    /// Run this system's own systems, highest priority first, optionally restricted to a lane
+    async fn run_own_systems(&mut self, lane: Option<&[Priority]>) -> Vec<ActionResult> {
+        let mut order: Vec<usize> = (0..self.systems.len())
+            .filter(|&i| lane.is_none_or(|lane| lane.contains(&self.systems[i].priority())))
+            .collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.systems[i].priority()));
+
+        let mut all_results = Vec::new();
+        for i in order {
+            let system = &mut self.systems[i];
+            if !system.is_ready() {
+                continue;
+            }
+            let priority = system.priority();
+            match systems::run_with_hooks(system.as_mut(), &self.objects, priority, None).await {
+                Ok(results) => all_results.extend(results),
+                Err(e) => all_results.push(ActionResult::failure(format!("System error: {}", e))),
+            }
+        }
+        all_results
+    }
+}
+
+impl OatsSystem<HasSystems> {
+    /// Run ticks until `shutdown` is cancelled
+    ///
+    /// With a `LanedSchedule` each priority lane ticks at its own rate; otherwise
+    /// every system runs at the configured tick rate.
+    pub async fn run_forever(&mut self, shutdown: CancellationToken) -> Result<()> {
+        if let Some(schedule) = self.schedule {
+            return self.run_laned(schedule, shutdown).await;
+        }
+
+        let mut interval = tokio::time::interval(self.tick_rate);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return Ok(()),
+                _ = interval.tick() => {
+                    self.run_once().await?;
+                }
+            }
+        }
+    }
+
+    async fn run_laned(&mut self, schedule: LanedSchedule, shutdown: CancellationToken) -> Result<()> {
+        let mut critical = LanedSchedule::interval(schedule.critical_hz);
+        let mut normal = LanedSchedule::interval(schedule.normal_hz);
+        let mut low = LanedSchedule::interval(schedule.low_hz);
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => return Ok(()),
+                _ = next_tick(&mut critical) => {
+                    self.run_lane(LanedSchedule::CRITICAL_LANE).await?;
+                }
+                _ = next_tick(&mut normal) => {
+                    self.run_lane(LanedSchedule::NORMAL_LANE).await?;
+                }
+                _ = next_tick(&mut low) => {
+                    self.run_lane(LanedSchedule::LOW_LANE).await?;
+                }
+            }
+        }
+    }
+}
+
+/// Builder that wires up an `OatsSystem` with its objects, actions, systems, manager and tick schedule
+///
+/// Adding a system moves the builder into the `HasSystems` state, which is
+/// required to call `OatsSystem::run_forever` on the built system.
+pub struct OatsSystemBuilder<S = NoSystems> {
+    objects: Vec<Object>,
+    actions: Vec<Box<dyn Action>>,
+    systems: Vec<Box<dyn System>>,
+    manager: Option<SystemManager>,
+    tick_rate: Duration,
+    schedule: Option<LanedSchedule>,
+    _state: PhantomData<S>,
+}
+
+impl OatsSystemBuilder {
+    /// Create a new builder with no systems
+    pub fn new() -> Self {
+        Self {
+            objects: Vec::new(),
+            actions: Vec::new(),
+            systems: Vec::new(),
+            manager: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            schedule: None,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl Default for OatsSystemBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> OatsSystemBuilder<S> {
+    /// Add an object
+    pub fn with_object(mut self, object: Object) -> Self {
+        self.objects.push(object);
+        self
+    }
+
+    /// Add multiple objects
+    pub fn with_objects(mut self, objects: impl IntoIterator<Item = Object>) -> Self {
+        self.objects.extend(objects);
+        self
+    }
+
+    /// Add an action, skipping it with a warning if its name is already registered
+    pub fn with_action(mut self, action: Box<dyn Action>) -> Self {
+        if self.actions.iter().any(|existing| existing.name() == action.name()) {
+            tracing::warn!("Skipping action: action '{}' already registered", action.name());
+        } else {
+            self.actions.push(action);
+        }
+        self
+    }
+
+    /// Attach a system manager that is processed on every tick
+    pub fn with_manager(mut self, manager: SystemManager) -> Self {
+        self.manager = Some(manager);
+        self
+    }
+
+    /// Set the interval between ticks of `run_forever`
+    pub fn with_tick_rate(mut self, tick_rate: Duration) -> Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Drive `run_forever` with separate tick rates per priority lane
+    pub fn with_laned_schedule(mut self, schedule: LanedSchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Add a system
+    pub fn with_system(self, system: Box<dyn System>) -> OatsSystemBuilder<HasSystems> {
+        let mut systems = self.systems;
+        systems.push(system);
+        OatsSystemBuilder {
+            objects: self.objects,
+            actions: self.actions,
+            systems,
+            manager: self.manager,
+            tick_rate: self.tick_rate,
+            schedule: self.schedule,
+            _state: PhantomData,
+        }
+    }
+
+    /// Build the configured OATS system
+    pub fn build(self) -> OatsSystem<S> {
+        OatsSystem {
+            objects: self.objects,
+            actions: self.actions,
+            systems: self.systems,
+            manager: self.manager,
+            tick_rate: self.tick_rate,
+            schedule: self.schedule,
+            _state: PhantomData,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -185,4 +570,214 @@ mod tests {
         system.reserve_objects(100);
         assert!(system.objects.capacity() >= 103);
     }
-} 
\ No newline at end of file
+
+    struct TickSystem {
+        ticks: usize,
+    }
+
+    struct ActionSystem {
+        actions: Vec<Box<dyn Action>>,
+    }
+
+    #[async_trait::async_trait]
+    impl System for ActionSystem {
+        fn name(&self) -> &str {
+            "actions"
+        }
+
+        fn description(&self) -> &str {
+            "Runs attached actions"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    impl Extend<Box<dyn Action>> for ActionSystem {
+        fn extend<I: IntoIterator<Item = Box<dyn Action>>>(&mut self, actions: I) {
+            self.actions.extend(actions);
+        }
+    }
+
+    struct NoopAction;
+
+    #[async_trait::async_trait]
+    impl Action for NoopAction {
+        fn name(&self) -> &str {
+            "noop"
+        }
+
+        fn description(&self) -> &str {
+            "Does nothing"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            Ok(ActionResult::success())
+        }
+    }
+
+    struct NamedAction(&'static str);
+
+    #[async_trait::async_trait]
+    impl Action for NamedAction {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Does nothing under a given name"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            Ok(ActionResult::success())
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_action_names_are_unique() {
+        let mut system = OatsSystem::new();
+        system.add_action_unique(Box::new(NamedAction("heal"))).unwrap();
+        system.add_action(Box::new(NoopAction));
+
+        let err = system.add_action_unique(Box::new(NamedAction("heal"))).unwrap_err();
+        assert!(matches!(err, OatsError::InvalidState { .. }));
+        assert!(err.to_string().contains("action 'heal' already registered"));
+        system.add_action(Box::new(NoopAction));
+        assert_eq!(system.get_action_names(), vec!["heal", "noop"]);
+
+        assert!(system.replace_action(Box::new(NamedAction("noop"))).is_some());
+        assert!(system.replace_action(Box::new(NamedAction("buff"))).is_none());
+        assert_eq!(system.get_action_names(), vec!["heal", "noop", "buff"]);
+
+        let built = OatsSystem::builder()
+            .with_action(Box::new(NoopAction))
+            .with_action(Box::new(NoopAction))
+            .build();
+        assert_eq!(built.action_count(), 1);
+    }
+
+    #[test]
+    fn test_add_action_to_system() {
+        let mut system = OatsSystem::new();
+        system.add_system(Box::new(ActionSystem { actions: Vec::new() }));
+        system.add_system(Box::new(TickSystem { ticks: 0 }));
+
+        system.add_action_to_system::<ActionSystem>("actions", Box::new(NoopAction)).unwrap();
+        assert!(matches!(
+            system.add_action_to_system::<ActionSystem>("tick", Box::new(NoopAction)),
+            Err(OatsError::InvalidState { .. })
+        ));
+        assert!(matches!(
+            system.add_action_to_system::<ActionSystem>("missing", Box::new(NoopAction)),
+            Err(OatsError::ObjectNotFound { .. })
+        ));
+        assert_eq!(system.action_count(), 0);
+        let attached = system.systems()[0].as_ref() as &dyn std::any::Any;
+        assert_eq!(attached.downcast_ref::<ActionSystem>().unwrap().actions.len(), 1);
+    }
+
+    #[async_trait::async_trait]
+    impl System for TickSystem {
+        fn name(&self) -> &str {
+            "tick"
+        }
+
+        fn description(&self) -> &str {
+            "Counts ticks"
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            self.ticks += 1;
+            Ok(objects.iter().map(|_| ActionResult::success()).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builder_run_once() {
+        let mut system = OatsSystem::from_builder(
+            OatsSystemBuilder::new()
+                .with_object(Object::new("obj", "type"))
+                .with_system(Box::new(TickSystem { ticks: 0 })),
+        );
+
+        let results = system.run_once().await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(system.system_count(), 1);
+
+        let cleared: OatsSystem<NoSystems> = system.clear_systems();
+        assert_eq!(cleared.system_count(), 0);
+        assert_eq!(cleared.object_count(), 1);
+    }
+
+    struct LaneCounter {
+        priority: Priority,
+        ticks: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl System for LaneCounter {
+        fn name(&self) -> &str {
+            "lane_counter"
+        }
+
+        fn description(&self) -> &str {
+            "Counts lane ticks"
+        }
+
+        fn priority(&self) -> Priority {
+            self.priority
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            self.ticks.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_forever_laned_schedule() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // Paused time auto-advances to each timer, so tick counts do not depend on the machine
+        tokio::time::pause();
+        let critical_ticks = Arc::new(AtomicUsize::new(0));
+        let low_ticks = Arc::new(AtomicUsize::new(0));
+        let mut system = OatsSystem::builder()
+            .with_laned_schedule(LanedSchedule { critical_hz: 200, normal_hz: 0, low_hz: 20 })
+            .with_system(Box::new(LaneCounter { priority: Priority::Critical, ticks: critical_ticks.clone() }))
+            .with_system(Box::new(LaneCounter { priority: Priority::Low, ticks: low_ticks.clone() }))
+            .build();
+
+        let shutdown = CancellationToken::new();
+        let trigger = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(198)).await;
+            trigger.cancel();
+        });
+        system.run_forever(shutdown).await.unwrap();
+
+        // Ticks at 0, 5, ..., 195ms and at 0, 50, 100 and 150ms
+        assert_eq!(critical_ticks.load(Ordering::SeqCst), 40);
+        assert_eq!(low_ticks.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_run_forever_stops_on_shutdown() {
+        let mut system = OatsSystem::builder()
+            .with_tick_rate(Duration::from_millis(1))
+            .with_system(Box::new(TickSystem { ticks: 0 }))
+            .build();
+
+        let shutdown = CancellationToken::new();
+        let trigger = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            trigger.cancel();
+        });
+
+        system.run_forever(shutdown).await.unwrap();
+    }
+}
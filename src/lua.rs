@@ -0,0 +1,392 @@
+//! Systems whose `process` body is a Lua script
+//!
+//! A script defines `function process(objects, priority)` and returns a list of results.
+//! Each object is a table with `id`, `name`, `type`, `traits` and `metadata` fields, and
+//! `priority` is the numeric `Priority` value. The global `oats` table provides
+//! `get_trait(obj, name)`, `set_trait(obj, name, value)`, `new_result(success)` and
+//! `add_message(result, msg)`. A result whose `target` field is an object carries that
+//! object's `set_trait` updates; updated objects without such a result get one implicitly.
+
+use async_trait::async_trait;
+use mlua::{Function, Lua, Table, Value};
+use std::path::Path;
+use std::sync::Mutex;
+use crate::systems::{Priority, System, SystemStats};
+use crate::{ActionResult, OatsError, Object, Result, Trait, TraitData};
+
+/// Hidden object field recording the trait names changed through `oats.set_trait`
+const UPDATES_KEY: &str = "__updates";
+
+/// A system that runs a Lua script's `process` function
+pub struct LuaSystem {
+    name: String,
+    description: String,
+    lua: Mutex<Lua>,
+    stats: SystemStats,
+}
+
+impl LuaSystem {
+    /// Load a script from a file, naming the system after the file unless the script sets `name`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let script = std::fs::read_to_string(path)?;
+        let default_name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("lua_system");
+        Self::load(&script, default_name)
+    }
+
+    /// Load a script from a string, named `lua_system` unless the script sets `name`
+    pub fn from_string(script: &str) -> Result<Self> {
+        Self::load(script, "lua_system")
+    }
+
+    /// Override the name taken from the script
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    fn load(script: &str, default_name: &str) -> Result<Self> {
+        let lua = Lua::new();
+        lua.globals().set("oats", oats_api(&lua).map_err(lua_error)?).map_err(lua_error)?;
+        lua.load(script).set_name(default_name).exec().map_err(lua_error)?;
+
+        let (name, description) = {
+            let globals = lua.globals();
+            if globals.get::<_, Option<Function>>("process").map_err(lua_error)?.is_none() {
+                return Err(OatsError::validation_error("Lua script does not define a `process` function"));
+            }
+            (
+                globals.get::<_, Option<String>>("name").map_err(lua_error)?,
+                globals.get::<_, Option<String>>("description").map_err(lua_error)?,
+            )
+        };
+
+        Ok(Self {
+            name: name.unwrap_or_else(|| default_name.to_string()),
+            description: description.unwrap_or_else(|| "Runs a Lua script".to_string()),
+            lua: Mutex::new(lua),
+            stats: SystemStats::default(),
+        })
+    }
+
+    /// Call the script's `process` function and convert what it returns
+    fn run_script(&self, objects: &[Object], priority: Priority) -> Result<Vec<ActionResult>> {
+        let lua = self.lua.lock().unwrap_or_else(|e| e.into_inner());
+        let tables = lua.create_table().map_err(lua_error)?;
+        let mut object_tables = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            let table = object_to_table(&lua, object).map_err(lua_error)?;
+            tables.raw_set(i + 1, table.clone()).map_err(lua_error)?;
+            object_tables.push((object, table));
+        }
+
+        let process: Function = lua.globals().get("process").map_err(lua_error)?;
+        let returned: Table = process.call((tables, priority.level())).map_err(lua_error)?;
+
+        let mut pending = Vec::with_capacity(object_tables.len());
+        for (object, table) in object_tables {
+            pending.push((object.id(), Some(pending_updates(&table)?)));
+        }
+
+        let mut results = Vec::new();
+        for result in returned.sequence_values::<Table>() {
+            let result = result.map_err(lua_error)?;
+            let mut action_result = table_to_result(&result).map_err(lua_error)?;
+            if let Some(target) = result.get::<_, Option<Table>>("target").map_err(lua_error)? {
+                let id: String = target.get("id").map_err(lua_error)?;
+                if let Some((target_id, updates)) = pending.iter_mut().find(|(object_id, _)| object_id.to_string() == id) {
+                    action_result.set_target(*target_id);
+                    action_result.add_trait_updates(updates.take().unwrap_or_default());
+                }
+            }
+            results.push(action_result);
+        }
+
+        for (target_id, updates) in pending {
+            let Some(updates) = updates.filter(|updates| !updates.is_empty()) else { continue };
+            let mut implicit = ActionResult::success();
+            implicit.set_target(target_id);
+            implicit.add_trait_updates(updates);
+            results.push(implicit);
+        }
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl System for LuaSystem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn process(&mut self, objects: &[Object], priority: Priority) -> Result<Vec<ActionResult>> {
+        let start_time = std::time::Instant::now();
+        let outcome = self.run_script(objects, priority);
+
+        self.stats.objects_processed += objects.len() as u64;
+        match &outcome {
+            Ok(results) => {
+                self.stats.actions_executed += results.len() as u64;
+                self.stats.errors += results.iter().filter(|r| r.is_failure()).count() as u64;
+            }
+            Err(_) => self.stats.errors += 1,
+        }
+        self.stats.total_processing_time_ms += start_time.elapsed().as_millis() as u64;
+        self.stats.last_processed = Some(chrono::Utc::now());
+        outcome
+    }
+
+    fn get_stats(&self) -> SystemStats {
+        self.stats.clone()
+    }
+}
+
+fn lua_error(e: mlua::Error) -> OatsError {
+    OatsError::system_error(format!("Lua error: {}", e))
+}
+
+/// Build the global `oats` helper table
+fn oats_api(lua: &Lua) -> mlua::Result<Table<'_>> {
+    let api = lua.create_table()?;
+    api.set(
+        "get_trait",
+        lua.create_function(|_, (object, name): (Table, String)| object.get::<_, Table>("traits")?.get::<_, Value>(name))?,
+    )?;
+    api.set(
+        "set_trait",
+        lua.create_function(|_, (object, name, value): (Table, String, Value)| {
+            lua_to_trait_data(&value)?;
+            object.get::<_, Table>("traits")?.set(name.clone(), value)?;
+            object.get::<_, Table>(UPDATES_KEY)?.set(name, true)
+        })?,
+    )?;
+    api.set(
+        "new_result",
+        lua.create_function(|lua, success: bool| {
+            let result = lua.create_table()?;
+            result.set("success", success)?;
+            result.set("messages", lua.create_table()?)?;
+            Ok(result)
+        })?,
+    )?;
+    api.set(
+        "add_message",
+        lua.create_function(|_, (result, message): (Table, String)| {
+            let messages: Table = result.get("messages")?;
+            messages.raw_set(messages.raw_len() + 1, message)
+        })?,
+    )?;
+    Ok(api)
+}
+
+fn object_to_table<'lua>(lua: &'lua Lua, object: &Object) -> mlua::Result<Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("id", object.id().to_string())?;
+    table.set("name", object.name())?;
+    table.set("type", object.object_type())?;
+
+    let traits = lua.create_table()?;
+    for (name, trait_obj) in object.traits() {
+        traits.set(name.as_str(), trait_data_to_lua(lua, trait_obj.data())?)?;
+    }
+    table.set("traits", traits)?;
+
+    let metadata = lua.create_table()?;
+    for (key, value) in object.metadata() {
+        metadata.set(key.as_str(), value.as_str())?;
+    }
+    table.set("metadata", metadata)?;
+    table.set(UPDATES_KEY, lua.create_table()?)?;
+    Ok(table)
+}
+
+/// Collect the traits an object table changed through `oats.set_trait`, sorted by name
+fn pending_updates(table: &Table) -> Result<Vec<Trait>> {
+    let updated: Table = table.get(UPDATES_KEY).map_err(lua_error)?;
+    let traits: Table = table.get("traits").map_err(lua_error)?;
+    let mut names = updated
+        .pairs::<String, bool>()
+        .map(|pair| pair.map(|(name, _)| name))
+        .collect::<mlua::Result<Vec<_>>>()
+        .map_err(lua_error)?;
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let value: Value = traits.get(name.as_str()).map_err(lua_error)?;
+            Trait::try_new(name, lua_to_trait_data(&value).map_err(lua_error)?)
+        })
+        .collect()
+}
+
+fn table_to_result(table: &Table) -> mlua::Result<ActionResult> {
+    let success = table.get::<_, Option<bool>>("success")?.unwrap_or(false);
+    let mut result = ActionResult {
+        success,
+        status_code: if success { 200 } else { 500 },
+        ..ActionResult::success()
+    };
+    if let Some(messages) = table.get::<_, Option<Table>>("messages")? {
+        for message in messages.sequence_values::<String>() {
+            result.add_message(message?);
+        }
+    }
+    Ok(result)
+}
+
+fn trait_data_to_lua<'lua>(lua: &'lua Lua, data: &TraitData) -> mlua::Result<Value<'lua>> {
+    Ok(match data {
+        TraitData::String(s) => Value::String(lua.create_string(s)?),
+        TraitData::Number(n) => Value::Number(*n),
+        TraitData::Boolean(b) => Value::Boolean(*b),
+        TraitData::Object(map) => {
+            let table = lua.create_table()?;
+            for (key, value) in map {
+                table.set(key.as_str(), json_to_lua(lua, value)?)?;
+            }
+            Value::Table(table)
+        }
+        TraitData::Array(items) => Value::Table(lua.create_sequence_from(
+            items.iter().map(|item| json_to_lua(lua, item)).collect::<mlua::Result<Vec<_>>>()?,
+        )?),
+        TraitData::Binary(bytes) => Value::String(lua.create_string(bytes)?),
+    })
+}
+
+fn lua_to_trait_data(value: &Value) -> mlua::Result<TraitData> {
+    Ok(match value {
+        Value::Boolean(b) => TraitData::Boolean(*b),
+        Value::Integer(i) => TraitData::Number(*i as f64),
+        Value::Number(n) => TraitData::Number(*n),
+        Value::String(s) => match s.to_str() {
+            Ok(s) => TraitData::String(s.to_string()),
+            Err(_) => TraitData::Binary(s.as_bytes().to_vec()),
+        },
+        Value::Table(table) => match table_to_json(table)? {
+            serde_json::Value::Array(items) => TraitData::Array(items),
+            serde_json::Value::Object(map) => TraitData::Object(map.into_iter().collect()),
+            _ => unreachable!("tables convert to arrays or objects"),
+        },
+        other => {
+            return Err(mlua::Error::RuntimeError(format!("cannot store a {} in a trait", other.type_name())))
+        }
+    })
+}
+
+fn json_to_lua<'lua>(lua: &'lua Lua, value: &serde_json::Value) -> mlua::Result<Value<'lua>> {
+    Ok(match value {
+        serde_json::Value::Null => Value::Nil,
+        serde_json::Value::Bool(b) => Value::Boolean(*b),
+        serde_json::Value::Number(n) => Value::Number(n.as_f64().unwrap_or_default()),
+        serde_json::Value::String(s) => Value::String(lua.create_string(s)?),
+        serde_json::Value::Array(items) => Value::Table(lua.create_sequence_from(
+            items.iter().map(|item| json_to_lua(lua, item)).collect::<mlua::Result<Vec<_>>>()?,
+        )?),
+        serde_json::Value::Object(map) => {
+            let table = lua.create_table()?;
+            for (key, value) in map {
+                table.set(key.as_str(), json_to_lua(lua, value)?)?;
+            }
+            Value::Table(table)
+        }
+    })
+}
+
+fn lua_to_json(value: &Value) -> mlua::Result<serde_json::Value> {
+    Ok(match value {
+        Value::Nil => serde_json::Value::Null,
+        Value::Boolean(b) => serde_json::Value::Bool(*b),
+        Value::Integer(i) => serde_json::json!(i),
+        Value::Number(n) => serde_json::Number::from_f64(*n).map_or(serde_json::Value::Null, serde_json::Value::Number),
+        Value::String(s) => serde_json::Value::String(s.to_str()?.to_string()),
+        Value::Table(table) => table_to_json(table)?,
+        other => return Err(mlua::Error::RuntimeError(format!("cannot convert a {} to JSON", other.type_name()))),
+    })
+}
+
+/// Convert a table to a JSON array if its keys are exactly `1..=n`, otherwise to a JSON object
+fn table_to_json(table: &Table) -> mlua::Result<serde_json::Value> {
+    let len = table.raw_len();
+    let pairs = table.clone().pairs::<Value, Value>().collect::<mlua::Result<Vec<_>>>()?;
+    if len > 0 && pairs.len() == len {
+        let items = (1..=len)
+            .map(|i| lua_to_json(&table.raw_get::<_, Value>(i)?))
+            .collect::<mlua::Result<Vec<_>>>()?;
+        return Ok(serde_json::Value::Array(items));
+    }
+
+    let mut map = serde_json::Map::with_capacity(pairs.len());
+    for (key, value) in pairs {
+        let key = match key {
+            Value::String(s) => s.to_str()?.to_string(),
+            Value::Integer(i) => i.to_string(),
+            other => return Err(mlua::Error::RuntimeError(format!("cannot use a {} as a JSON key", other.type_name()))),
+        };
+        map.insert(key, lua_to_json(&value)?);
+    }
+    Ok(serde_json::Value::Object(map))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGEN_SCRIPT: &str = r#"
+        name = "lua_regen"
+
+        function process(objects, priority)
+            local results = {}
+            for _, obj in ipairs(objects) do
+                local health = oats.get_trait(obj, "health")
+                if health ~= nil and health < 50 then
+                    oats.set_trait(obj, "health", health + 10)
+                    local result = oats.new_result(true)
+                    oats.add_message(result, obj.name .. " regenerates at priority " .. priority)
+                    result.target = obj
+                    table.insert(results, result)
+                elseif obj.type == "buff" then
+                    oats.set_trait(obj, "stacks", { 1, 2 })
+                end
+            end
+            return results
+        end
+    "#;
+
+    #[tokio::test]
+    async fn test_lua_system_process() {
+        let mut system = LuaSystem::from_string(REGEN_SCRIPT).unwrap();
+        assert_eq!(system.name(), "lua_regen");
+
+        let mut wounded = Object::new("hero", "player");
+        wounded.add_trait(Trait::new("health", TraitData::Number(40.0)));
+        let mut healthy = Object::new("healer", "player");
+        healthy.add_trait(Trait::new("health", TraitData::Number(90.0)));
+        let haste = Object::new("haste", "buff");
+
+        let results = system.process(&[wounded.clone(), healthy, haste.clone()], Priority::High).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].target_id, Some(wounded.id()));
+        assert_eq!(results[0].messages, vec!["hero regenerates at priority 3".to_string()]);
+        assert_eq!(results[0].trait_updates[0].data, TraitData::Number(50.0));
+        assert_eq!(results[1].target_id, Some(haste.id()));
+        assert_eq!(
+            results[1].trait_updates[0].data,
+            TraitData::Array(vec![serde_json::json!(1), serde_json::json!(2)])
+        );
+        assert_eq!(system.get_stats().objects_processed, 3);
+    }
+
+    #[test]
+    fn test_lua_system_requires_process_function() {
+        assert!(matches!(
+            LuaSystem::from_string("x = 1"),
+            Err(OatsError::ValidationError { .. })
+        ));
+        assert!(LuaSystem::from_string("function process(").is_err());
+    }
+}
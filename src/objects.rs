@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use uuid::Uuid;
-use crate::traits::{Trait, TraitId};
+use crate::traits::{Trait, TraitData, TraitId};
 
 /// Object identifier
 pub type ObjectId = Uuid;
@@ -11,14 +12,20 @@ pub type ObjectId = Uuid;
 pub struct Object {
     /// Unique identifier for this object
     pub id: ObjectId,
-    /// Name of the object
-    pub name: String,
-    /// Type of the object
-    pub object_type: String,
+    /// Name of the object, changed through `rename`
+    pub(crate) name: String,
+    /// Type of the object, changed through `retype`
+    pub(crate) object_type: String,
     /// Traits associated with this object
     pub traits: HashMap<String, Trait>,
     /// Metadata about the object
     pub metadata: HashMap<String, String>,
+    /// Tags used to categorize the object
+    #[serde(default)]
+    pub tags: HashSet<String>,
+    /// Whether the object has been soft-deleted
+    #[serde(default)]
+    pub is_deleted: bool,
     /// Creation timestamp
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// Last update timestamp
@@ -29,18 +36,30 @@ impl Object {
     /// Create a new object with the given name and type
     #[inline]
     pub fn new(name: impl Into<String>, object_type: impl Into<String>) -> Self {
+        Self::with_fixed_id(name, object_type, Uuid::new_v4())
+    }
+
+    /// Create a new object with a caller-chosen ID, e.g. for tests or replay
+    pub fn with_fixed_id(name: impl Into<String>, object_type: impl Into<String>, id: ObjectId) -> Self {
         let now = chrono::Utc::now();
         Self {
-            id: Uuid::new_v4(),
+            id,
             name: name.into(),
             object_type: object_type.into(),
             traits: HashMap::new(),
             metadata: HashMap::new(),
+            tags: HashSet::new(),
+            is_deleted: false,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Create a new object whose ID is a v5 UUID derived from `seed`, so the same seed gives the same ID
+    pub fn with_deterministic_id(name: impl Into<String>, object_type: impl Into<String>, seed: &str) -> Self {
+        Self::with_fixed_id(name, object_type, Uuid::new_v5(&Uuid::NAMESPACE_OID, seed.as_bytes()))
+    }
+
     /// Create a new object with initial traits
     pub fn with_traits(
         name: impl Into<String>,
@@ -66,6 +85,8 @@ impl Object {
             object_type: object_type.into(),
             traits: HashMap::with_capacity(trait_capacity),
             metadata: HashMap::with_capacity(metadata_capacity),
+            tags: HashSet::new(),
+            is_deleted: false,
             created_at: now,
             updated_at: now,
         }
@@ -81,6 +102,201 @@ impl Object {
         &self.object_type
     }
 
+    /// Sort objects by the value of a trait, compared with `TraitData`'s `PartialOrd`
+    ///
+    /// Values that do not compare are treated as equal. Objects whose trait is missing or
+    /// not a finite number sort last, keeping their relative order.
+    pub fn sort_by_trait(objects: &mut [Object], trait_name: &str, ascending: bool) {
+        fn sort_key<'a>(object: &'a Object, trait_name: &str) -> Option<&'a TraitData> {
+            object.get_trait_data(trait_name).filter(|data| data.is_finite_number())
+        }
+        objects.sort_by(|a, b| {
+            match (sort_key(a, trait_name), sort_key(b, trait_name)) {
+                (Some(x), Some(y)) => {
+                    let ordering = x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal);
+                    if ascending { ordering } else { ordering.reverse() }
+                }
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        });
+    }
+
+    /// Copy this object under a fresh ID and timestamps so both can be registered
+    ///
+    /// Its traits get fresh IDs too. `clone` keeps every ID and should be used when the
+    /// copy is the same object.
+    pub fn clone_with_new_id(&self) -> Object {
+        let now = chrono::Utc::now();
+        Object {
+            id: Uuid::new_v4(),
+            traits: copy_traits_with_new_ids(&self.traits),
+            created_at: now,
+            updated_at: now,
+            ..self.clone()
+        }
+    }
+
+    /// Get the trait data differences from this object to `other`
+    pub fn diff(&self, other: &Object) -> ObjectDiff {
+        let (added, removed, changed) = diff_maps(&self.traits, &other.traits, |t| t.data());
+        ObjectDiff { added, removed, changed }
+    }
+
+    /// Apply `patch` operations in order
+    ///
+    /// The patch is atomic: if any operation fails, including a `Test`, the object is left
+    /// unchanged and the error is returned.
+    pub fn apply_patch(&mut self, patch: ObjectPatch) -> Result<(), crate::OatsError> {
+        if patch.is_empty() {
+            return Ok(());
+        }
+        let mut traits = self.traits.clone();
+        for op in patch {
+            apply_patch_op(&mut traits, op)?;
+        }
+        self.traits = traits;
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Compute a patch that turns this object's traits into `target`'s
+    ///
+    /// A removed trait whose data reappears under an added name becomes a `Move`; other
+    /// differences become `Remove`, `Replace` and `Add`. Operations are sorted by trait name.
+    pub fn diff_to_patch(&self, target: &Object) -> ObjectPatch {
+        let diff = self.diff(target);
+        let mut removed: Vec<(String, TraitData)> = diff.removed.into_iter().collect();
+        let mut added: Vec<(String, TraitData)> = diff.added.into_iter().collect();
+        let mut changed: Vec<(String, (TraitData, TraitData))> = diff.changed.into_iter().collect();
+        removed.sort_by(|a, b| a.0.cmp(&b.0));
+        added.sort_by(|a, b| a.0.cmp(&b.0));
+        changed.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut patch = ObjectPatch::new();
+        let mut removes = Vec::new();
+        for (trait_name, data) in removed {
+            match added.iter().position(|(_, added_data)| *added_data == data) {
+                Some(index) => patch.push(PatchOp::Move { from_trait: trait_name, to_trait: added.remove(index).0 }),
+                None => removes.push(PatchOp::Remove { trait_name }),
+            }
+        }
+        patch.extend(removes);
+        patch.extend(changed.into_iter().map(|(trait_name, (_, data))| PatchOp::Replace { trait_name, data }));
+        patch.extend(added.into_iter().map(|(trait_name, data)| PatchOp::Add { trait_name, data }));
+        patch
+    }
+
+    /// Get the metadata differences from this object to `other`
+    pub fn diff_metadata(&self, other: &Object) -> MetadataDiff {
+        let (added, removed, changed) = diff_maps(&self.metadata, &other.metadata, |v| v);
+        MetadataDiff { added, removed, changed }
+    }
+
+    /// Get the trait, metadata and timestamp differences from this object to `other`
+    pub fn full_diff(&self, other: &Object) -> ObjectFullDiff {
+        ObjectFullDiff {
+            traits: self.diff(other),
+            metadata: self.diff_metadata(other),
+            timestamp_changed: self.updated_at != other.updated_at,
+        }
+    }
+
+    /// Serialize just the trait data as a JSON map keyed by trait name
+    ///
+    /// Identity, timestamps, metadata and trait versions are left out, which suits caching.
+    pub fn serialize_traits_only(&self) -> serde_json::Value {
+        let traits = self
+            .traits
+            .iter()
+            .map(|(name, trait_obj)| {
+                (name.clone(), serde_json::to_value(trait_obj.data()).unwrap_or(serde_json::Value::Null))
+            })
+            .collect();
+        serde_json::Value::Object(traits)
+    }
+
+    /// Parse a map produced by `serialize_traits_only` and add its traits to `target`
+    ///
+    /// Nothing is added unless every entry parses, and traits with the same names are replaced.
+    pub fn deserialize_traits_from(value: &serde_json::Value, target: &mut Object) -> Result<(), crate::OatsError> {
+        let map = value
+            .as_object()
+            .ok_or_else(|| crate::OatsError::validation_error("Serialized traits must be a JSON object"))?;
+        let traits = map
+            .iter()
+            .map(|(name, data)| Trait::try_new(name.clone(), serde_json::from_value(data.clone())?))
+            .collect::<Result<Vec<_>, crate::OatsError>>()?;
+        target.add_traits(traits);
+        Ok(())
+    }
+
+    /// Get a SHA-256 hex digest of the object's traits and metadata
+    ///
+    /// Keys are sorted before hashing so equal logical state always gives the same
+    /// checksum; identity, timestamps and trait IDs are not included.
+    pub fn compute_checksum(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let traits: serde_json::Map<String, serde_json::Value> = self
+            .traits
+            .iter()
+            .map(|(name, trait_obj)| {
+                let state = serde_json::json!({
+                    "data": trait_obj.data(),
+                    "version": trait_obj.version(),
+                    "metadata": trait_obj.metadata,
+                });
+                (name.clone(), state)
+            })
+            .collect();
+        let state = serde_json::json!({ "traits": traits, "metadata": self.metadata });
+
+        let mut canonical = String::new();
+        write_canonical_json(&state, &mut canonical);
+        Sha256::digest(canonical.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Check if the object's checksum matches `expected`
+    pub fn verify_checksum(&self, expected: &str) -> bool {
+        self.compute_checksum().eq_ignore_ascii_case(expected)
+    }
+
+    /// Evaluate a boolean expression over this object's boolean traits
+    pub fn evaluate_trait_condition(&self, expr: &BoolExpr) -> bool {
+        expr.evaluate(self)
+    }
+
+    /// Check if the object was updated after `timestamp`
+    #[inline]
+    pub fn updated_since(&self, timestamp: chrono::DateTime<chrono::Utc>) -> bool {
+        self.updated_at > timestamp
+    }
+
+    /// Change the object's display name, keeping its identity
+    pub fn rename(&mut self, new_name: &str) -> Result<(), crate::OatsError> {
+        if new_name.trim().is_empty() {
+            return Err(crate::OatsError::validation_error("Object name cannot be empty"));
+        }
+        self.name = new_name.to_string();
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Change the object's type, keeping its identity
+    pub fn retype(&mut self, new_type: &str) -> Result<(), crate::OatsError> {
+        if new_type.trim().is_empty() {
+            return Err(crate::OatsError::validation_error("Object type cannot be empty"));
+        }
+        self.object_type = new_type.to_string();
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
     /// Get the object ID
     pub fn id(&self) -> ObjectId {
         self.id
@@ -92,6 +308,17 @@ impl Object {
         self.updated_at = chrono::Utc::now();
     }
 
+    /// Add a trait unless a content-equal trait with the same name is already present
+    ///
+    /// Returns whether the trait was added.
+    pub fn add_trait_idempotent(&mut self, trait_obj: Trait) -> bool {
+        if self.traits.get(trait_obj.name()).is_some_and(|existing| existing.content_eq(&trait_obj)) {
+            return false;
+        }
+        self.add_trait(trait_obj);
+        true
+    }
+
     /// Add multiple traits efficiently
     pub fn add_traits(&mut self, traits: impl IntoIterator<Item = Trait>) {
         let mut updated = false;
@@ -134,12 +361,30 @@ impl Object {
         self.traits.get(trait_name)
     }
 
+    /// Look up a nested value inside a trait's object data by dot-separated path
+    #[inline]
+    pub fn get_trait_path(&self, trait_name: &str, path: &str) -> Option<&serde_json::Value> {
+        self.traits.get(trait_name)?.data().get_path(path)
+    }
+
     /// Get a trait by name (mutable)
     #[inline]
     pub fn get_trait_mut(&mut self, trait_name: &str) -> Option<&mut Trait> {
         self.traits.get_mut(trait_name)
     }
 
+    /// Remove every trait whose expiry time has passed, returning how many were removed
+    pub fn purge_expired_traits(&mut self) -> usize {
+        let now = chrono::Utc::now();
+        let before = self.traits.len();
+        self.traits.retain(|_, trait_obj| !trait_obj.is_expired_at(now));
+        let purged = before - self.traits.len();
+        if purged > 0 {
+            self.updated_at = now;
+        }
+        purged
+    }
+
     /// Get trait data by name (zero-copy access)
     #[inline]
     pub fn get_trait_data(&self, trait_name: &str) -> Option<&crate::traits::TraitData> {
@@ -152,6 +397,68 @@ impl Object {
         self.traits.get_mut(trait_name).map(|t| t.data_mut())
     }
 
+    /// Set a numeric trait, creating it if it does not exist
+    ///
+    /// NaN and infinite values are rejected with a validation error.
+    pub fn set_trait_number(&mut self, name: &str, value: f64) -> Result<(), crate::OatsError> {
+        let checked = Trait::try_new(name, TraitData::Number(value))?;
+        match self.traits.get_mut(name) {
+            Some(trait_obj) => *trait_obj.data_mut() = checked.data,
+            None => {
+                self.traits.insert(name.to_string(), checked);
+            }
+        }
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Increment a numeric trait, returning the new value
+    pub fn increment_trait(&mut self, name: &str, by: f64) -> Result<f64, crate::OatsError> {
+        let new_value = self.trait_number(name)? + by;
+        self.set_trait_number(name, new_value)?;
+        Ok(new_value)
+    }
+
+    /// Decrement a numeric trait without going below `min`, returning the new value
+    pub fn decrement_trait(&mut self, name: &str, by: f64, min: f64) -> Result<f64, crate::OatsError> {
+        let new_value = (self.trait_number(name)? - by).max(min);
+        self.set_trait_number(name, new_value)?;
+        Ok(new_value)
+    }
+
+    /// Append an item to an array trait
+    pub fn push_trait_array_item(&mut self, trait_name: &str, item: serde_json::Value) -> Result<(), crate::OatsError> {
+        self.get_trait_data_mut(trait_name)
+            .ok_or_else(|| crate::OatsError::trait_not_found(trait_name))?
+            .push_array_item(item)?;
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Deserialize a field of an object-valued trait
+    pub fn get_object_trait_field<T: serde::de::DeserializeOwned>(&self, trait_name: &str, field: &str) -> Result<T, crate::OatsError> {
+        self.get_trait_data(trait_name)
+            .ok_or_else(|| crate::OatsError::trait_not_found(trait_name))?
+            .get_field(field)
+    }
+
+    /// Shallow-merge fields into an object-valued trait
+    pub fn update_trait_fields(&mut self, trait_name: &str, fields: HashMap<String, serde_json::Value>) -> Result<(), crate::OatsError> {
+        self.get_trait_data_mut(trait_name)
+            .ok_or_else(|| crate::OatsError::trait_not_found(trait_name))?
+            .merge_fields(&fields)?;
+        self.updated_at = chrono::Utc::now();
+        Ok(())
+    }
+
+    /// Read a numeric trait value, failing if it is missing or not numeric
+    fn trait_number(&self, name: &str) -> Result<f64, crate::OatsError> {
+        self.get_trait_data(name)
+            .ok_or_else(|| crate::OatsError::trait_not_found(name))?
+            .as_number()
+            .ok_or_else(|| crate::OatsError::invalid_state(format!("Trait '{}' is not numeric", name)))
+    }
+
     /// Get all traits
     #[inline]
     pub fn traits(&self) -> &HashMap<String, Trait> {
@@ -170,6 +477,16 @@ impl Object {
         trait_names.iter().all(|name| self.traits.contains_key(*name))
     }
 
+    /// Check whether any trait satisfies the given predicate
+    pub fn any_trait_matches(&self, pred: impl Fn(&str, &TraitData) -> bool) -> bool {
+        self.traits.values().any(|t| pred(&t.name, &t.data))
+    }
+
+    /// Get all traits that satisfy the given predicate
+    pub fn traits_matching(&self, pred: impl Fn(&str, &TraitData) -> bool) -> Vec<&Trait> {
+        self.traits.values().filter(|t| pred(&t.name, &t.data)).collect()
+    }
+
     /// Check if the object has any traits
     #[inline]
     pub fn has_any_traits(&self) -> bool {
@@ -195,6 +512,56 @@ impl Object {
         &self.metadata
     }
 
+    /// Add a tag to this object
+    #[inline]
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        if self.tags.insert(tag.into()) {
+            self.updated_at = chrono::Utc::now();
+        }
+    }
+
+    /// Remove a tag from this object
+    #[inline]
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let removed = self.tags.remove(tag);
+        if removed {
+            self.updated_at = chrono::Utc::now();
+        }
+        removed
+    }
+
+    /// Check if the object has a specific tag
+    #[inline]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Get all tags
+    #[inline]
+    pub fn tags(&self) -> &HashSet<String> {
+        &self.tags
+    }
+
+    /// Mark the object as deleted while keeping it around for auditing
+    #[inline]
+    pub fn soft_delete(&mut self) {
+        self.is_deleted = true;
+        self.updated_at = chrono::Utc::now();
+    }
+
+    /// Restore a soft-deleted object
+    #[inline]
+    pub fn restore_deleted(&mut self) {
+        self.is_deleted = false;
+        self.updated_at = chrono::Utc::now();
+    }
+
+    /// Check if the object has been soft-deleted
+    #[inline]
+    pub fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+
     /// Get the creation timestamp
     #[inline]
     pub fn created_at(&self) -> chrono::DateTime<chrono::Utc> {
@@ -213,6 +580,24 @@ impl Object {
         self.traits.keys().collect()
     }
 
+    /// Iterate `(name, data)` pairs of every trait without allocating
+    #[inline]
+    pub fn trait_iter(&self) -> impl Iterator<Item = (&str, &TraitData)> {
+        self.traits.iter().map(|(name, trait_obj)| (name.as_str(), trait_obj.data()))
+    }
+
+    /// Iterate the data of every trait without allocating
+    #[inline]
+    pub fn trait_values_iter(&self) -> impl Iterator<Item = &TraitData> {
+        self.traits.values().map(Trait::data)
+    }
+
+    /// Iterate trait names without allocating
+    #[inline]
+    pub fn trait_names_iter(&self) -> impl Iterator<Item = &str> {
+        self.traits.keys().map(String::as_str)
+    }
+
     /// Get trait IDs as a vector
     #[inline]
     pub fn trait_ids(&self) -> Vec<TraitId> {
@@ -243,6 +628,23 @@ impl Object {
         self.metadata.reserve(additional);
     }
 
+    /// Approximate bytes used by this object, for memory budgets rather than exact accounting
+    ///
+    /// Sums the struct size, name, type, each trait keyed by name, metadata and tags.
+    pub fn estimated_memory_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.name.len()
+            + self.object_type.len()
+            + self.traits.iter().map(|(k, t)| k.len() + t.estimated_memory_size()).sum::<usize>()
+            + self.metadata.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>()
+            + self.tags.iter().map(String::len).sum::<usize>()
+    }
+
+    /// Check this object against a schema, collecting every violation rather than stopping at the first
+    pub fn validate_with_schema(&self, schema: &crate::validation::ObjectSchema) -> Vec<crate::validation::ValidationError> {
+        schema.validate(self)
+    }
+
     /// Validate that the object has required traits
     pub fn validate_required_traits(&self, required_traits: &[&str]) -> Result<(), crate::OatsError> {
         let missing: Vec<_> = required_traits
@@ -259,12 +661,36 @@ impl Object {
         Ok(())
     }
 
+    /// Capture this object's type, traits, metadata and tags as a reusable template
+    pub fn as_template(&self) -> ObjectTemplate {
+        ObjectTemplate {
+            object_type: self.object_type.clone(),
+            traits: self.traits.clone(),
+            metadata: self.metadata.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+
     /// Check if the object is valid (has required fields)
     pub fn is_valid(&self) -> bool {
         !self.name.is_empty() && !self.object_type.is_empty()
     }
 }
 
+impl std::fmt::Display for Object {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let id = self.id.simple().to_string();
+        write!(
+            f,
+            "Object(id={}, name={}, type={}, traits={})",
+            &id[..8],
+            self.name,
+            self.object_type,
+            self.traits.len()
+        )
+    }
+}
+
 impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -279,11 +705,497 @@ impl std::hash::Hash for Object {
     }
 }
 
+/// Write JSON with object keys sorted at every level
+fn write_canonical_json(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(value, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Add a tag to every object in the slice
+pub fn tag_objects(objects: &mut [Object], tag: &str) {
+    for object in objects {
+        object.add_tag(tag);
+    }
+}
+
+/// Remove a tag from every object in the slice
+pub fn untag_objects(objects: &mut [Object], tag: &str) {
+    for object in objects {
+        object.remove_tag(tag);
+    }
+}
+
+/// Add a copy of a trait to every object in the slice, replacing any trait with the same name
+pub fn set_trait_on_objects(objects: &mut [Object], trait_obj: Trait) {
+    for object in objects {
+        object.add_trait(trait_obj.clone());
+    }
+}
+
+/// Iterate over the objects in the slice that have a tag
+pub fn filter_objects_by_tag<'a>(objects: &'a [Object], tag: &str) -> impl Iterator<Item = &'a Object> {
+    let tag = tag.to_string();
+    objects.iter().filter(move |object| object.has_tag(&tag))
+}
+
+/// A prototype for creating objects that share the same traits
+///
+/// Templates carry no identity or timestamps; each instantiated object gets its own, and
+/// so does each of its traits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectTemplate {
+    /// Type of the objects created from this template
+    pub object_type: String,
+    /// Traits copied into each new object
+    pub traits: HashMap<String, Trait>,
+    /// Metadata copied into each new object
+    pub metadata: HashMap<String, String>,
+    /// Tags copied into each new object
+    #[serde(default)]
+    pub tags: HashSet<String>,
+}
+
+impl ObjectTemplate {
+    /// Create a new object from this template
+    pub fn instantiate(&self, name: &str) -> Object {
+        let mut object = Object::new(name, self.object_type.clone());
+        object.traits = copy_traits_with_new_ids(&self.traits);
+        object.metadata = self.metadata.clone();
+        object.tags = self.tags.clone();
+        object
+    }
+
+    /// Create one object per name from this template
+    pub fn instantiate_many(&self, names: &[&str]) -> Vec<Object> {
+        names.iter().map(|name| self.instantiate(name)).collect()
+    }
+}
+
+/// Copy traits, giving each copy a fresh ID
+fn copy_traits_with_new_ids(traits: &HashMap<String, Trait>) -> HashMap<String, Trait> {
+    traits
+        .iter()
+        .map(|(name, t)| (name.clone(), Trait { id: Uuid::new_v4(), ..t.clone() }))
+        .collect()
+}
+
+/// Trait data differences between two versions of an object
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObjectDiff {
+    /// Traits only present in the newer object
+    pub added: HashMap<String, TraitData>,
+    /// Traits only present in the older object
+    pub removed: HashMap<String, TraitData>,
+    /// Traits whose data changed, as `(old, new)`
+    pub changed: HashMap<String, (TraitData, TraitData)>,
+}
+
+impl ObjectDiff {
+    /// Check if no traits differ
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Metadata differences between two versions of an object
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataDiff {
+    /// Entries only present in the newer object
+    pub added: HashMap<String, String>,
+    /// Entries only present in the older object
+    pub removed: HashMap<String, String>,
+    /// Entries whose value changed, as `(old, new)`
+    pub changed: HashMap<String, (String, String)>,
+}
+
+impl MetadataDiff {
+    /// Check if no metadata differs
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Trait and metadata differences between two versions of an object
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObjectFullDiff {
+    /// Trait differences
+    pub traits: ObjectDiff,
+    /// Metadata differences
+    pub metadata: MetadataDiff,
+    /// Whether `updated_at` differs
+    pub timestamp_changed: bool,
+}
+
+/// One operation of an `ObjectPatch`, modelled on JSON Patch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PatchOp {
+    /// Add a trait, replacing any trait with the same name
+    Add { trait_name: String, data: TraitData },
+    /// Remove an existing trait
+    Remove { trait_name: String },
+    /// Replace the data of an existing trait, keeping its metadata
+    Replace { trait_name: String, data: TraitData },
+    /// Rename an existing trait, replacing any trait with the new name
+    Move { from_trait: String, to_trait: String },
+    /// Add a new trait holding a copy of an existing trait's data
+    Copy { from_trait: String, to_trait: String },
+    /// Check that a trait holds `expected`, failing the whole patch otherwise
+    Test { trait_name: String, expected: TraitData },
+}
+
+/// Operations applied in order by `Object::apply_patch`
+pub type ObjectPatch = Vec<PatchOp>;
+
+/// Apply one patch operation to a set of traits
+fn apply_patch_op(traits: &mut HashMap<String, Trait>, op: PatchOp) -> Result<(), crate::OatsError> {
+    use crate::OatsError;
+    match op {
+        PatchOp::Add { trait_name, data } => {
+            traits.insert(trait_name.clone(), Trait::try_new(trait_name, data)?);
+        }
+        PatchOp::Remove { trait_name } => {
+            traits.remove(&trait_name).ok_or_else(|| OatsError::trait_not_found(&trait_name))?;
+        }
+        PatchOp::Replace { trait_name, data } => {
+            let checked = Trait::try_new(trait_name.as_str(), data)?;
+            let existing = traits.get_mut(&trait_name).ok_or_else(|| OatsError::trait_not_found(&trait_name))?;
+            *existing.data_mut() = checked.data;
+        }
+        PatchOp::Move { from_trait, to_trait } => {
+            let mut moved = traits.remove(&from_trait).ok_or_else(|| OatsError::trait_not_found(&from_trait))?;
+            moved.name = to_trait.clone();
+            traits.insert(to_trait, moved);
+        }
+        PatchOp::Copy { from_trait, to_trait } => {
+            let data = traits
+                .get(&from_trait)
+                .map(|t| t.data().clone())
+                .ok_or_else(|| OatsError::trait_not_found(&from_trait))?;
+            traits.insert(to_trait.clone(), Trait::try_new(to_trait, data)?);
+        }
+        PatchOp::Test { trait_name, expected } => match traits.get(&trait_name) {
+            Some(actual) if actual.data() == &expected => {}
+            Some(actual) => {
+                return Err(OatsError::validation_error(format!(
+                    "Patch test failed: trait '{}' is {}, expected {}",
+                    trait_name,
+                    actual.data(),
+                    expected
+                )))
+            }
+            None => {
+                return Err(OatsError::validation_error(format!(
+                    "Patch test failed: trait '{}' is missing",
+                    trait_name
+                )))
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Added, removed and changed entries between two maps
+type MapDiff<T> = (HashMap<String, T>, HashMap<String, T>, HashMap<String, (T, T)>);
+
+/// Compare two maps, cloning values through `value` into added, removed and changed entries
+fn diff_maps<V, T: Clone + PartialEq>(
+    old: &HashMap<String, V>,
+    new: &HashMap<String, V>,
+    value: impl Fn(&V) -> &T,
+) -> MapDiff<T> {
+    let mut added = HashMap::new();
+    let mut removed = HashMap::new();
+    let mut changed = HashMap::new();
+    for (key, old_value) in old {
+        match new.get(key) {
+            None => {
+                removed.insert(key.clone(), value(old_value).clone());
+            }
+            Some(new_value) if value(new_value) != value(old_value) => {
+                changed.insert(key.clone(), (value(old_value).clone(), value(new_value).clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            added.insert(key.clone(), value(new_value).clone());
+        }
+    }
+    (added, removed, changed)
+}
+
+/// Boxed predicate over a trait's name and data
+pub type TraitPredicate = Box<dyn Fn(&str, &TraitData) -> bool + Send + Sync>;
+
+/// A composable predicate over objects
+///
+/// Filters combine with `&`, `|` and `!`:
+/// `ObjectFilter::has_trait("health") & ObjectFilter::has_trait("position")`.
+#[derive(Clone)]
+pub struct ObjectFilter {
+    predicate: Arc<dyn Fn(&Object) -> bool + Send + Sync>,
+}
+
+impl ObjectFilter {
+    /// Create a filter from a predicate
+    pub fn new(predicate: impl Fn(&Object) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// Check if an object matches this filter
+    #[inline]
+    pub fn matches(&self, object: &Object) -> bool {
+        (self.predicate)(object)
+    }
+
+    /// Match objects that have the named trait
+    pub fn has_trait(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::new(move |object| object.has_trait(&name))
+    }
+
+    /// Match objects of the given type
+    pub fn object_type(object_type: impl Into<String>) -> Self {
+        let object_type = object_type.into();
+        Self::new(move |object| object.object_type() == object_type)
+    }
+
+    /// Match objects whose numeric trait is greater than `threshold`
+    pub fn trait_number_gt(name: impl Into<String>, threshold: f64) -> Self {
+        let name = name.into();
+        Self::new(move |object| {
+            object
+                .get_trait_data(&name)
+                .and_then(|data| data.as_number())
+                .is_some_and(|value| value > threshold)
+        })
+    }
+
+    /// Match objects whose numeric trait is less than `threshold`
+    pub fn trait_number_lt(name: impl Into<String>, threshold: f64) -> Self {
+        let name = name.into();
+        Self::new(move |object| {
+            object
+                .get_trait_data(&name)
+                .and_then(|data| data.as_number())
+                .is_some_and(|value| value < threshold)
+        })
+    }
+
+    /// Match objects carrying the given tag
+    pub fn tag(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::new(move |object| object.has_tag(&name))
+    }
+
+    /// Match objects with at least one trait satisfying the predicate
+    pub fn trait_matches(pred: TraitPredicate) -> Self {
+        Self::new(move |object| object.any_trait_matches(&pred))
+    }
+
+    /// Match every object
+    pub fn always() -> Self {
+        Self::new(|_| true)
+    }
+
+    /// Match no object
+    pub fn never() -> Self {
+        Self::new(|_| false)
+    }
+}
+
+impl std::fmt::Debug for ObjectFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectFilter").finish_non_exhaustive()
+    }
+}
+
+impl std::ops::BitAnd for ObjectFilter {
+    type Output = ObjectFilter;
+
+    fn bitand(self, rhs: ObjectFilter) -> ObjectFilter {
+        ObjectFilter::new(move |object| self.matches(object) && rhs.matches(object))
+    }
+}
+
+impl std::ops::BitOr for ObjectFilter {
+    type Output = ObjectFilter;
+
+    fn bitor(self, rhs: ObjectFilter) -> ObjectFilter {
+        ObjectFilter::new(move |object| self.matches(object) || rhs.matches(object))
+    }
+}
+
+impl std::ops::Not for ObjectFilter {
+    type Output = ObjectFilter;
+
+    fn not(self) -> ObjectFilter {
+        ObjectFilter::new(move |object| !self.matches(object))
+    }
+}
+
+/// Boolean expression over an object's boolean traits
+///
+/// Combine expressions with `&`, `|` and `!`. A trait that is missing or not a boolean
+/// evaluates to false.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoolExpr {
+    /// Value of a boolean trait
+    Trait(String),
+    /// Both expressions hold
+    And(Box<BoolExpr>, Box<BoolExpr>),
+    /// Either expression holds
+    Or(Box<BoolExpr>, Box<BoolExpr>),
+    /// The expression does not hold
+    Not(Box<BoolExpr>),
+}
+
+impl BoolExpr {
+    /// Create an expression reading a boolean trait
+    pub fn flag(name: impl Into<String>) -> Self {
+        BoolExpr::Trait(name.into())
+    }
+
+    /// Evaluate the expression against an object
+    pub fn evaluate(&self, object: &Object) -> bool {
+        match self {
+            BoolExpr::Trait(name) => object.get_trait_data(name).and_then(|d| d.as_boolean()).unwrap_or(false),
+            BoolExpr::And(a, b) => a.evaluate(object) && b.evaluate(object),
+            BoolExpr::Or(a, b) => a.evaluate(object) || b.evaluate(object),
+            BoolExpr::Not(expr) => !expr.evaluate(object),
+        }
+    }
+}
+
+impl std::ops::BitAnd for BoolExpr {
+    type Output = BoolExpr;
+
+    fn bitand(self, rhs: BoolExpr) -> BoolExpr {
+        BoolExpr::And(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::BitOr for BoolExpr {
+    type Output = BoolExpr;
+
+    fn bitor(self, rhs: BoolExpr) -> BoolExpr {
+        BoolExpr::Or(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::Not for BoolExpr {
+    type Output = BoolExpr;
+
+    fn not(self) -> BoolExpr {
+        BoolExpr::Not(Box::new(self))
+    }
+}
+
+/// Objects that convert to the domain type `T`, with typed access to them
+///
+/// Each object is converted once, by reference, when the collection is built; the
+/// objects themselves are moved in rather than cloned.
+pub struct TypedObjectCollection<T: for<'a> TryFrom<&'a Object>> {
+    objects: Vec<Object>,
+    values: Vec<T>,
+}
+
+impl<T: for<'a> TryFrom<&'a Object>> TypedObjectCollection<T> {
+    /// Collect the objects that convert to `T`, dropping the rest
+    pub fn from_objects(objects: impl IntoIterator<Item = Object>) -> Self {
+        let (objects, values) = objects
+            .into_iter()
+            .filter_map(|o| T::try_from(&o).ok().map(|value| (o, value)))
+            .unzip();
+        Self { objects, values }
+    }
+
+    /// Iterate over the converted values
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.values.iter()
+    }
+
+    /// Get the number of objects in the collection
+    pub fn count(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Keep only the objects matching `pred`, along with their converted values
+    pub fn filter(self, pred: impl Fn(&Object) -> bool) -> TypedObjectCollection<T> {
+        let (objects, values) = self.objects.into_iter().zip(self.values).filter(|(o, _)| pred(o)).unzip();
+        Self { objects, values }
+    }
+
+    /// Get the underlying objects
+    pub fn objects(&self) -> &[Object] {
+        &self.objects
+    }
+}
+
+impl<T: for<'a> TryFrom<&'a Object>> IntoIterator for TypedObjectCollection<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Take the converted values
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::traits::{Trait, TraitData};
 
+    struct Player {
+        name: String,
+        health: f64,
+    }
+
+    impl TryFrom<&Object> for Player {
+        type Error = crate::OatsError;
+
+        fn try_from(object: &Object) -> Result<Self, Self::Error> {
+            if object.object_type() != "player" {
+                return Err(crate::OatsError::validation_error("Not a player"));
+            }
+            let health = object
+                .get_trait_data("health")
+                .and_then(|d| d.as_number())
+                .ok_or_else(|| crate::OatsError::trait_not_found("health"))?;
+            Ok(Player { name: object.name.clone(), health })
+        }
+    }
+
     #[test]
     fn test_object_creation() {
         let obj = Object::new("test_object", "test_type");
@@ -294,6 +1206,102 @@ mod tests {
         assert!(!obj.has_any_traits());
     }
 
+    #[test]
+    fn test_apply_patch() {
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(Trait::new("health", TraitData::Number(100.0)));
+        hero.add_trait(Trait::new("hp_label", TraitData::String("HP".to_string())));
+
+        hero.apply_patch(vec![
+            PatchOp::Test { trait_name: "health".to_string(), expected: TraitData::Number(100.0) },
+            PatchOp::Replace { trait_name: "health".to_string(), data: TraitData::Number(80.0) },
+            PatchOp::Copy { from_trait: "health".to_string(), to_trait: "max_health".to_string() },
+            PatchOp::Move { from_trait: "hp_label".to_string(), to_trait: "label".to_string() },
+            PatchOp::Add { trait_name: "alive".to_string(), data: TraitData::Boolean(true) },
+            PatchOp::Remove { trait_name: "alive".to_string() },
+        ])
+        .unwrap();
+        assert_eq!(hero.get_trait_data("max_health"), Some(&TraitData::Number(80.0)));
+        assert_eq!(hero.get_trait("label").unwrap().name(), "label");
+        assert!(!hero.has_trait("hp_label") && !hero.has_trait("alive"));
+
+        let before = hero.clone();
+        let failed = hero.apply_patch(vec![
+            PatchOp::Remove { trait_name: "label".to_string() },
+            PatchOp::Test { trait_name: "health".to_string(), expected: TraitData::Number(1.0) },
+        ]);
+        assert!(matches!(failed, Err(crate::OatsError::ValidationError { .. })));
+        assert!(hero.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_diff_to_patch() {
+        let mut source = Object::new("hero", "player");
+        source.add_trait(Trait::new("health", TraitData::Number(100.0)));
+        source.add_trait(Trait::new("mana", TraitData::Number(5.0)));
+        source.add_trait(Trait::new("title", TraitData::String("Squire".to_string())));
+
+        let mut target = Object::new("hero", "player");
+        target.add_trait(Trait::new("health", TraitData::Number(60.0)));
+        target.add_trait(Trait::new("rank", TraitData::String("Squire".to_string())));
+        target.add_trait(Trait::new("stamina", TraitData::Number(10.0)));
+
+        let patch = source.diff_to_patch(&target);
+        assert_eq!(
+            patch,
+            vec![
+                PatchOp::Move { from_trait: "title".to_string(), to_trait: "rank".to_string() },
+                PatchOp::Remove { trait_name: "mana".to_string() },
+                PatchOp::Replace { trait_name: "health".to_string(), data: TraitData::Number(60.0) },
+                PatchOp::Add { trait_name: "stamina".to_string(), data: TraitData::Number(10.0) },
+            ]
+        );
+        source.apply_patch(patch).unwrap();
+        assert!(source.diff(&target).is_empty());
+    }
+
+    #[test]
+    fn test_trait_iterators() {
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(Trait::new("health", TraitData::Number(100.0)));
+        hero.add_trait(Trait::new("alive", TraitData::Boolean(true)));
+
+        let mut pairs: Vec<(&str, &TraitData)> = hero.trait_iter().collect();
+        pairs.sort_by_key(|(name, _)| *name);
+        assert_eq!(pairs, vec![("alive", &TraitData::Boolean(true)), ("health", &TraitData::Number(100.0))]);
+
+        let mut names: Vec<&str> = hero.trait_names_iter().collect();
+        names.sort();
+        assert_eq!(names, vec!["alive", "health"]);
+        assert_eq!(hero.trait_values_iter().filter_map(TraitData::as_number).sum::<f64>(), 100.0);
+    }
+
+    #[test]
+    fn test_get_trait_path() {
+        let mut order = TraitData::Object(HashMap::new());
+        order.set_path("status", serde_json::json!("processing")).unwrap();
+        order.set_path("shipping.address.zip", serde_json::json!("10001")).unwrap();
+        let mut customer = Object::new("alice", "customer");
+        customer.add_trait(Trait::new("current_order", order));
+
+        assert_eq!(customer.get_trait_path("current_order", "status"), Some(&serde_json::json!("processing")));
+        assert_eq!(customer.get_trait_path("current_order", "shipping.address.zip"), Some(&serde_json::json!("10001")));
+        assert_eq!(customer.get_trait_path("missing", "status"), None);
+    }
+
+    #[test]
+    fn test_fixed_and_deterministic_ids() {
+        let id = Uuid::parse_str("6f1c2b8e-4d3a-4f5e-9a7b-1c2d3e4f5a6b").unwrap();
+        assert_eq!(Object::with_fixed_id("hero", "player", id).id(), id);
+
+        let first = Object::with_deterministic_id("hero", "player", "hero-1");
+        let second = Object::with_deterministic_id("other", "npc", "hero-1");
+        assert_eq!(first.id(), second.id());
+        assert_eq!(first.id(), Uuid::new_v5(&Uuid::NAMESPACE_OID, b"hero-1"));
+        assert_ne!(first.id(), Object::with_deterministic_id("hero", "player", "hero-2").id());
+        assert_eq!(first.created_at, first.updated_at);
+    }
+
     #[test]
     fn test_object_with_traits() {
         let trait1 = Trait::new("health", TraitData::Number(100.0));
@@ -346,4 +1354,414 @@ mod tests {
         let ids = obj.trait_ids();
         assert_eq!(ids.len(), 2);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_numeric_trait_updates() {
+        let mut obj = Object::new("product", "item");
+        obj.set_trait_number("stock", 5.0).unwrap();
+        assert!(obj.set_trait_number("stock", f64::INFINITY).is_err());
+
+        assert_eq!(obj.increment_trait("stock", 3.0).unwrap(), 8.0);
+        assert_eq!(obj.decrement_trait("stock", 10.0, 0.0).unwrap(), 0.0);
+        assert_eq!(obj.get_trait_data("stock").and_then(|d| d.as_number()), Some(0.0));
+
+        assert!(matches!(
+            obj.increment_trait("missing", 1.0),
+            Err(crate::OatsError::TraitNotFound { .. })
+        ));
+
+        obj.add_trait(Trait::new("label", TraitData::String("box".to_string())));
+        assert!(matches!(
+            obj.decrement_trait("label", 1.0, 0.0),
+            Err(crate::OatsError::InvalidState { .. })
+        ));
+    }
+
+    #[test]
+    fn test_push_trait_array_item() {
+        let mut customer = Object::new("john_doe", "customer");
+        customer.add_trait(Trait::new("order_history", TraitData::Array(Vec::new())));
+        customer.push_trait_array_item("order_history", serde_json::json!("ORD-001")).unwrap();
+
+        assert_eq!(customer.get_trait_data("order_history").and_then(|d| d.array_len()), Some(1));
+        assert!(matches!(
+            customer.push_trait_array_item("wishlist", serde_json::json!("ORD-002")),
+            Err(crate::OatsError::TraitNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_get_object_trait_field() {
+        let mut address = TraitData::Object(HashMap::new());
+        address.set_field("city", "Lisbon").unwrap();
+        let mut customer = Object::new("john_doe", "customer");
+        customer.add_trait(Trait::new("address", address));
+
+        assert_eq!(customer.get_object_trait_field::<String>("address", "city").unwrap(), "Lisbon");
+
+        customer
+            .update_trait_fields("address", HashMap::from([("city".to_string(), serde_json::json!("Porto"))]))
+            .unwrap();
+        assert_eq!(customer.get_object_trait_field::<String>("address", "city").unwrap(), "Porto");
+        assert!(customer.update_trait_fields("billing", HashMap::new()).is_err());
+        assert!(matches!(
+            customer.get_object_trait_field::<String>("billing", "city"),
+            Err(crate::OatsError::TraitNotFound { .. })
+        ));
+        assert!(matches!(
+            customer.get_object_trait_field::<String>("address", "zip"),
+            Err(crate::OatsError::ObjectNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rename_and_retype() {
+        let mut object = Object::new("prototype", "item");
+        let id = object.id();
+        let updated_at = object.updated_at;
+
+        object.rename("sword").unwrap();
+        object.retype("weapon").unwrap();
+        assert_eq!(object.name(), "sword");
+        assert_eq!(object.object_type(), "weapon");
+        assert_eq!(object.id(), id);
+        assert!(object.updated_at >= updated_at);
+
+        assert!(matches!(object.rename("  "), Err(crate::OatsError::ValidationError { .. })));
+        assert!(object.retype("").is_err());
+        assert_eq!(object.name(), "sword");
+    }
+
+    #[test]
+    fn test_sort_by_trait() {
+        let score = |name: &str, value: Option<f64>| {
+            let mut player = Object::new(name, "player");
+            if let Some(value) = value {
+                player.add_trait(Trait::new("score", TraitData::Number(value)));
+            }
+            player
+        };
+        let mut players = vec![score("ann", Some(20.0)), score("bob", None), score("cid", Some(50.0))];
+
+        Object::sort_by_trait(&mut players, "score", false);
+        let names: Vec<&str> = players.iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["cid", "ann", "bob"]);
+
+        Object::sort_by_trait(&mut players, "score", true);
+        let names: Vec<&str> = players.iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["ann", "cid", "bob"]);
+    }
+
+    #[test]
+    fn test_sort_by_trait_mixed_variants() {
+        let tagged = |name: &str, data: TraitData| Object::with_traits(name, "player", vec![Trait::new("tag", data)]);
+        let mut players = vec![
+            tagged("list", TraitData::Array(vec![serde_json::json!(1)])),
+            tagged("yes", TraitData::Boolean(true)),
+            tagged("bob", TraitData::String("bob".to_string())),
+            Object::new("none", "player"),
+            tagged("ten", TraitData::Number(10.0)),
+            tagged("ann", TraitData::String("ann".to_string())),
+            tagged("neg", TraitData::Number(-1.0)),
+            tagged("no", TraitData::Boolean(false)),
+        ];
+
+        Object::sort_by_trait(&mut players, "tag", true);
+        let names: Vec<&str> = players.iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["neg", "ten", "list", "yes", "bob", "none", "ann", "no"]);
+
+        Object::sort_by_trait(&mut players, "tag", false);
+        let names: Vec<&str> = players.iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["ten", "neg", "list", "yes", "bob", "none", "ann", "no"]);
+    }
+
+    #[test]
+    fn test_typed_object_collection() {
+        let player = |name: &str, health: f64| {
+            Object::with_traits(name, "player", vec![Trait::new("health", TraitData::Number(health))])
+        };
+        let players = TypedObjectCollection::<Player>::from_objects(vec![
+            player("hero", 80.0),
+            player("healer", 0.0),
+            Object::new("spectator", "player"),
+            Object::new("goblin", "enemy"),
+        ]);
+        assert_eq!(players.count(), 2);
+        assert_eq!(players.iter().map(|p| p.health).sum::<f64>(), 80.0);
+
+        let alive = players.filter(|o| o.get_trait_data("health").and_then(|d| d.as_number()) > Some(0.0));
+        assert_eq!(alive.objects()[0].name(), "hero");
+        let names: Vec<String> = alive.into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["hero"]);
+
+        static CONVERSIONS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        struct Counted;
+        impl TryFrom<&Object> for Counted {
+            type Error = crate::OatsError;
+
+            fn try_from(_: &Object) -> Result<Self, Self::Error> {
+                CONVERSIONS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Ok(Counted)
+            }
+        }
+        let counted = TypedObjectCollection::<Counted>::from_objects(vec![player("a", 1.0), player("b", 2.0)]);
+        let counted = counted.filter(|o| o.name() == "a");
+        assert_eq!(counted.iter().count(), 1);
+        assert_eq!(CONVERSIONS.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_clone_with_new_id() {
+        let mut original = Object::new("goblin", "enemy");
+        original.add_trait(Trait::new("health", TraitData::Number(30.0)));
+        original.add_tag("hostile");
+
+        let copy = original.clone_with_new_id();
+        assert_ne!(copy.id(), original.id());
+        assert_ne!(copy, original);
+        assert!(copy.created_at >= original.created_at);
+        assert_eq!(copy.name(), "goblin");
+        assert_eq!(copy.get_trait_data("health"), original.get_trait_data("health"));
+        assert_ne!(copy.get_trait("health").unwrap().id, original.get_trait("health").unwrap().id);
+        assert!(copy.has_tag("hostile"));
+        assert_eq!(original.clone().id(), original.id());
+    }
+
+    #[cfg(feature = "derive")]
+    #[crate::oats_object]
+    #[derive(Debug, PartialEq)]
+    struct Hero {
+        health: f64,
+        level: i64,
+        title: String,
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_oats_object_macro() {
+        let mut object = Object::new("hero", "player");
+        assert!(Hero::from_object(&object).is_none());
+
+        let hero = Hero { health: 80.0, level: 3, title: "Knight".to_string() };
+        hero.update_object(&mut object).unwrap();
+        assert_eq!(object.get_trait_data("level").and_then(|d| d.as_number()), Some(3.0));
+        assert_eq!(Hero::from_object(&object), Some(hero));
+
+        let broken = Hero { health: f64::NAN, level: 1, title: "Ghost".to_string() };
+        assert!(broken.to_traits().is_err());
+        assert!(broken.update_object(&mut object).is_err());
+        assert_eq!(object.get_trait_data("title").and_then(|d| d.as_string()).map(String::as_str), Some("Knight"));
+
+        object.add_trait(Trait::new("level", TraitData::Number(3.5)));
+        assert!(Hero::try_from(&object).is_err());
+    }
+
+    #[test]
+    fn test_checksum() {
+        let mut order = HashMap::new();
+        order.insert("sku".to_string(), serde_json::json!("A-1"));
+        order.insert("qty".to_string(), serde_json::json!(2));
+        let mut object = Object::new("order_1", "order");
+        object.add_trait(Trait::new("line", TraitData::Object(order)));
+        object.set_metadata("channel", "web");
+
+        let checksum = object.compute_checksum();
+        assert_eq!(checksum.len(), 64);
+        let round_trip: Object = serde_json::from_str(&serde_json::to_string(&object).unwrap()).unwrap();
+        assert!(round_trip.verify_checksum(&checksum));
+
+        object.set_metadata("region", "eu");
+        assert!(!object.verify_checksum(&checksum));
+    }
+
+    #[test]
+    fn test_estimated_memory_size() {
+        let mut hero = Object::new("hero", "player");
+        let empty = hero.estimated_memory_size();
+        assert_eq!(empty, std::mem::size_of::<Object>() + "hero".len() + "player".len());
+
+        hero.add_trait(Trait::new("health", TraitData::Number(100.0)));
+        let with_health = hero.estimated_memory_size();
+        assert_eq!(with_health - empty, "health".len() * 2 + std::mem::size_of::<Trait>() + 8);
+
+        hero.add_trait(Trait::new("bio", TraitData::String("a".repeat(1000))));
+        assert!(hero.estimated_memory_size() > with_health + 1000);
+    }
+
+    #[test]
+    fn test_add_trait_idempotent() {
+        let mut hero = Object::new("hero", "player");
+        assert!(hero.add_trait_idempotent(Trait::new("health", TraitData::Number(100.0))));
+        let original_id = hero.get_trait("health").unwrap().id;
+
+        assert!(!hero.add_trait_idempotent(Trait::new("health", TraitData::Number(100.0))));
+        assert_eq!(hero.get_trait("health").unwrap().id, original_id);
+
+        assert!(hero.add_trait_idempotent(Trait::new("health", TraitData::Number(90.0))));
+        assert_eq!(hero.get_trait_data("health").and_then(|d| d.as_number()), Some(90.0));
+
+        hero.add_trait(Trait::new("armor", TraitData::Number(0.3)));
+        assert!(!hero.add_trait_idempotent(Trait::new("armor", TraitData::Number(0.1 + 0.2))));
+        assert_eq!(hero.get_trait_data("armor").and_then(|d| d.as_number()), Some(0.3));
+    }
+
+    #[test]
+    fn test_evaluate_trait_condition() {
+        let mut customer = Object::new("jane", "customer");
+        customer.add_trait(Trait::new("active", TraitData::Boolean(true)));
+        customer.add_trait(Trait::new("is_premium", TraitData::Boolean(false)));
+        customer.add_trait(Trait::new("has_discount", TraitData::String("yes".to_string())));
+
+        let active = BoolExpr::flag("active");
+        assert!(customer.evaluate_trait_condition(&(active.clone() & !BoolExpr::flag("is_premium"))));
+        assert!(!customer.evaluate_trait_condition(&(active.clone() & BoolExpr::flag("is_premium"))));
+        assert!(customer.evaluate_trait_condition(&(BoolExpr::flag("is_premium") | active)));
+        assert!(!customer.evaluate_trait_condition(&BoolExpr::flag("has_discount")));
+        assert!(!customer.evaluate_trait_condition(&BoolExpr::flag("missing")));
+    }
+
+    #[test]
+    fn test_serialize_traits_only_round_trip() {
+        let mut hero = Object::new("hero", "player");
+        hero.set_metadata("faction", "alliance");
+        hero.add_trait(Trait::new("health", TraitData::Number(80.0)));
+        hero.add_trait(Trait::new("title", TraitData::String("knight".to_string())));
+
+        let value = hero.serialize_traits_only();
+        let map = value.as_object().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["health"], serde_json::to_value(TraitData::Number(80.0)).unwrap());
+
+        let mut restored = Object::new("hero_copy", "player");
+        Object::deserialize_traits_from(&value, &mut restored).unwrap();
+        assert!(hero.diff(&restored).is_empty());
+        assert!(restored.get_metadata("faction").is_none());
+
+        let invalid = serde_json::json!({"health": {"Number": 1.0}, "mana": "full"});
+        assert!(Object::deserialize_traits_from(&invalid, &mut restored).is_err());
+        assert!(!restored.has_trait("mana"));
+        assert!(Object::deserialize_traits_from(&serde_json::json!([1, 2]), &mut restored).is_err());
+    }
+
+    #[test]
+    fn test_slice_helpers() {
+        let mut objects = vec![Object::new("goblin_1", "enemy"), Object::new("goblin_2", "enemy")];
+        tag_objects(&mut objects, "aggressive");
+        set_trait_on_objects(&mut objects, Trait::new("damage", TraitData::Number(5.0)));
+        assert_eq!(filter_objects_by_tag(&objects, "aggressive").count(), 2);
+        assert!(objects.iter().all(|o| o.get_trait_data("damage").and_then(|d| d.as_number()) == Some(5.0)));
+
+        untag_objects(&mut objects[..1], "aggressive");
+        let tagged: Vec<_> = filter_objects_by_tag(&objects, "aggressive").map(|o| o.name()).collect();
+        assert_eq!(tagged, vec!["goblin_2"]);
+    }
+
+    #[test]
+    fn test_full_diff() {
+        let mut before = Object::new("gateway", "device");
+        before.add_trait(Trait::new("firmware", TraitData::String("1.0".to_string())));
+        before.add_trait(Trait::new("uptime", TraitData::Number(10.0)));
+        before.set_metadata("region", "eu");
+        before.set_metadata("owner", "ops");
+
+        let mut after = before.clone();
+        assert!(before.full_diff(&after).metadata.is_empty());
+        after.add_trait(Trait::new("firmware", TraitData::String("1.1".to_string())));
+        after.remove_trait("uptime");
+        after.set_metadata("region", "us");
+        after.metadata.remove("owner");
+        after.set_metadata("rack", "r42");
+        after.updated_at = before.updated_at + chrono::Duration::seconds(1);
+
+        let diff = before.full_diff(&after);
+        assert!(diff.traits.removed.contains_key("uptime"));
+        assert_eq!(diff.traits.changed["firmware"].1, TraitData::String("1.1".to_string()));
+        assert_eq!(diff.metadata.added["rack"], "r42");
+        assert_eq!(diff.metadata.removed["owner"], "ops");
+        assert_eq!(diff.metadata.changed["region"], ("eu".to_string(), "us".to_string()));
+        assert!(diff.timestamp_changed);
+    }
+
+    #[test]
+    fn test_soft_delete() {
+        let mut obj = Object::new("order", "order");
+        assert!(!obj.is_deleted());
+
+        obj.soft_delete();
+        assert!(obj.is_deleted());
+
+        obj.restore_deleted();
+        assert!(!obj.is_deleted());
+    }
+
+    #[test]
+    fn test_object_display() {
+        let mut obj = Object::new("hero", "player");
+        obj.add_trait(Trait::new("health", TraitData::Number(100.0)));
+
+        let shown = obj.to_string();
+        let short_id = &obj.id().simple().to_string()[..8];
+        assert_eq!(shown, format!("Object(id={}, name=hero, type=player, traits=1)", short_id));
+    }
+
+    #[test]
+    fn test_trait_predicates() {
+        let mut obj = Object::new("saiyan", "player");
+        obj.add_trait(Trait::new("power", TraitData::Number(9001.0)));
+        obj.add_trait(Trait::new("stamina", TraitData::Number(40.0)));
+        obj.add_trait(Trait::new("title", TraitData::String("prince".to_string())));
+
+        let over_9000 = |_: &str, data: &TraitData| data.as_number().is_some_and(|n| n > 9000.0);
+        assert!(obj.any_trait_matches(over_9000));
+        assert_eq!(obj.traits_matching(|_, data| data.is_number()).len(), 2);
+        assert!(!obj.any_trait_matches(|name, _| name == "stock"));
+        assert!(ObjectFilter::trait_matches(Box::new(over_9000)).matches(&obj));
+    }
+
+    #[test]
+    fn test_object_template() {
+        let mut goblin = Object::new("goblin", "enemy");
+        goblin.add_trait(Trait::new("health", TraitData::Number(30.0)));
+        goblin.set_metadata("faction", "horde");
+
+        let template = goblin.as_template();
+        let json = serde_json::to_string(&template).unwrap();
+        let template: ObjectTemplate = serde_json::from_str(&json).unwrap();
+
+        let spawned = template.instantiate_many(&["goblin_1", "goblin_2"]);
+        assert_eq!(spawned.len(), 2);
+        assert_ne!(spawned[0].id(), spawned[1].id());
+        assert_ne!(spawned[0].id(), goblin.id());
+        assert_eq!(spawned[1].name(), "goblin_2");
+        assert_eq!(spawned[1].object_type(), "enemy");
+        assert_eq!(spawned[1].get_trait_data("health").and_then(|d| d.as_number()), Some(30.0));
+        assert_eq!(spawned[1].get_metadata("faction"), Some(&"horde".to_string()));
+
+        let trait_id = |object: &Object| object.get_trait("health").unwrap().id;
+        assert_ne!(trait_id(&spawned[0]), trait_id(&spawned[1]));
+        assert_ne!(trait_id(&spawned[0]), trait_id(&goblin));
+    }
+
+    #[test]
+    fn test_object_filter_composition() {
+        let mut player = Object::new("hero", "player");
+        player.add_trait(Trait::new("health", TraitData::Number(80.0)));
+        player.add_trait(Trait::new("position", TraitData::Object(HashMap::new())));
+        player.add_tag("aggressive");
+
+        let mut chest = Object::new("chest", "item");
+        chest.add_trait(Trait::new("position", TraitData::Object(HashMap::new())));
+
+        let combatant = ObjectFilter::has_trait("health") & ObjectFilter::has_trait("position");
+        assert!(combatant.matches(&player));
+        assert!(!combatant.matches(&chest));
+
+        let either = ObjectFilter::object_type("item") | ObjectFilter::trait_number_gt("health", 50.0);
+        assert!(either.matches(&player));
+        assert!(either.matches(&chest));
+
+        assert!((!ObjectFilter::tag("aggressive")).matches(&chest));
+        assert!(ObjectFilter::always().matches(&chest));
+        assert!(!ObjectFilter::never().matches(&chest));
+    }
+}
@@ -0,0 +1,172 @@
+//! Push-style event streams with FRP combinators
+//!
+//! `SystemManager::events` returns an `Observable<SystemEvent>` fed by a broadcast channel.
+//! Combinators consume the observable and return a new one, so pipelines read left to right:
+//! `manager.events().filter(SystemEvent::is_action_completed).map(SystemEvent::into_result)`.
+
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::ActionResult;
+
+/// Something that happened while a `SystemManager` processed objects
+#[derive(Debug, Clone)]
+pub enum SystemEvent {
+    /// A system returned a result
+    ActionCompleted { system: String, result: ActionResult },
+    /// A system finished processing a batch of objects
+    SystemCompleted { system: String, objects_processed: usize },
+    /// A system returned an error instead of results
+    SystemFailed { system: String, error: String },
+}
+
+impl SystemEvent {
+    /// Get the name of the system the event came from
+    pub fn system_name(&self) -> &str {
+        match self {
+            SystemEvent::ActionCompleted { system, .. }
+            | SystemEvent::SystemCompleted { system, .. }
+            | SystemEvent::SystemFailed { system, .. } => system,
+        }
+    }
+
+    /// Get the action result carried by an `ActionCompleted` event
+    pub fn result(&self) -> Option<&ActionResult> {
+        match self {
+            SystemEvent::ActionCompleted { result, .. } => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Take the action result carried by an `ActionCompleted` event
+    pub fn into_result(self) -> Option<ActionResult> {
+        match self {
+            SystemEvent::ActionCompleted { result, .. } => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Check whether this is an `ActionCompleted` event
+    pub fn is_action_completed(&self) -> bool {
+        matches!(self, SystemEvent::ActionCompleted { .. })
+    }
+}
+
+/// A stream of values transformed by chained combinators
+pub struct Observable<T> {
+    inner: BoxStream<'static, T>,
+}
+
+impl<T: Send + 'static> Observable<T> {
+    /// Wrap any stream
+    pub fn from_stream(stream: impl Stream<Item = T> + Send + 'static) -> Self {
+        Self { inner: stream.boxed() }
+    }
+
+    /// Emit values sent on a broadcast channel until it closes, skipping any the receiver lagged behind on
+    pub fn from_broadcast(receiver: broadcast::Receiver<T>) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_stream(stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(value) => return Some((value, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    /// Keep only values matching `predicate`
+    pub fn filter(self, mut predicate: impl FnMut(&T) -> bool + Send + 'static) -> Self {
+        Self::from_stream(self.inner.filter(move |value| futures::future::ready(predicate(value))))
+    }
+
+    /// Transform each value
+    pub fn map<U: Send + 'static>(self, f: impl FnMut(T) -> U + Send + 'static) -> Observable<U> {
+        Observable::from_stream(self.inner.map(f))
+    }
+
+    /// Replace each value with an observable and emit everything it produces, in order
+    pub fn flat_map<U: Send + 'static>(self, f: impl FnMut(T) -> Observable<U> + Send + 'static) -> Observable<U> {
+        Observable::from_stream(self.inner.map(f).flatten())
+    }
+
+    /// Group values into batches of `size`; the last batch may be smaller
+    pub fn buffer(self, size: usize) -> Observable<Vec<T>> {
+        Observable::from_stream(self.inner.chunks(size.max(1)))
+    }
+
+    /// Emit a value, then drop values arriving within `interval` of it
+    pub fn throttle(self, interval: Duration) -> Self {
+        let mut last_emitted: Option<tokio::time::Instant> = None;
+        self.filter(move |_| {
+            let now = tokio::time::Instant::now();
+            if last_emitted.is_some_and(|last| now.duration_since(last) < interval) {
+                return false;
+            }
+            last_emitted = Some(now);
+            true
+        })
+    }
+
+    /// Emit values while `predicate` holds, ending at the first that fails it
+    pub fn take_while(self, mut predicate: impl FnMut(&T) -> bool + Send + 'static) -> Self {
+        Self::from_stream(self.inner.take_while(move |value| futures::future::ready(predicate(value))))
+    }
+
+    /// Call `f` for every value until the observable ends
+    pub async fn for_each(self, mut f: impl FnMut(T) + Send + 'static) {
+        self.inner
+            .for_each(move |value| {
+                f(value);
+                futures::future::ready(())
+            })
+            .await
+    }
+}
+
+impl<T> Stream for Observable<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_combinators() {
+        let doubled: Vec<Vec<i32>> = Observable::from_stream(stream::iter(1..=10))
+            .filter(|n| n % 2 == 1)
+            .flat_map(|n| Observable::from_stream(stream::iter([n, n * 10])))
+            .take_while(|n| *n < 70)
+            .buffer(4)
+            .collect()
+            .await;
+        assert_eq!(doubled, vec![vec![1, 10, 3, 30], vec![5, 50, 7]]);
+
+        let throttled: Vec<i32> = Observable::from_stream(stream::iter(1..=5))
+            .throttle(Duration::from_secs(60))
+            .collect()
+            .await;
+        assert_eq!(throttled, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_from_broadcast_ends_when_channel_closes() {
+        let (sender, receiver) = broadcast::channel(8);
+        let observable = Observable::from_broadcast(receiver).map(|n: i32| n + 1);
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+        drop(sender);
+        assert_eq!(observable.collect::<Vec<_>>().await, vec![2, 3]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Protobuf serialization for objects and traits
+//!
+//! The message types are generated from `src/proto/oats.proto` by `build.rs`.
+//! `TraitData::Object` and `TraitData::Array` are carried as JSON text since
+//! `serde_json::Value` has no direct protobuf equivalent.
+
+use prost::Message;
+use std::collections::HashMap;
+use crate::{Object, OatsError, Result, Trait, TraitData};
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/oats.rs"));
+}
+
+pub use generated::{Object as ProtoObject, Trait as ProtoTrait, TraitData as ProtoTraitData};
+use generated::trait_data::Value as ProtoValue;
+
+impl Object {
+    /// Encode this object as protobuf bytes
+    pub fn to_proto_bytes(&self) -> Result<Vec<u8>> {
+        Ok(ProtoObject::try_from(self)?.encode_to_vec())
+    }
+
+    /// Decode an object from protobuf bytes
+    pub fn from_proto_bytes(bytes: &[u8]) -> Result<Object> {
+        let proto = ProtoObject::decode(bytes)
+            .map_err(|e| OatsError::validation_error(format!("Invalid protobuf object: {}", e)))?;
+        Object::try_from(proto)
+    }
+}
+
+impl TryFrom<&Object> for ProtoObject {
+    type Error = OatsError;
+
+    fn try_from(object: &Object) -> Result<Self> {
+        let traits = object
+            .traits
+            .iter()
+            .map(|(name, t)| Ok((name.clone(), ProtoTrait::try_from(t)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(Self {
+            id: object.id.to_string(),
+            name: object.name.clone(),
+            object_type: object.object_type.clone(),
+            traits,
+            metadata: object.metadata.clone(),
+            tags: object.tags.iter().cloned().collect(),
+            is_deleted: object.is_deleted,
+            created_at_micros: object.created_at.timestamp_micros(),
+            updated_at_micros: object.updated_at.timestamp_micros(),
+        })
+    }
+}
+
+impl TryFrom<ProtoObject> for Object {
+    type Error = OatsError;
+
+    fn try_from(proto: ProtoObject) -> Result<Self> {
+        let traits = proto
+            .traits
+            .into_iter()
+            .map(|(name, t)| Ok((name, Trait::try_from(t)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(Self {
+            id: parse_uuid(&proto.id)?,
+            name: proto.name,
+            object_type: proto.object_type,
+            traits,
+            metadata: proto.metadata,
+            tags: proto.tags.into_iter().collect(),
+            is_deleted: proto.is_deleted,
+            created_at: parse_timestamp(proto.created_at_micros)?,
+            updated_at: parse_timestamp(proto.updated_at_micros)?,
+        })
+    }
+}
+
+impl TryFrom<&Trait> for ProtoTrait {
+    type Error = OatsError;
+
+    fn try_from(trait_obj: &Trait) -> Result<Self> {
+        Ok(Self {
+            id: trait_obj.id.to_string(),
+            name: trait_obj.name.clone(),
+            version: trait_obj.version,
+            data: Some(ProtoTraitData::try_from(&trait_obj.data)?),
+            metadata: trait_obj.metadata.clone(),
+            created_by: trait_obj.created_by.clone(),
+            description: trait_obj.description.clone(),
+            tags: trait_obj.tags.clone(),
+            display_name: trait_obj.display_name.clone(),
+            localized_names: trait_obj.localized_names.clone(),
+        })
+    }
+}
+
+impl TryFrom<ProtoTrait> for Trait {
+    type Error = OatsError;
+
+    fn try_from(proto: ProtoTrait) -> Result<Self> {
+        let data = proto
+            .data
+            .ok_or_else(|| OatsError::validation_error(format!("Trait '{}' has no data", proto.name)))?;
+
+        let mut trait_obj = Trait::try_new(proto.name, TraitData::try_from(data)?)?;
+        trait_obj.id = parse_uuid(&proto.id)?;
+        trait_obj.version = proto.version;
+        trait_obj.metadata = proto.metadata;
+        trait_obj.created_by = proto.created_by;
+        trait_obj.description = proto.description;
+        trait_obj.tags = proto.tags;
+        trait_obj.display_name = proto.display_name;
+        trait_obj.localized_names = proto.localized_names;
+        Ok(trait_obj)
+    }
+}
+
+impl TryFrom<&TraitData> for ProtoTraitData {
+    type Error = OatsError;
+
+    fn try_from(data: &TraitData) -> Result<Self> {
+        let value = match data {
+            TraitData::String(s) => ProtoValue::String(s.clone()),
+            TraitData::Number(n) => ProtoValue::Number(*n),
+            TraitData::Boolean(b) => ProtoValue::Boolean(*b),
+            TraitData::Object(o) => ProtoValue::ObjectJson(serde_json::to_string(o)?),
+            TraitData::Array(a) => ProtoValue::ArrayJson(serde_json::to_string(a)?),
+            TraitData::Binary(b) => ProtoValue::Binary(b.clone()),
+        };
+        Ok(Self { value: Some(value) })
+    }
+}
+
+impl TryFrom<ProtoTraitData> for TraitData {
+    type Error = OatsError;
+
+    fn try_from(proto: ProtoTraitData) -> Result<Self> {
+        let value = proto
+            .value
+            .ok_or_else(|| OatsError::validation_error("Trait data has no value"))?;
+
+        Ok(match value {
+            ProtoValue::String(s) => TraitData::String(s),
+            ProtoValue::Number(n) => TraitData::Number(n),
+            ProtoValue::Boolean(b) => TraitData::Boolean(b),
+            ProtoValue::ObjectJson(json) => TraitData::Object(serde_json::from_str(&json)?),
+            ProtoValue::ArrayJson(json) => TraitData::Array(serde_json::from_str(&json)?),
+            ProtoValue::Binary(b) => TraitData::Binary(b),
+        })
+    }
+}
+
+fn parse_uuid(id: &str) -> Result<uuid::Uuid> {
+    uuid::Uuid::parse_str(id).map_err(|e| OatsError::validation_error(format!("Invalid id '{}': {}", id, e)))
+}
+
+fn parse_timestamp(micros: i64) -> Result<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::from_timestamp_micros(micros)
+        .ok_or_else(|| OatsError::validation_error(format!("Invalid timestamp: {}", micros)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proto_round_trip() {
+        let mut obj = Object::new("hero", "player");
+        obj.add_trait(Trait::new("health", TraitData::Number(87.5)));
+        obj.add_trait(Trait::new("inventory", TraitData::Array(vec![serde_json::json!("sword")])));
+        obj.add_tag("aggressive");
+        obj.set_metadata("zone", "forest");
+
+        let bytes = obj.to_proto_bytes().unwrap();
+        let decoded = Object::from_proto_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.id(), obj.id());
+        assert_eq!(decoded.name(), "hero");
+        assert_eq!(decoded.get_trait_data("health").and_then(|d| d.as_number()), Some(87.5));
+        assert_eq!(decoded.get_trait_data("inventory").and_then(|d| d.as_array()).map(Vec::len), Some(1));
+        assert!(decoded.has_tag("aggressive"));
+        assert_eq!(decoded.get_metadata("zone"), Some(&"forest".to_string()));
+        assert_eq!(decoded.created_at().timestamp_micros(), obj.created_at().timestamp_micros());
+    }
+}
@@ -0,0 +1,120 @@
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use crate::{Object, TraitData};
+
+impl TraitData {
+    /// Get the JSON Schema type name for this trait data
+    pub fn json_schema_type(&self) -> &'static str {
+        match self {
+            TraitData::String(_) => "string",
+            TraitData::Number(_) => "number",
+            TraitData::Boolean(_) => "boolean",
+            TraitData::Object(_) => "object",
+            TraitData::Array(_) | TraitData::Binary(_) => "array",
+        }
+    }
+}
+
+impl Object {
+    /// Generate a JSON Schema describing this object's traits
+    pub fn to_json_schema(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .traits
+            .iter()
+            .map(|(name, t)| (name.clone(), json!({ "type": t.data.json_schema_type() })))
+            .collect();
+
+        json!({
+            "type": "object",
+            "properties": properties,
+        })
+    }
+}
+
+/// Collects JSON Schemas per object type, merging the properties of every object seen
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: HashMap<String, Map<String, Value>>,
+}
+
+impl SchemaRegistry {
+    /// Create an empty schema registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge an object's trait properties into the schema for its type
+    ///
+    /// A property seen with different types gets a `"type"` array listing all of them.
+    pub fn add_object(&mut self, object: &Object) {
+        let properties = self.schemas.entry(object.object_type.clone()).or_default();
+
+        for (name, t) in &object.traits {
+            let new_type = t.data.json_schema_type();
+            match properties.get_mut(name).and_then(|p| p.get_mut("type")) {
+                Some(Value::String(existing)) if existing.as_str() != new_type => {
+                    let existing = std::mem::take(existing);
+                    properties.insert(name.clone(), json!({ "type": [existing, new_type] }));
+                }
+                Some(Value::Array(types)) if !types.iter().any(|ty| ty == new_type) => {
+                    types.push(json!(new_type));
+                }
+                Some(_) => {}
+                None => {
+                    properties.insert(name.clone(), json!({ "type": new_type }));
+                }
+            }
+        }
+    }
+
+    /// Get the merged schema for an object type
+    pub fn schema(&self, object_type: &str) -> Option<Value> {
+        self.schemas.get(object_type).map(|properties| {
+            json!({
+                "type": "object",
+                "properties": properties,
+            })
+        })
+    }
+
+    /// Get the object types with a collected schema
+    pub fn object_types(&self) -> Vec<&String> {
+        self.schemas.keys().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Trait;
+
+    #[test]
+    fn test_object_json_schema() {
+        let mut product = Object::new("laptop_pro", "product");
+        product.add_trait(Trait::new("price", TraitData::Number(999.99)));
+        product.add_trait(Trait::new("in_stock", TraitData::Boolean(true)));
+
+        let schema = product.to_json_schema();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["price"]["type"], "number");
+        assert_eq!(schema["properties"]["in_stock"]["type"], "boolean");
+    }
+
+    #[test]
+    fn test_schema_registry_merges_properties() {
+        let mut laptop = Object::new("laptop_pro", "product");
+        laptop.add_trait(Trait::new("price", TraitData::Number(999.99)));
+        let mut book = Object::new("rust_book", "product");
+        book.add_trait(Trait::new("price", TraitData::String("49.99".to_string())));
+        book.add_trait(Trait::new("isbn", TraitData::String("978-1".to_string())));
+
+        let mut registry = SchemaRegistry::new();
+        registry.add_object(&laptop);
+        registry.add_object(&book);
+
+        let schema = registry.schema("product").unwrap();
+        assert_eq!(schema["properties"]["isbn"]["type"], "string");
+        assert_eq!(schema["properties"]["price"]["type"], json!(["number", "string"]));
+        assert!(registry.schema("customer").is_none());
+    }
+}
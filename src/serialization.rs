@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use crate::{Object, OatsError, Result};
+
+/// Name under which `JsonObjectSerializer` is registered by default
+pub const JSON_SERIALIZER: &str = "json";
+
+/// Name under which `MsgPackObjectSerializer` is registered by default
+#[cfg(feature = "msgpack")]
+pub const MSGPACK_SERIALIZER: &str = "msgpack";
+
+/// Converts objects to and from a wire format
+pub trait ObjectSerializer: Send + Sync {
+    /// Encode an object
+    fn serialize(&self, object: &Object) -> Result<Vec<u8>>;
+
+    /// Decode an object
+    fn deserialize(&self, bytes: &[u8]) -> Result<Object>;
+
+    /// Get the MIME type of the encoded bytes
+    fn content_type(&self) -> &str;
+}
+
+/// Serializes objects as JSON
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonObjectSerializer;
+
+impl ObjectSerializer for JsonObjectSerializer {
+    fn serialize(&self, object: &Object) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(object)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Object> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+}
+
+/// Serializes objects as MessagePack maps
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgPackObjectSerializer;
+
+#[cfg(feature = "msgpack")]
+impl ObjectSerializer for MsgPackObjectSerializer {
+    fn serialize(&self, object: &Object) -> Result<Vec<u8>> {
+        rmp_serde::to_vec_named(object)
+            .map_err(|e| OatsError::system_error(format!("MessagePack encoding failed: {}", e)))
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Object> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| OatsError::validation_error(format!("MessagePack decoding failed: {}", e)))
+    }
+
+    fn content_type(&self) -> &str {
+        "application/msgpack"
+    }
+}
+
+/// Named object serializers, with an optional default serializer per object type
+pub struct SerializationRegistry {
+    serializers: HashMap<String, Box<dyn ObjectSerializer>>,
+    type_serializers: HashMap<String, String>,
+}
+
+impl SerializationRegistry {
+    /// Create a registry holding only the built-in serializers
+    pub fn new() -> Self {
+        let mut registry = Self {
+            serializers: HashMap::new(),
+            type_serializers: HashMap::new(),
+        };
+        registry.register(JSON_SERIALIZER, Box::new(JsonObjectSerializer));
+        #[cfg(feature = "msgpack")]
+        registry.register(MSGPACK_SERIALIZER, Box::new(MsgPackObjectSerializer));
+        registry
+    }
+
+    /// Register a serializer under a name, replacing any previous one
+    pub fn register(&mut self, name: impl Into<String>, serializer: Box<dyn ObjectSerializer>) {
+        self.serializers.insert(name.into(), serializer);
+    }
+
+    /// Use the named serializer for objects of `object_type`
+    pub fn assign_type(&mut self, object_type: impl Into<String>, serializer_name: impl Into<String>) {
+        self.type_serializers.insert(object_type.into(), serializer_name.into());
+    }
+
+    /// Get a serializer by name
+    pub fn get(&self, name: &str) -> Option<&dyn ObjectSerializer> {
+        self.serializers.get(name).map(|s| s.as_ref())
+    }
+
+    /// Get the serializer for an object type, falling back to JSON
+    pub fn for_object_type(&self, object_type: &str) -> Option<&dyn ObjectSerializer> {
+        let name = self
+            .type_serializers
+            .get(object_type)
+            .map(String::as_str)
+            .unwrap_or(JSON_SERIALIZER);
+        self.get(name)
+    }
+
+    /// Serialize an object with the named serializer
+    pub fn serialize(&self, object: &Object, serializer_name: &str) -> Result<Vec<u8>> {
+        self.get(serializer_name)
+            .ok_or_else(|| OatsError::validation_error(format!("Unknown serializer '{}'", serializer_name)))?
+            .serialize(object)
+    }
+}
+
+impl Default for SerializationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Trait, TraitData};
+
+    struct CsvSerializer;
+
+    impl ObjectSerializer for CsvSerializer {
+        fn serialize(&self, object: &Object) -> Result<Vec<u8>> {
+            Ok(format!("{},{}", object.name(), object.object_type()).into_bytes())
+        }
+
+        fn deserialize(&self, bytes: &[u8]) -> Result<Object> {
+            let line = String::from_utf8_lossy(bytes);
+            let (name, object_type) = line
+                .split_once(',')
+                .ok_or_else(|| OatsError::validation_error("Expected name,type"))?;
+            Ok(Object::new(name, object_type))
+        }
+
+        fn content_type(&self) -> &str {
+            "text/csv"
+        }
+    }
+
+    #[test]
+    fn test_registry_serializers() {
+        let mut registry = SerializationRegistry::new();
+        registry.register("csv", Box::new(CsvSerializer));
+        registry.assign_type("sensor", "csv");
+
+        let mut sensor = Object::new("thermostat", "sensor");
+        sensor.add_trait(Trait::new("celsius", TraitData::Number(21.5)));
+
+        assert_eq!(registry.serialize(&sensor, "csv").unwrap(), b"thermostat,sensor");
+        assert_eq!(registry.for_object_type("sensor").unwrap().content_type(), "text/csv");
+        assert_eq!(registry.for_object_type("player").unwrap().content_type(), "application/json");
+        assert!(registry.serialize(&sensor, "xml").is_err());
+
+        let json = registry.get(JSON_SERIALIZER).unwrap();
+        let decoded = json.deserialize(&json.serialize(&sensor).unwrap()).unwrap();
+        assert_eq!(decoded.id(), sensor.id());
+        assert_eq!(decoded.get_trait_data("celsius").and_then(|d| d.as_number()), Some(21.5));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_round_trip() {
+        let mut sword = Object::new("sword", "item");
+        sword.add_trait(Trait::new("damage", TraitData::Number(12.0)));
+
+        let bytes = MsgPackObjectSerializer.serialize(&sword).unwrap();
+        let decoded = MsgPackObjectSerializer.deserialize(&bytes).unwrap();
+        assert_eq!(decoded.id(), sword.id());
+        assert_eq!(decoded.get_trait_data("damage").and_then(|d| d.as_number()), Some(12.0));
+    }
+}
@@ -0,0 +1,140 @@
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+use std::collections::HashMap;
+use crate::objects::ObjectId;
+use crate::Object;
+
+/// Entry stored in the spatial index: a 2D point tagged with its object ID
+pub type SpatialEntry = GeomWithData<[f64; 2], ObjectId>;
+
+/// Name of the trait holding an object's `{"x": .., "y": ..}` position
+pub const POSITION_TRAIT: &str = "position";
+
+/// R-tree index over the 2D positions of objects
+#[derive(Debug, Default)]
+pub struct SpatialIndex2D {
+    tree: RTree<SpatialEntry>,
+    positions: HashMap<ObjectId, [f64; 2]>,
+}
+
+impl SpatialIndex2D {
+    /// Create an empty spatial index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an index from every object carrying a position trait
+    pub fn from_objects<'a>(objects: impl IntoIterator<Item = &'a Object>) -> Self {
+        let positions: HashMap<ObjectId, [f64; 2]> = objects
+            .into_iter()
+            .filter_map(|object| Self::position_of(object).map(|point| (object.id, point)))
+            .collect();
+        let entries = positions.iter().map(|(id, point)| SpatialEntry::new(*point, *id)).collect();
+
+        Self {
+            tree: RTree::bulk_load(entries),
+            positions,
+        }
+    }
+
+    /// Read an object's position from its `position` trait
+    pub fn position_of(object: &Object) -> Option<[f64; 2]> {
+        let position = object.get_trait_data(POSITION_TRAIT)?.as_object()?;
+        let x = position.get("x")?.as_f64()?;
+        let y = position.get("y")?.as_f64()?;
+        Some([x, y])
+    }
+
+    /// Insert or move an object; objects without a position are removed
+    pub fn update(&mut self, object: &Object) {
+        self.remove(object.id);
+        if let Some(point) = Self::position_of(object) {
+            self.tree.insert(SpatialEntry::new(point, object.id));
+            self.positions.insert(object.id, point);
+        }
+    }
+
+    /// Remove an object from the index
+    pub fn remove(&mut self, id: ObjectId) -> bool {
+        match self.positions.remove(&id) {
+            Some(point) => self.tree.remove(&SpatialEntry::new(point, id)).is_some(),
+            None => false,
+        }
+    }
+
+    /// Replace the index contents with the given objects
+    pub fn rebuild<'a>(&mut self, objects: impl IntoIterator<Item = &'a Object>) {
+        *self = Self::from_objects(objects);
+    }
+
+    /// Get the indexed position of an object
+    pub fn position(&self, id: ObjectId) -> Option<[f64; 2]> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Get the IDs of objects within `radius` of the given object, excluding itself
+    pub fn nearest_neighbors(&self, id: ObjectId, radius: f64) -> Vec<ObjectId> {
+        match self.position(id) {
+            Some(point) => self
+                .within_radius(point, radius)
+                .into_iter()
+                .filter(|other| *other != id)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the IDs of objects within `radius` of a point
+    pub fn within_radius(&self, point: [f64; 2], radius: f64) -> Vec<ObjectId> {
+        self.tree
+            .locate_within_distance(point, radius * radius)
+            .map(|entry| entry.data)
+            .collect()
+    }
+
+    /// Get the number of indexed objects
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check if the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Trait, TraitData};
+
+    fn positioned(name: &str, x: f64, y: f64) -> Object {
+        let mut position = HashMap::new();
+        position.insert("x".to_string(), serde_json::json!(x));
+        position.insert("y".to_string(), serde_json::json!(y));
+        let mut object = Object::new(name, "character");
+        object.add_trait(Trait::new(POSITION_TRAIT, TraitData::Object(position)));
+        object
+    }
+
+    #[test]
+    fn test_nearest_neighbors() {
+        let hero = positioned("hero", 0.0, 0.0);
+        let goblin = positioned("goblin", 2.0, 2.0);
+        let orc = positioned("orc", 10.0, 0.0);
+        let chest = Object::new("chest", "item");
+
+        let mut index = SpatialIndex2D::from_objects([&hero, &goblin, &orc, &chest]);
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.nearest_neighbors(hero.id(), 3.0), vec![goblin.id()]);
+        assert!(index.nearest_neighbors(chest.id(), 3.0).is_empty());
+
+        let mut moved_orc = positioned("orc", 1.0, 0.0);
+        moved_orc.id = orc.id();
+        index.update(&moved_orc);
+        assert_eq!(index.nearest_neighbors(hero.id(), 3.0).len(), 2);
+
+        assert!(index.remove(goblin.id()));
+        assert_eq!(index.nearest_neighbors(hero.id(), 3.0), vec![orc.id()]);
+    }
+}
@@ -0,0 +1,264 @@
+//! Object registry together with the per-type counts, spatial index and change channels kept in sync with it
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use dashmap::DashMap;
+use tokio::sync::{broadcast, RwLock, RwLockWriteGuard};
+use crate::{OatsError, Object, Result};
+use crate::spatial::SpatialIndex2D;
+use crate::systems::{ObjectChange, ObjectRegistry, EVENT_CHANNEL_CAPACITY};
+
+tokio::task_local! {
+    /// Store of the `SystemManager` processing run currently executing
+    static PROCESS_STORE: ObjectStore;
+}
+
+/// Registry handle that keeps per-type counts, the spatial index and change subscribers in sync
+///
+/// Clones share state. Writes made by a `SystemManager`, and by built-in systems while it
+/// processes objects, all go through a `StoreWriter`.
+#[derive(Clone)]
+pub(crate) struct ObjectStore {
+    pub(crate) registry: ObjectRegistry,
+    pub(crate) spatial_index: Option<Arc<RwLock<SpatialIndex2D>>>,
+    type_counts: Arc<DashMap<String, AtomicUsize>>,
+    memory_bytes: Arc<AtomicUsize>,
+    object_changes: broadcast::Sender<ObjectChange>,
+    type_change_channels: Arc<DashMap<String, broadcast::Sender<ObjectChange>>>,
+}
+
+impl ObjectStore {
+    /// Create an empty store
+    pub(crate) fn with_capacity(expected_objects: usize) -> Self {
+        Self {
+            registry: Arc::new(RwLock::new(HashMap::with_capacity(expected_objects))),
+            spatial_index: None,
+            type_counts: Arc::new(DashMap::new()),
+            memory_bytes: Arc::new(AtomicUsize::new(0)),
+            object_changes: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            type_change_channels: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Copy the objects into an independent store without subscribers, e.g. for a dry run
+    pub(crate) async fn shadow(&self) -> Self {
+        let objects = self.registry.read().await.clone();
+        let mut store = Self::with_capacity(0);
+        if self.spatial_index.is_some() {
+            store.spatial_index = Some(Arc::new(RwLock::new(SpatialIndex2D::new())));
+        }
+        store.write().await.replace_all(objects);
+        store
+    }
+
+    /// Get the store of the processing run this code executes in, if any
+    pub(crate) fn current() -> Option<Self> {
+        PROCESS_STORE.try_with(Clone::clone).ok()
+    }
+
+    /// Run `f` with this store as the current one
+    pub(crate) async fn scope<F: std::future::Future>(self, f: F) -> F::Output {
+        PROCESS_STORE.scope(self, f).await
+    }
+
+    /// Run `f` synchronously with this store as the current one
+    #[cfg(feature = "work_stealing")]
+    pub(crate) fn scope_sync<R>(self, f: impl FnOnce() -> R) -> R {
+        PROCESS_STORE.sync_scope(self, f)
+    }
+
+    /// Lock the registry, and the spatial index if any, for writing
+    pub(crate) async fn write(&self) -> StoreWriter<'_> {
+        let registry = self.registry.write().await;
+        let index = match &self.spatial_index {
+            Some(index) => Some(index.write().await),
+            None => None,
+        };
+        StoreWriter { store: self, registry, index, counts: PendingCounts { store: self, deltas: HashMap::new() } }
+    }
+
+    /// Get the live objects a processing run sees, resyncing the spatial index to them
+    pub(crate) async fn prepare_run(&self) -> Vec<Object> {
+        let objects: Vec<Object> = {
+            let registry = self.registry.read().await;
+            registry.values().filter(|object| !object.is_deleted()).cloned().collect()
+        };
+        if let Some(index) = &self.spatial_index {
+            index.write().await.rebuild(&objects);
+        }
+        objects
+    }
+
+    /// Subscribe to every object change
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<ObjectChange> {
+        self.object_changes.subscribe()
+    }
+
+    /// Subscribe to changes of objects of one type
+    pub(crate) fn subscribe_to_type(&self, type_name: &str) -> broadcast::Receiver<ObjectChange> {
+        self.type_change_channels
+            .entry(type_name.to_string())
+            .or_insert_with(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Get the number of stored objects of a type
+    pub(crate) fn count_by_type(&self, type_name: &str) -> usize {
+        self.type_counts
+            .get(type_name)
+            .map(|count| count.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Get the number of stored objects of every type
+    pub(crate) fn counts_by_type(&self) -> HashMap<String, usize> {
+        self.type_counts
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Get the running total of `Object::estimated_memory_size` across stored objects
+    pub(crate) fn memory_bytes(&self) -> usize {
+        self.memory_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Add `delta` to a type's count, saturating at zero
+    fn adjust_type_count(&self, type_name: &str, delta: isize) {
+        let apply = |count: &AtomicUsize| {
+            let _ = count.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| Some(n.saturating_add_signed(delta)));
+        };
+        match self.type_counts.get(type_name) {
+            Some(count) => apply(&count),
+            None if delta > 0 => apply(&self.type_counts.entry(type_name.to_string()).or_default()),
+            None => {}
+        }
+    }
+
+    /// Send a change to the global and type channels, cloning `object` only if someone is listening
+    fn publish_change(&self, object: &Object, change: fn(Object) -> ObjectChange) {
+        let typed = self
+            .type_change_channels
+            .get(&object.object_type)
+            .filter(|sender| sender.receiver_count() > 0);
+        if typed.is_none() && self.object_changes.receiver_count() == 0 {
+            return;
+        }
+
+        let change = change(object.clone());
+        if let Some(sender) = typed {
+            let _ = sender.send(change.clone());
+        }
+        let _ = self.object_changes.send(change);
+    }
+}
+
+/// Write access to an `ObjectStore`, holding the registry and spatial index locks until dropped
+///
+/// Per-type count changes are collected while the locks are held and applied once they are
+/// released, so count readers never wait on the registry.
+pub(crate) struct StoreWriter<'a> {
+    store: &'a ObjectStore,
+    pub(crate) registry: RwLockWriteGuard<'a, HashMap<String, Object>>,
+    index: Option<RwLockWriteGuard<'a, SpatialIndex2D>>,
+    // Declared after the guards: fields drop in order, so the counts update after the unlock
+    counts: PendingCounts<'a>,
+}
+
+/// Per-type count changes applied to the store when dropped
+struct PendingCounts<'a> {
+    store: &'a ObjectStore,
+    deltas: HashMap<String, isize>,
+}
+
+impl PendingCounts<'_> {
+    fn add(&mut self, type_name: &str, delta: isize) {
+        match self.deltas.get_mut(type_name) {
+            Some(pending) => *pending += delta,
+            None => {
+                self.deltas.insert(type_name.to_string(), delta);
+            }
+        }
+    }
+}
+
+impl Drop for PendingCounts<'_> {
+    fn drop(&mut self) {
+        for (type_name, delta) in self.deltas.drain().filter(|(_, delta)| *delta != 0) {
+            self.store.adjust_type_count(&type_name, delta);
+        }
+    }
+}
+
+impl StoreWriter<'_> {
+    /// Insert or replace an object, returning the object it replaced
+    pub(crate) fn insert(&mut self, object: Object) -> Option<Object> {
+        if let Some(index) = self.index.as_mut() {
+            index.update(&object);
+        }
+        self.counts.add(&object.object_type, 1);
+        self.store.memory_bytes.fetch_add(object.estimated_memory_size(), Ordering::Relaxed);
+        let key = object.id.to_string();
+        let replaced = self.registry.insert(key.clone(), object);
+        if let Some(replaced) = &replaced {
+            self.counts.add(&replaced.object_type, -1);
+            self.store.memory_bytes.fetch_sub(replaced.estimated_memory_size(), Ordering::Relaxed);
+        }
+        let change = if replaced.is_some() { ObjectChange::Updated } else { ObjectChange::Added };
+        self.store.publish_change(&self.registry[&key], change);
+        replaced
+    }
+
+    /// Remove an object by ID
+    pub(crate) fn remove(&mut self, id: &str) -> Option<Object> {
+        let removed = self.registry.remove(id)?;
+        if let Some(index) = self.index.as_mut() {
+            index.remove(removed.id);
+        }
+        self.counts.add(&removed.object_type, -1);
+        self.store.memory_bytes.fetch_sub(removed.estimated_memory_size(), Ordering::Relaxed);
+        self.store.publish_change(&removed, ObjectChange::Removed);
+        Some(removed)
+    }
+
+    /// Modify a stored object in place
+    pub(crate) fn update(&mut self, id: &str, f: impl FnOnce(&mut Object)) -> Result<()> {
+        let object = self.registry.get_mut(id).ok_or_else(|| OatsError::object_not_found(id))?;
+        let before = object.estimated_memory_size();
+        f(object);
+        let after = object.estimated_memory_size();
+        if after >= before {
+            self.store.memory_bytes.fetch_add(after - before, Ordering::Relaxed);
+        } else {
+            self.store.memory_bytes.fetch_sub(before - after, Ordering::Relaxed);
+        }
+        if let Some(index) = self.index.as_mut() {
+            index.update(object);
+        }
+        self.store.publish_change(object, ObjectChange::Updated);
+        Ok(())
+    }
+
+    /// Replace every object, adjusting counts and rebuilding the spatial index without publishing changes
+    ///
+    /// Counts change by the difference between the old and new objects, so they are never
+    /// cleared while other tasks read them.
+    pub(crate) fn replace_all(&mut self, objects: HashMap<String, Object>) -> HashMap<String, Object> {
+        let previous = std::mem::replace(&mut *self.registry, objects);
+        for object in previous.values() {
+            self.counts.add(&object.object_type, -1);
+        }
+        let mut memory_bytes = 0;
+        for object in self.registry.values() {
+            self.counts.add(&object.object_type, 1);
+            memory_bytes += object.estimated_memory_size();
+        }
+        self.store.memory_bytes.store(memory_bytes, Ordering::Relaxed);
+        if let Some(index) = self.index.as_mut() {
+            index.rebuild(self.registry.values());
+        }
+        previous
+    }
+}
@@ -1,32 +1,160 @@
 use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use crate::{Result, Object, OatsError};
-use crate::actions::ActionResult;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use crate::{Result, Object, ObjectFilter, OatsError};
+use crate::objects::{ObjectDiff, ObjectId, TypedObjectCollection};
+use crate::spatial::SpatialIndex2D;
+use crate::serialization::SerializationRegistry;
+use crate::traits::TraitMigrationRegistry;
+use crate::actions::{ActionContext, ActionResult, ServiceLocator, StreamingAction};
+use crate::store::ObjectStore;
 
 /// System identifier
 pub type SystemId = uuid::Uuid;
 
+/// Shared object registry keyed by object ID
+pub type ObjectRegistry = Arc<RwLock<HashMap<String, Object>>>;
+
 /// Priority levels for system operations
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+///
+/// Serializes as its name, e.g. `"High"`, or a custom level as a numeric string, e.g. `"192"`.
+/// Deserializes from a name, a level, or a numeric string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Priority {
-    Low = 1,
-    Normal = 2,
-    High = 3,
-    Critical = 4,
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+    /// A level outside the named ones; levels 1 to 4 parse to the named variants instead
+    Custom(u8),
 }
 
-impl Default for Priority {
-    fn default() -> Self {
-        Self::Normal
+impl Priority {
+    /// Get the numeric level, from 1 for `Low` to 4 for `Critical`
+    pub fn level(&self) -> u8 {
+        match self {
+            Priority::Low => 1,
+            Priority::Normal => 2,
+            Priority::High => 3,
+            Priority::Critical => 4,
+            Priority::Custom(level) => *level,
+        }
+    }
+
+    /// Get the name of a named priority, e.g. `"High"`
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Priority::Low => Some("Low"),
+            Priority::Normal => Some("Normal"),
+            Priority::High => Some("High"),
+            Priority::Critical => Some("Critical"),
+            Priority::Custom(_) => None,
+        }
+    }
+}
+
+/// Orders by level; a named priority sorts before a custom one at the same level
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let key = |p: &Priority| (p.level(), matches!(p, Priority::Custom(_)));
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{}", self.level()),
+        }
+    }
+}
+
+impl From<u8> for Priority {
+    fn from(level: u8) -> Self {
+        match level {
+            1 => Priority::Low,
+            2 => Priority::Normal,
+            3 => Priority::High,
+            4 => Priority::Critical,
+            level => Priority::Custom(level),
+        }
+    }
+}
+
+/// Parse a name in any case, e.g. `"critical"`, or a level such as `"192"`
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        if let Ok(level) = s.trim().parse::<u8>() {
+            return Ok(Priority::from(level));
+        }
+        [Priority::Low, Priority::Normal, Priority::High, Priority::Critical]
+            .into_iter()
+            .find(|priority| priority.name().is_some_and(|name| name.eq_ignore_ascii_case(s.trim())))
+            .ok_or_else(|| format!("unknown priority '{}'", s))
+    }
+}
+
+impl Serialize for Priority {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Priority {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct PriorityVisitor;
+
+        impl serde::de::Visitor<'_> for PriorityVisitor {
+            type Value = Priority;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a priority level from 0 to 255 or a priority name")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, level: u64) -> std::result::Result<Priority, E> {
+                u8::try_from(level)
+                    .map(Priority::from)
+                    .map_err(|_| E::custom(format!("priority level {} is outside 0..=255", level)))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, level: i64) -> std::result::Result<Priority, E> {
+                match u64::try_from(level) {
+                    Ok(level) => self.visit_u64(level),
+                    Err(_) => Err(E::custom(format!("priority level {} is outside 0..=255", level))),
+                }
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> std::result::Result<Priority, E> {
+                name.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(PriorityVisitor)
     }
 }
 
 /// A system represents orchestration that coordinates actions and manages resources
+///
+/// Systems are `Any`, so a `dyn System` can be downcast to its concrete type.
 #[async_trait]
-pub trait System: Send + Sync {
+pub trait System: Any + Send + Sync {
     /// Get the name of this system
     fn name(&self) -> &str;
 
@@ -43,8 +171,47 @@ pub trait System: Send + Sync {
         Ok(())
     }
 
+    /// Reset state accumulated across `process` calls, such as stats and caches
+    async fn drain(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Prepare for processing, e.g. flushing caches; an error aborts `process`
+    async fn before_process(&mut self, _objects: &[Object], _priority: Priority) -> Result<()> {
+        Ok(())
+    }
+
     /// Process objects with the given priority
-    async fn process(&mut self, objects: Vec<Object>, priority: Priority) -> Result<Vec<ActionResult>>;
+    async fn process(&mut self, objects: &[Object], priority: Priority) -> Result<Vec<ActionResult>>;
+
+    /// Process objects, building each action context from `shared` via `ActionContext::for_target`
+    ///
+    /// The default ignores `shared` and calls `process`.
+    async fn process_with_context(
+        &mut self,
+        objects: &[Object],
+        priority: Priority,
+        _shared: &ActionContext<'_>,
+    ) -> Result<Vec<ActionResult>> {
+        self.process(objects, priority).await
+    }
+
+    /// Load expensive state, such as model weights or rate caches, before the first `process`
+    async fn warm_up(&mut self, _sample_objects: &[Object]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Finish processing, e.g. persisting results; errors are logged and do not roll back
+    async fn after_process(&mut self, _results: &[ActionResult], _priority: Priority) -> Result<()> {
+        Ok(())
+    }
+
+    /// Get the filter selecting which objects `process_all` passes to this system
+    ///
+    /// The default of `None` passes every object.
+    fn object_filter(&self) -> Option<ObjectFilter> {
+        None
+    }
 
     /// Get the priority of this system
     fn priority(&self) -> Priority {
@@ -56,12 +223,32 @@ pub trait System: Send + Sync {
         true
     }
 
+    /// Check if `process_object` may run on a `WorkStealingSystemManager` pool
+    fn is_thread_safe(&self) -> bool {
+        false
+    }
+
+    /// Process one object through a shared reference, as a `WorkStealingSystemManager` work item
+    ///
+    /// Systems whose `is_thread_safe` returns true must implement this. It runs synchronously
+    /// on a pool thread, concurrently with other objects of the same system, so it suits
+    /// CPU-bound work; stats it keeps need interior mutability.
+    fn process_object(&self, _object: &Object, _priority: Priority) -> Result<Vec<ActionResult>> {
+        Err(OatsError::invalid_state(format!(
+            "System '{}' does not support process_object",
+            self.name()
+        )))
+    }
+
     /// Get system statistics
     fn get_stats(&self) -> SystemStats {
         SystemStats::default()
     }
 }
 
+/// Marker for systems that can be replaced by `SystemManager::swap_system` while running
+pub trait HotSwapable: System {}
+
 /// Statistics for a system
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SystemStats {
@@ -79,6 +266,15 @@ pub struct SystemStats {
     pub avg_processing_time_ms: f64,
     /// Peak processing time in milliseconds
     pub peak_processing_time_ms: u64,
+    /// Number of stale objects removed by garbage collection
+    #[serde(default)]
+    pub gc_collected: u64,
+    /// Whether `SystemManager::warm_up_all` has warmed up the system
+    #[serde(default)]
+    pub warm_up_completed: bool,
+    /// Time spent in `warm_up` in milliseconds
+    #[serde(default)]
+    pub warm_up_duration_ms: u64,
 }
 
 impl SystemStats {
@@ -109,6 +305,7 @@ impl SystemStats {
         self.total_processing_time_ms = 0;
         self.avg_processing_time_ms = 0.0;
         self.peak_processing_time_ms = 0;
+        self.gc_collected = 0;
         self.last_processed = None;
     }
 
@@ -121,192 +318,2197 @@ impl SystemStats {
             0.0
         }
     }
-}
 
-/// A system manager that coordinates multiple systems
-pub struct SystemManager {
-    systems: HashMap<String, Box<dyn System>>,
-    object_registry: Arc<RwLock<HashMap<String, Object>>>,
-}
+    /// Get the percentage of executed actions that did not error
+    pub fn success_rate(&self) -> f64 {
+        if self.actions_executed > 0 {
+            self.actions_executed.saturating_sub(self.errors) as f64 / self.actions_executed as f64 * 100.0
+        } else {
+            100.0
+        }
+    }
 
-impl SystemManager {
-    /// Create a new system manager
-    pub fn new() -> Self {
-        Self {
-            systems: HashMap::new(),
-            object_registry: Arc::new(RwLock::new(HashMap::with_capacity(100))),
+    /// Get the average number of actions executed per processed object
+    pub fn actions_per_object(&self) -> f64 {
+        if self.objects_processed > 0 {
+            self.actions_executed as f64 / self.objects_processed as f64
+        } else {
+            0.0
         }
     }
 
-    /// Create a new system manager with expected capacity
-    pub fn with_capacity(expected_objects: usize) -> Self {
-        Self {
-            systems: HashMap::new(),
-            object_registry: Arc::new(RwLock::new(HashMap::with_capacity(expected_objects))),
+    /// Get a single health indicator combining actions per object and error rate
+    pub fn efficiency_score(&self) -> f64 {
+        self.actions_per_object() * (1.0 - self.error_rate() / 100.0)
+    }
+
+    /// Format the stats as an InfluxDB line protocol point, timestamped now by default
+    ///
+    /// `last_processed` is written as nanoseconds since the epoch and omitted when unset.
+    #[cfg(feature = "influx_export")]
+    pub fn to_influx_line_protocol(&self, system_name: &str, timestamp: Option<chrono::DateTime<chrono::Utc>>) -> String {
+        let timestamp = timestamp.unwrap_or_else(chrono::Utc::now);
+        let tag: String = system_name
+            .chars()
+            .flat_map(|c| {
+                let escape = matches!(c, ',' | '=' | ' ').then_some('\\');
+                escape.into_iter().chain(std::iter::once(c))
+            })
+            .collect();
+
+        let mut line = format!(
+            "oats_system,system={} objects_processed={}i,actions_executed={}i,errors={}i,avg_latency_ms={}",
+            tag, self.objects_processed, self.actions_executed, self.errors, self.avg_processing_time_ms
+        );
+        if let Some(nanos) = self.last_processed.and_then(|t| t.timestamp_nanos_opt()) {
+            line.push_str(&format!(",last_processed={}i", nanos));
         }
+        line.push_str(&format!(" {}", timestamp.timestamp_nanos_opt().unwrap_or_default()));
+        line
     }
 
-    /// Add a system to the manager
-    pub fn add_system(&mut self, system: Box<dyn System>) {
-        let name = system.name().to_string();
-        self.systems.insert(name, system);
+    /// Format the stats as an OpenMetrics text page for Prometheus, timestamped now
+    pub fn to_openmetrics(&self, system_name: &str) -> String {
+        render_openmetrics(&[(system_name, self.clone())], chrono::Utc::now().timestamp())
     }
 
-    /// Remove a system from the manager
-    pub fn remove_system(&mut self, name: &str) -> Option<Box<dyn System>> {
-        self.systems.remove(name)
+    /// Sample values in the order of `OPENMETRICS_FAMILIES`
+    fn openmetrics_values(&self) -> [String; 5] {
+        [
+            self.objects_processed.to_string(),
+            self.actions_executed.to_string(),
+            self.errors.to_string(),
+            self.avg_processing_time_ms.to_string(),
+            self.peak_processing_time_ms.to_string(),
+        ]
     }
+}
 
-    /// Get a system by name
-    pub fn get_system(&self, name: &str) -> Option<&Box<dyn System>> {
-        self.systems.get(name)
+/// Metric families exported by `SystemStats::to_openmetrics`, as (name, type)
+const OPENMETRICS_FAMILIES: [(&str, &str); 5] = [
+    ("oats_objects_processed", "counter"),
+    ("oats_actions_executed", "counter"),
+    ("oats_errors", "counter"),
+    ("oats_avg_processing_time_ms", "gauge"),
+    ("oats_peak_processing_time_ms", "gauge"),
+];
+
+/// Render one sample per system for each metric family, grouped by family and ending in `# EOF`
+fn render_openmetrics(stats: &[(&str, SystemStats)], timestamp: i64) -> String {
+    let labelled: Vec<(String, [String; 5])> = stats
+        .iter()
+        .map(|(name, stats)| {
+            let label = name.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            (label, stats.openmetrics_values())
+        })
+        .collect();
+
+    let mut page = String::new();
+    for (i, (family, metric_type)) in OPENMETRICS_FAMILIES.iter().enumerate() {
+        let suffix = if *metric_type == "counter" { "_total" } else { "" };
+        page.push_str(&format!("# TYPE {} {}\n", family, metric_type));
+        for (label, values) in &labelled {
+            page.push_str(&format!("{}{}{{system=\"{}\"}} {} {}\n", family, suffix, label, values[i], timestamp));
+        }
     }
+    page.push_str("# EOF\n");
+    page
+}
 
-    /// Get all systems
-    pub fn systems(&self) -> &HashMap<String, Box<dyn System>> {
-        &self.systems
+/// Counts from a `SystemManager::process_and_apply` run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessingSummary {
+    /// Number of action results produced
+    pub total_actions: usize,
+    /// Number of trait updates written to the registry
+    pub total_updates: usize,
+    /// Number of failed results plus results that could not be applied
+    pub total_errors: usize,
+    /// Number of expired traits removed before processing
+    #[serde(default)]
+    pub expired_traits_purged: usize,
+}
+
+/// A change to a registered object, sent to `SystemManager::object_changes` subscribers
+#[derive(Debug, Clone)]
+pub enum ObjectChange {
+    /// A new object was registered
+    Added(Object),
+    /// A registered object was replaced or had an action result applied
+    Updated(Object),
+    /// An object was removed
+    Removed(Object),
+}
+
+impl ObjectChange {
+    /// Get the object as it is after the change, or as it was when removed
+    pub fn object(&self) -> &Object {
+        match self {
+            ObjectChange::Added(object) | ObjectChange::Updated(object) | ObjectChange::Removed(object) => object,
+        }
     }
+}
 
-    /// Get system count
-    pub fn system_count(&self) -> usize {
-        self.systems.len()
+/// Progress of a `SystemManager::run_with_progress` run, reported after each system
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    /// System that just finished its batch
+    pub system_name: String,
+    /// Objects processed so far, summed over systems
+    pub objects_processed: usize,
+    /// Objects the run will process, summed over systems
+    pub total_objects: usize,
+    /// `objects_processed` as a percentage of `total_objects`
+    pub percentage: f64,
+}
+
+impl ProgressEvent {
+    /// Create an event, computing the percentage
+    pub fn new(system_name: impl Into<String>, objects_processed: usize, total_objects: usize) -> Self {
+        let percentage = if total_objects == 0 {
+            100.0
+        } else {
+            objects_processed as f64 * 100.0 / total_objects as f64
+        };
+        Self {
+            system_name: system_name.into(),
+            objects_processed,
+            total_objects,
+            percentage,
+        }
     }
+}
 
-    /// Register an object with the manager
-    pub async fn register_object(&self, object: Object) {
-        let mut registry = self.object_registry.write().await;
-        registry.insert(object.id.to_string(), object);
+/// Predicted outcome of a `SystemManager::dry_run_process_all` run
+#[derive(Debug, Clone, Default)]
+pub struct DryRunReport {
+    /// Results the systems produced
+    pub results: Vec<ActionResult>,
+    /// Trait changes the run would make, for each object it would change or add
+    pub predicted_object_changes: HashMap<ObjectId, ObjectDiff>,
+    /// Objects the run would add
+    pub added_objects: Vec<Object>,
+    /// IDs of the objects the run would remove
+    pub removed_objects: Vec<ObjectId>,
+}
+
+impl DryRunReport {
+    /// Count the trait updates carried by successful results
+    pub fn total_trait_updates(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|result| !result.is_failure())
+            .map(ActionResult::trait_update_count)
+            .sum()
     }
 
-    /// Get an object by ID
-    pub async fn get_object(&self, id: &str) -> Option<Object> {
-        let registry = self.object_registry.read().await;
-        registry.get(id).cloned()
+    /// Check whether any result failed
+    pub fn has_any_failures(&self) -> bool {
+        self.results.iter().any(ActionResult::is_failure)
     }
+}
 
-    /// Get all objects
-    pub async fn get_all_objects(&self) -> Vec<Object> {
-        let registry = self.object_registry.read().await;
-        registry.values().cloned().collect()
+/// Aggregate type, trait and metadata statistics over the live objects in a `SystemManager`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObjectStats {
+    /// Number of live objects
+    pub total_objects: usize,
+    /// Number of live objects of each type
+    pub by_type: HashMap<String, usize>,
+    /// Number of traits summed over all objects
+    pub total_traits: usize,
+    /// `total_traits` divided by `total_objects`, or zero without objects
+    pub avg_traits_per_object: f64,
+    /// Number of objects carrying each trait name
+    pub trait_name_frequency: HashMap<String, usize>,
+    /// Number of objects carrying each metadata key
+    pub metadata_key_frequency: HashMap<String, usize>,
+}
+
+impl ObjectStats {
+    /// Accumulate statistics over `objects` in a single pass
+    pub fn from_objects<'a>(objects: impl IntoIterator<Item = &'a Object>) -> Self {
+        let mut stats = Self::default();
+        for object in objects {
+            stats.total_objects += 1;
+            stats.total_traits += object.trait_count();
+            *stats.by_type.entry(object.object_type().to_string()).or_insert(0) += 1;
+            for name in object.traits().keys() {
+                *stats.trait_name_frequency.entry(name.clone()).or_insert(0) += 1;
+            }
+            for key in object.metadata().keys() {
+                *stats.metadata_key_frequency.entry(key.clone()).or_insert(0) += 1;
+            }
+        }
+        if stats.total_objects > 0 {
+            stats.avg_traits_per_object = stats.total_traits as f64 / stats.total_objects as f64;
+        }
+        stats
     }
 
-    /// Get object count
-    pub async fn object_count(&self) -> usize {
-        let registry = self.object_registry.read().await;
-        registry.len()
+    /// Render the statistics as a human-readable table, most frequent entries first
+    pub fn to_report(&self) -> String {
+        let mut report = format!(
+            "Objects: {} | Traits: {} | Avg traits per object: {:.2}\n",
+            self.total_objects, self.total_traits, self.avg_traits_per_object
+        );
+        for (title, counts) in [
+            ("Type", &self.by_type),
+            ("Trait", &self.trait_name_frequency),
+            ("Metadata key", &self.metadata_key_frequency),
+        ] {
+            if counts.is_empty() {
+                continue;
+            }
+            let mut rows: Vec<_> = counts.iter().collect();
+            rows.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max(title.len());
+            report.push_str(&format!("{:<width$}  Count\n", title, width = width));
+            for (name, count) in rows {
+                report.push_str(&format!("{:<width$}  {:>5}\n", name, count, width = width));
+            }
+        }
+        report
     }
+}
 
-    /// Clear all objects
-    pub async fn clear_objects(&self) {
-        let mut registry = self.object_registry.write().await;
-        registry.clear();
+/// A system that runs streaming actions and applies each result to the registry as it arrives
+pub struct StreamingSystem {
+    name: String,
+    description: String,
+    priority: Priority,
+    actions: Vec<Box<dyn StreamingAction>>,
+    registry: ObjectRegistry,
+    stats: SystemStats,
+}
+
+impl StreamingSystem {
+    /// Create a new streaming system writing into the given registry
+    pub fn new(name: impl Into<String>, description: impl Into<String>, registry: ObjectRegistry) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            priority: Priority::Normal,
+            actions: Vec::new(),
+            registry,
+            stats: SystemStats::default(),
+        }
     }
 
-    /// Reserve capacity for objects
-    pub async fn reserve_objects(&self, additional: usize) {
-        let mut registry = self.object_registry.write().await;
-        registry.reserve(additional);
+    /// Set the priority of this system
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
     }
 
-    /// Process all objects through all systems
-    pub async fn process_all(&mut self, priority: Priority) -> Result<Vec<ActionResult>> {
-        let objects = self.get_all_objects().await;
-        let mut all_results = Vec::new();
+    /// Add a streaming action to the system
+    pub fn add_action(&mut self, action: Box<dyn StreamingAction>) {
+        self.actions.push(action);
+    }
 
-        // Sort systems by priority (highest first)
-        let mut system_names: Vec<_> = self.systems.keys().cloned().collect();
-        system_names.sort_by(|a, b| {
-            let a_priority = self.systems.get(a).map(|s| s.priority()).unwrap_or(Priority::Normal);
-            let b_priority = self.systems.get(b).map(|s| s.priority()).unwrap_or(Priority::Normal);
-            b_priority.cmp(&a_priority)
-        });
+    /// Get action count
+    pub fn action_count(&self) -> usize {
+        self.actions.len()
+    }
 
-        for system_name in system_names {
-            if let Some(system) = self.systems.get_mut(&system_name) {
-                if system.is_ready() {
-                    match system.process(objects.clone(), priority).await {
-                        Ok(results) => all_results.extend(results),
+    /// Stream every action over every object, starting each context from `shared`
+    async fn run_actions(&mut self, objects: &[Object], shared: &ActionContext<'_>) -> Result<Vec<ActionResult>> {
+        let mut results = Vec::new();
+        let start_time = std::time::Instant::now();
+
+        for object in objects {
+            let object_id = object.id.to_string();
+
+            for action in &self.actions {
+                let context = shared.for_target(object);
+                if let Err(e) = action.validate_context(&context).await {
+                    self.stats.errors += 1;
+                    results.push(ActionResult::failure(format!("Stream '{}' failed validation: {}", action.name(), e)));
+                    continue;
+                }
+
+                let mut stream = match action.stream_execute(context).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        self.stats.errors += 1;
+                        results.push(ActionResult::failure(format!("Stream '{}' failed to start: {}", action.name(), e)));
+                        continue;
+                    }
+                };
+
+                while let Some(item) = stream.next().await {
+                    match item {
+                        Ok(result) => {
+                            self.apply_result(&object_id, &result).await;
+                            self.stats.actions_executed += 1;
+                            results.push(result);
+                        }
                         Err(e) => {
-                            let error_result = ActionResult::failure(format!("System error: {}", e));
-                            all_results.push(error_result);
+                            self.stats.errors += 1;
+                            results.push(ActionResult::failure(format!("Stream '{}' failed: {}", action.name(), e)));
                         }
                     }
                 }
             }
+            self.stats.objects_processed += 1;
         }
 
-        Ok(all_results)
+        self.stats.update_processing_time(start_time.elapsed().as_millis() as u64);
+        self.stats.last_processed = Some(chrono::Utc::now());
+
+        Ok(results)
     }
 
-    /// Process objects through a specific system
-    pub async fn process_with_system(
+    /// Apply a streamed result to the registered object it was produced for
+    ///
+    /// While a `SystemManager` runs this system, the result is applied through the manager,
+    /// as `apply_action_result` would, so change subscribers and the spatial index see it.
+    async fn apply_result(&self, object_id: &str, result: &ActionResult) {
+        if !result.is_success() || result.trait_updates.is_empty() {
+            return;
+        }
+        match ObjectStore::current() {
+            Some(store) => {
+                let _ = store.write().await.update(object_id, |object| result.apply_to(object));
+            }
+            None => {
+                if let Some(object) = self.registry.write().await.get_mut(object_id) {
+                    result.apply_to(object);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl System for StreamingSystem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+        self.run_actions(objects, &ActionContext::new()).await
+    }
+
+    async fn process_with_context(
         &mut self,
-        system_name: &str,
-        objects: Vec<Object>,
-        priority: Priority,
+        objects: &[Object],
+        _priority: Priority,
+        shared: &ActionContext<'_>,
     ) -> Result<Vec<ActionResult>> {
-        let system = self
-            .systems
-            .get_mut(system_name)
-            .ok_or_else(|| OatsError::system_error(format!("System '{}' not found", system_name)))?;
-
-        if !system.is_ready() {
-            return Err(OatsError::system_error("System is not ready"));
-        }
+        self.run_actions(objects, shared).await
+    }
 
-        system.process(objects, priority).await
+    fn priority(&self) -> Priority {
+        self.priority
     }
 
-    /// Initialize all systems
-    pub async fn initialize_all(&mut self) -> Result<()> {
-        for (name, system) in &mut self.systems {
-            if let Err(e) = system.initialize().await {
-                return Err(OatsError::system_error(format!(
-                    "Failed to initialize system '{}': {}",
-                    name, e
-                )));
-            }
-        }
+    async fn drain(&mut self) -> Result<()> {
+        self.stats.reset();
         Ok(())
     }
 
-    /// Shutdown all systems
-    pub async fn shutdown_all(&mut self) -> Result<()> {
-        for (name, system) in &mut self.systems {
-            if let Err(e) = system.shutdown().await {
-                return Err(OatsError::system_error(format!(
-                    "Failed to shutdown system '{}': {}",
-                    name, e
-                )));
-            }
+    fn get_stats(&self) -> SystemStats {
+        self.stats.clone()
+    }
+}
+
+/// A system that soft-deletes or removes objects not updated within `max_age`
+pub struct GarbageCollectionSystem {
+    name: String,
+    max_age: std::time::Duration,
+    filter: ObjectFilter,
+    hard_delete: bool,
+    registry: ObjectRegistry,
+    stats: SystemStats,
+}
+
+impl GarbageCollectionSystem {
+    /// Create a collector for objects matching `filter` in the given registry
+    ///
+    /// While a `SystemManager` runs the collector, its objects are collected through the
+    /// manager instead, keeping per-type counts, the spatial index and subscribers in sync.
+    pub fn new(name: impl Into<String>, registry: ObjectRegistry, max_age: std::time::Duration, filter: ObjectFilter) -> Self {
+        Self {
+            name: name.into(),
+            max_age,
+            filter,
+            hard_delete: false,
+            registry,
+            stats: SystemStats::default(),
         }
-        Ok(())
     }
 
-    /// Get statistics for all systems
-    pub fn get_all_stats(&self) -> HashMap<String, SystemStats> {
-        self.systems
-            .iter()
-            .map(|(name, system)| (name.clone(), system.get_stats()))
-            .collect()
+    /// Remove collected objects from the registry instead of soft-deleting them
+    pub fn with_hard_delete(mut self, hard_delete: bool) -> Self {
+        self.hard_delete = hard_delete;
+        self
     }
-}
 
-impl Default for SystemManager {
-    fn default() -> Self {
-        Self::new()
+    /// Check if an object is a collection candidate at `now`
+    fn is_stale(&self, object: &Object, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let age = (now - object.updated_at()).to_std().unwrap_or_default();
+        age > self.max_age && self.filter.matches(object)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[async_trait]
+impl System for GarbageCollectionSystem {
+    fn name(&self) -> &str {
+        &self.name
+    }
 
-    #[test]
-    fn test_priority_ordering() {
-        assert!(Priority::Critical > Priority::High);
-        assert!(Priority::High > Priority::Normal);
-        assert!(Priority::Normal > Priority::Low);
+    fn description(&self) -> &str {
+        "Collects objects that have not been updated recently"
+    }
+
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+        let start_time = std::time::Instant::now();
+        let now = chrono::Utc::now();
+        let stale: Vec<String> = objects
+            .iter()
+            .filter(|object| self.is_stale(object, now))
+            .map(|object| object.id.to_string())
+            .collect();
+
+        let mut results = Vec::with_capacity(stale.len());
+        let collected = match ObjectStore::current() {
+            Some(store) => {
+                let mut store = store.write().await;
+                stale
+                    .iter()
+                    .filter_map(|id| {
+                        if self.hard_delete {
+                            store.remove(id).map(|removed| removed.name)
+                        } else {
+                            let mut name = None;
+                            store.update(id, |object| {
+                                object.soft_delete();
+                                name = Some(object.name.clone());
+                            }).ok()?;
+                            name
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }
+            None => {
+                let mut registry = self.registry.write().await;
+                stale
+                    .iter()
+                    .filter_map(|id| {
+                        if self.hard_delete {
+                            registry.remove(id).map(|removed| removed.name)
+                        } else {
+                            let object = registry.get_mut(id)?;
+                            object.soft_delete();
+                            Some(object.name.clone())
+                        }
+                    })
+                    .collect()
+            }
+        };
+        for name in collected {
+            self.stats.gc_collected += 1;
+            let mut result = ActionResult::success();
+            result.add_message(format!("Collected stale object {}", name));
+            results.push(result);
+        }
+
+        self.stats.objects_processed += objects.len() as u64;
+        self.stats.update_processing_time(start_time.elapsed().as_millis() as u64);
+        self.stats.last_processed = Some(now);
+        Ok(results)
+    }
+
+    fn priority(&self) -> Priority {
+        Priority::Low
+    }
+
+    async fn drain(&mut self) -> Result<()> {
+        self.stats.reset();
+        Ok(())
+    }
+
+    fn get_stats(&self) -> SystemStats {
+        self.stats.clone()
+    }
+}
+
+/// A system manager that coordinates multiple systems
+pub struct SystemManager {
+    systems: HashMap<String, Box<dyn System>>,
+    typed_systems: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    system_filters: HashMap<String, ObjectFilter>,
+    migrations: Option<Arc<TraitMigrationRegistry>>,
+    serializers: SerializationRegistry,
+    allowed_types: HashSet<String>,
+    strict_types: bool,
+    max_objects: Option<usize>,
+    memory_limit_bytes: Option<usize>,
+    transaction_depth: Arc<AtomicUsize>,
+    last_process_time: Option<chrono::DateTime<chrono::Utc>>,
+    warm_up_durations: HashMap<String, u64>,
+    auto_purge_expired: bool,
+    pipes: Vec<OutputPipe>,
+    concurrency_limits: crate::actions::ConcurrencyLimits,
+    #[cfg(feature = "reactive")]
+    events: tokio::sync::broadcast::Sender<crate::observable::SystemEvent>,
+    services: ServiceLocator,
+    #[cfg(feature = "snapshot")]
+    journal: crate::actions::ActionJournal,
+    #[cfg(feature = "snapshot")]
+    journal_enabled: bool,
+    #[cfg(feature = "snapshot")]
+    replay_actions: HashMap<String, Box<dyn crate::actions::Action>>,
+    store: ObjectStore,
+}
+
+/// Messages buffered per broadcast subscriber before the oldest are dropped
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Progress callback shared across awaits; the mutex keeps the processing future `Send`
+type ProgressCallback = std::sync::Mutex<Box<dyn Fn(ProgressEvent) + Send>>;
+
+/// Optional restrictions and observers for one processing run
+#[derive(Clone, Copy, Default)]
+struct RunOptions<'a> {
+    /// Only run systems with this priority
+    lane: Option<Priority>,
+    /// Only process objects updated after this time
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Start every action context from this context
+    shared: Option<&'a ActionContext<'a>>,
+    /// Report progress after each system
+    progress: Option<&'a ProgressCallback>,
+}
+
+/// Task-local state that every processing run executes inside
+#[derive(Clone)]
+struct RunScope {
+    services: ServiceLocator,
+    store: ObjectStore,
+    task_id: String,
+    limits: crate::actions::ConcurrencyLimits,
+    #[cfg(feature = "snapshot")]
+    journal: Option<crate::actions::ActionJournal>,
+}
+
+impl RunScope {
+    /// Run `f` with the services, object store, task ID, concurrency limits and, if journaling, the journal in scope
+    async fn run<F: std::future::Future>(self, f: F) -> F::Output {
+        // Boxed so the nested scopes do not all sit inline on the caller's stack
+        let f = Box::pin(f);
+        let store = self.store;
+        let limits = self.limits;
+        let f = async move {
+            let f = crate::actions::with_process_task_id(self.task_id, f);
+            if limits.is_empty() {
+                f.await
+            } else {
+                crate::actions::with_concurrency_limits(limits, f).await
+            }
+        };
+        #[cfg(feature = "snapshot")]
+        if let Some(journal) = self.journal {
+            let journaled = crate::actions::with_action_journal(journal, f);
+            return store.scope(crate::actions::with_process_services(self.services, journaled)).await;
+        }
+        store.scope(crate::actions::with_process_services(self.services, f)).await
+    }
+
+    /// Run `f` synchronously on a pool thread with this scope entered
+    ///
+    /// Actions cannot be awaited here, so the journal is not entered.
+    #[cfg(feature = "work_stealing")]
+    fn run_sync<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.store.clone().scope_sync(|| {
+            crate::actions::with_process_scope_sync(self.services.clone(), self.task_id.clone(), f)
+        })
+    }
+
+    /// Tag each result with the run's task ID
+    fn tag_results(task_id: &str, results: &mut [ActionResult]) {
+        for result in results {
+            result.add_data("task_id", serde_json::json!(task_id));
+        }
+    }
+}
+
+/// Swaps a shadow store into a `SystemManager` for a dry run, swapping the real one back on drop
+///
+/// The shadow store has no subscribers, and system events go to a channel nobody listens
+/// on, so nothing is published even if the dry run is cancelled part-way.
+struct DryRunGuard<'a> {
+    manager: &'a mut SystemManager,
+    store: ObjectStore,
+    last_process_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[cfg(feature = "reactive")]
+    events: broadcast::Sender<crate::observable::SystemEvent>,
+}
+
+impl<'a> DryRunGuard<'a> {
+    fn enter(manager: &'a mut SystemManager, shadow: ObjectStore) -> Self {
+        Self {
+            store: std::mem::replace(&mut manager.store, shadow),
+            last_process_time: manager.last_process_time,
+            #[cfg(feature = "reactive")]
+            events: std::mem::replace(&mut manager.events, broadcast::channel(1).0),
+            manager,
+        }
+    }
+}
+
+impl Drop for DryRunGuard<'_> {
+    fn drop(&mut self) {
+        std::mem::swap(&mut self.manager.store, &mut self.store);
+        self.manager.last_process_time = self.last_process_time;
+        #[cfg(feature = "reactive")]
+        std::mem::swap(&mut self.manager.events, &mut self.events);
+    }
+}
+
+/// Marks a `SystemManager::transaction` as running until dropped, even if its future is cancelled
+struct TransactionDepthGuard(Arc<AtomicUsize>);
+
+impl TransactionDepthGuard {
+    /// Enter a transaction, or return `None` if one is already running
+    fn enter(depth: &Arc<AtomicUsize>) -> Option<Self> {
+        depth
+            .compare_exchange(0, 1, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self(Arc::clone(depth)))
+    }
+}
+
+impl Drop for TransactionDepthGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Routing rule feeding one system's updated objects into another in the same tick
+#[derive(Debug, Clone)]
+struct OutputPipe {
+    from: String,
+    to: String,
+    object_name: String,
+}
+
+impl SystemManager {
+    /// Create a new system manager
+    pub fn new() -> Self {
+        Self::with_capacity(100)
+    }
+
+    /// Create a new system manager with expected capacity
+    pub fn with_capacity(expected_objects: usize) -> Self {
+        Self {
+            systems: HashMap::new(),
+            typed_systems: HashMap::new(),
+            system_filters: HashMap::new(),
+            migrations: None,
+            serializers: SerializationRegistry::new(),
+            allowed_types: HashSet::new(),
+            strict_types: false,
+            max_objects: None,
+            memory_limit_bytes: None,
+            transaction_depth: Arc::new(AtomicUsize::new(0)),
+            last_process_time: None,
+            warm_up_durations: HashMap::new(),
+            auto_purge_expired: true,
+            pipes: Vec::new(),
+            concurrency_limits: Arc::new(HashMap::new()),
+            #[cfg(feature = "reactive")]
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            services: ServiceLocator::new(),
+            #[cfg(feature = "snapshot")]
+            journal: Default::default(),
+            #[cfg(feature = "snapshot")]
+            journal_enabled: false,
+            #[cfg(feature = "snapshot")]
+            replay_actions: HashMap::new(),
+            store: ObjectStore::with_capacity(expected_objects),
+        }
+    }
+
+    /// Attach a trait migration registry used to migrate objects lazily in `get_object`
+    pub fn with_migration_registry(mut self, registry: TraitMigrationRegistry) -> Self {
+        self.migrations = Some(Arc::new(registry));
+        self
+    }
+
+    /// Give every action context created while processing access to `services`
+    pub fn with_services(mut self, services: ServiceLocator) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// Get the services given to action contexts
+    pub fn services(&self) -> &ServiceLocator {
+        &self.services
+    }
+
+    /// Cap the number of registered objects
+    pub fn with_max_objects(mut self, limit: usize) -> Self {
+        self.max_objects = Some(limit);
+        self
+    }
+
+    /// Get the configured object limit, if any
+    pub fn object_limit(&self) -> Option<usize> {
+        self.max_objects
+    }
+
+    /// Cap the total `Object::estimated_memory_size` of registered objects
+    ///
+    /// The limit is checked on registration only. Applying action results can grow objects
+    /// past it, and the next registration is rejected until objects are removed.
+    pub fn with_memory_limit_bytes(mut self, limit: usize) -> Self {
+        self.memory_limit_bytes = Some(limit);
+        self
+    }
+
+    /// Get the configured memory limit in bytes, if any
+    pub fn memory_limit_bytes(&self) -> Option<usize> {
+        self.memory_limit_bytes
+    }
+
+    /// Purge expired traits from every object at the start of `process_and_apply` (default true)
+    pub fn with_auto_purge_expired(mut self, enabled: bool) -> Self {
+        self.auto_purge_expired = enabled;
+        self
+    }
+
+    /// Reject objects whose type was not added with `register_object_type`
+    pub fn with_strict_type_checking(mut self, strict: bool) -> Self {
+        self.strict_types = strict;
+        self
+    }
+
+    /// Replace the serializers used by `export_object`
+    pub fn with_serialization_registry(mut self, registry: SerializationRegistry) -> Self {
+        self.serializers = registry;
+        self
+    }
+
+    /// Maintain a spatial index over objects' `position` traits
+    ///
+    /// The index is updated on `register_object` and rebuilt at the start of each `process_all`.
+    pub fn with_spatial_index(mut self) -> Self {
+        self.store.spatial_index = Some(Arc::new(RwLock::new(SpatialIndex2D::new())));
+        self
+    }
+
+    /// Get a shared handle to the spatial index, if enabled
+    pub fn spatial_index(&self) -> Option<Arc<RwLock<SpatialIndex2D>>> {
+        self.store.spatial_index.as_ref().map(Arc::clone)
+    }
+
+    /// Add a system to the manager
+    pub fn add_system(&mut self, system: Box<dyn System>) {
+        let name = system.name().to_string();
+        self.warm_up_durations.remove(&name);
+        self.systems.insert(name, system);
+    }
+
+    /// Remove a system from the manager
+    pub fn remove_system(&mut self, name: &str) -> Option<Box<dyn System>> {
+        self.system_filters.remove(name);
+        self.warm_up_durations.remove(name);
+        self.systems.remove(name)
+    }
+
+    /// Feed the objects updated by `from_system` into `to_system` in the same processing tick
+    ///
+    /// After `from_system` runs, its successful results are applied to the objects named by
+    /// their `target_id`, and each updated object is processed by `to_system` with the object
+    /// also added to the context as `object_name_in_context`. `to_system` then only runs on
+    /// piped objects while `from_system` is part of the run.
+    pub fn pipe_output(&mut self, from_system: &str, to_system: &str, object_name_in_context: &str) -> Result<()> {
+        if from_system == to_system {
+            return Err(OatsError::validation_error(format!(
+                "Cannot pipe system '{}' into itself",
+                from_system
+            )));
+        }
+        self.pipes.push(OutputPipe {
+            from: from_system.to_string(),
+            to: to_system.to_string(),
+            object_name: object_name_in_context.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Replace a registered system with a new version, returning the old one
+    ///
+    /// The old system is shut down before the new one is initialized. If the new
+    /// system fails to initialize, the old system is re-initialized and kept, even if
+    /// re-initializing it fails too; the error then reports both failures.
+    /// The new system must have the same name, and keeps any assigned filter.
+    pub async fn swap_system<S: HotSwapable + 'static>(&mut self, name: &str, new_system: Box<S>) -> Result<Box<dyn System>> {
+        if new_system.name() != name {
+            return Err(OatsError::validation_error(format!(
+                "Cannot swap system '{}' for differently named system '{}'",
+                name,
+                new_system.name()
+            )));
+        }
+        let mut old_system = self
+            .systems
+            .remove(name)
+            .ok_or_else(|| OatsError::system_error(format!("System '{}' not found", name)))?;
+
+        let mut new_system: Box<dyn System> = new_system;
+        let swapped = match old_system.shutdown().await {
+            Ok(()) => new_system.initialize().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = swapped {
+            let restored = old_system.initialize().await;
+            self.systems.insert(name.to_string(), old_system);
+            return Err(match restored {
+                Ok(()) => OatsError::system_error(format!("Failed to swap system '{}': {}", name, e)),
+                Err(restore_error) => OatsError::system_error(format!(
+                    "Failed to swap system '{}': {}; re-initializing the old system also failed: {}",
+                    name, e, restore_error
+                )),
+            });
+        }
+
+        self.warm_up_durations.remove(name);
+        self.systems.insert(name.to_string(), new_system);
+        Ok(old_system)
+    }
+
+    /// Route only objects matching `filter` to the named system in `process_all`
+    ///
+    /// Systems without an assigned filter receive every object.
+    pub fn assign_system_filter(&mut self, system_name: &str, filter: ObjectFilter) {
+        self.system_filters.insert(system_name.to_string(), filter);
+    }
+
+    /// Limit how many actions the named system may dispatch through `Action::execute_scoped` at once
+    ///
+    /// Dispatches past the limit wait for a permit, whichever method processes the system.
+    /// Systems are unbounded by default, and a limit of zero is rejected.
+    pub fn set_system_concurrency_limit(&mut self, system_name: &str, limit: usize) -> Result<()> {
+        if limit == 0 {
+            return Err(OatsError::validation_error(format!(
+                "Concurrency limit for system '{}' must be at least 1",
+                system_name
+            )));
+        }
+        Arc::make_mut(&mut self.concurrency_limits)
+            .insert(system_name.to_string(), Arc::new(tokio::sync::Semaphore::new(limit)));
+        Ok(())
+    }
+
+    /// Get the semaphore limiting the named system, to share its limit with other callers
+    pub fn system_concurrency_limiter(&self, system_name: &str) -> Option<Arc<tokio::sync::Semaphore>> {
+        self.concurrency_limits.get(system_name).map(Arc::clone)
+    }
+
+    /// Get a system by name
+    #[allow(clippy::borrowed_box)]
+    pub fn get_system(&self, name: &str) -> Option<&Box<dyn System>> {
+        self.systems.get(name)
+    }
+
+    /// Add a system while keeping typed access to it through `get_typed_system`
+    ///
+    /// The system also takes part in `process_all` like any other system. Only one
+    /// system per concrete type can be registered; a second call replaces the first.
+    pub fn add_typed_system<T: System + 'static>(&mut self, system: T) -> Arc<Mutex<T>> {
+        let name = system.name().to_string();
+        let adapter = TypedSystemAdapter {
+            name: name.clone(),
+            description: system.description().to_string(),
+            priority: system.priority(),
+            inner: Arc::new(Mutex::new(system)),
+        };
+        let handle = Arc::clone(&adapter.inner);
+
+        // Find the previous registration by its adapter type, so a busy system is still removed
+        self.systems.retain(|_, system| {
+            let system: &dyn Any = &**system;
+            !system.is::<TypedSystemAdapter<T>>()
+        });
+        self.typed_systems.insert(TypeId::of::<T>(), Box::new(Arc::clone(&handle)));
+        self.systems.insert(name, Box::new(adapter));
+        handle
+    }
+
+    /// Get a typed system registered with `add_typed_system`
+    pub fn get_typed_system<T: System + 'static>(&self) -> Option<Arc<Mutex<T>>> {
+        self.typed_systems
+            .get(&TypeId::of::<T>())
+            .and_then(|handle| handle.downcast_ref::<Arc<Mutex<T>>>())
+            .map(Arc::clone)
+    }
+
+    /// Process all objects through the typed system registered for `T`
+    pub async fn process_typed<T: System + 'static>(&self, priority: Priority) -> Result<Vec<ActionResult>> {
+        let handle = self.get_typed_system::<T>().ok_or_else(|| {
+            OatsError::system_error(format!("Typed system '{}' not found", std::any::type_name::<T>()))
+        })?;
+        let objects = self.get_all_objects().await;
+        let mut system = handle.lock().await;
+
+        if !system.is_ready() {
+            return Err(OatsError::system_error("System is not ready"));
+        }
+
+        run_with_hooks(&mut *system, &objects, priority, None).await
+    }
+
+    /// Get all systems
+    pub fn systems(&self) -> &HashMap<String, Box<dyn System>> {
+        &self.systems
+    }
+
+    /// Get system count
+    pub fn system_count(&self) -> usize {
+        self.systems.len()
+    }
+
+    /// Get a shared handle to the object registry
+    pub fn registry(&self) -> ObjectRegistry {
+        Arc::clone(&self.store.registry)
+    }
+
+    /// Register an object with the manager
+    ///
+    /// With strict type checking enabled, objects of unregistered types are rejected.
+    /// New objects are rejected once the registry holds `with_max_objects` objects, and any
+    /// object is rejected if it would push the estimated size past `with_memory_limit_bytes`.
+    pub async fn register_object(&self, object: Object) -> Result<()> {
+        if self.strict_types && !self.allowed_types.contains(&object.object_type) {
+            return Err(OatsError::validation_error(format!(
+                "Unknown object type '{}'",
+                object.object_type
+            )));
+        }
+        let mut store = self.store.write().await;
+        if let Some(limit) = self.max_objects {
+            if store.registry.len() >= limit && !store.registry.contains_key(&object.id.to_string()) {
+                return Err(OatsError::resource_exhausted("object registry full"));
+            }
+        }
+        self.check_memory_limit(&store.registry, std::slice::from_ref(&object))?;
+        store.insert(object);
+        Ok(())
+    }
+
+    /// Register many objects under a single write lock
+    ///
+    /// Every object is checked against the type and capacity limits first, so either all
+    /// objects are registered or none are.
+    pub async fn register_objects_bulk(&self, objects: impl IntoIterator<Item = Object>) -> Result<()> {
+        let objects: Vec<Object> = objects.into_iter().collect();
+        if self.strict_types {
+            if let Some(object) = objects.iter().find(|o| !self.allowed_types.contains(&o.object_type)) {
+                return Err(OatsError::validation_error(format!(
+                    "Unknown object type '{}'",
+                    object.object_type
+                )));
+            }
+        }
+
+        let mut store = self.store.write().await;
+        if let Some(limit) = self.max_objects {
+            let new_keys: HashSet<String> = objects
+                .iter()
+                .map(|o| o.id.to_string())
+                .filter(|key| !store.registry.contains_key(key))
+                .collect();
+            if store.registry.len() + new_keys.len() > limit {
+                return Err(OatsError::resource_exhausted("object registry full"));
+            }
+        }
+        self.check_memory_limit(&store.registry, &objects)?;
+        for object in objects {
+            store.insert(object);
+        }
+        Ok(())
+    }
+
+    /// Reject `incoming` if registering it would exceed the memory limit; replaced objects are not counted
+    fn check_memory_limit(&self, registry: &HashMap<String, Object>, incoming: &[Object]) -> Result<()> {
+        let Some(limit) = self.memory_limit_bytes else { return Ok(()) };
+        let incoming_ids: HashSet<String> = incoming.iter().map(|o| o.id.to_string()).collect();
+        let replaced: usize = incoming_ids
+            .iter()
+            .filter_map(|id| registry.get(id))
+            .map(Object::estimated_memory_size)
+            .sum();
+        let required = self.store.memory_bytes().saturating_sub(replaced)
+            + incoming.iter().map(Object::estimated_memory_size).sum::<usize>();
+        if required > limit {
+            return Err(OatsError::resource_exhausted(format!(
+                "memory limit exceeded: {} of {} bytes",
+                required, limit
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sum `Object::estimated_memory_size` across all registered objects
+    ///
+    /// This is a running total; objects written directly through `registry()` are counted
+    /// from the next processing run.
+    pub async fn total_estimated_memory_bytes(&self) -> usize {
+        self.store.memory_bytes()
+    }
+
+    /// Remove many objects under a single write lock, returning each in the order of `ids`
+    pub async fn remove_objects_bulk(&self, ids: &[ObjectId]) -> Vec<Option<Object>> {
+        let mut store = self.store.write().await;
+        ids.iter().map(|id| store.remove(&id.to_string())).collect()
+    }
+
+    /// Remove the `n` objects with the earliest `created_at`, returning them
+    pub async fn evict_oldest_n(&self, n: usize) -> Vec<Object> {
+        let mut store = self.store.write().await;
+        let mut oldest: Vec<(chrono::DateTime<chrono::Utc>, String)> = store
+            .registry
+            .iter()
+            .map(|(id, object)| (object.created_at, id.clone()))
+            .collect();
+        oldest.sort();
+        oldest.into_iter().take(n).filter_map(|(_, id)| store.remove(&id)).collect()
+    }
+
+    /// Create a garbage collector over this manager's objects
+    pub fn garbage_collection_system(
+        &self,
+        name: impl Into<String>,
+        max_age: std::time::Duration,
+        filter: ObjectFilter,
+    ) -> GarbageCollectionSystem {
+        GarbageCollectionSystem::new(name, self.registry(), max_age, filter)
+    }
+
+    /// Allow objects of `type_name` to be registered in strict mode
+    pub fn register_object_type(&mut self, type_name: &str) {
+        self.allowed_types.insert(type_name.to_string());
+    }
+
+    /// Remove an object from the manager
+    pub async fn remove_object(&self, id: &str) -> Option<Object> {
+        self.store.write().await.remove(id)
+    }
+
+    /// Subscribe to every object change
+    ///
+    /// Objects are reported when registered, removed or updated through `apply_action_result`
+    /// (which `process_and_apply` uses); edits made directly through `registry()` are not seen.
+    pub fn object_changes(&self) -> broadcast::Receiver<ObjectChange> {
+        self.store.subscribe()
+    }
+
+    /// Subscribe to changes of objects of one type, without filtering the global stream
+    pub fn subscribe_to_object_type(&self, type_name: &str) -> broadcast::Receiver<ObjectChange> {
+        self.store.subscribe_to_type(type_name)
+    }
+
+    /// Get the number of registered objects of a type without locking the registry
+    ///
+    /// Objects written directly through `registry()` are not counted.
+    pub fn object_count_by_type(&self, type_name: &str) -> usize {
+        self.store.count_by_type(type_name)
+    }
+
+    /// Get the number of registered objects of every type
+    pub fn object_counts_by_type(&self) -> HashMap<String, usize> {
+        self.store.counts_by_type()
+    }
+
+    /// Apply an action result's trait updates to a registered object
+    pub async fn apply_action_result(&self, id: &str, result: &ActionResult) -> Result<()> {
+        self.store.write().await.update(id, |object| result.apply_to(object))
+    }
+
+    /// Get an object by ID
+    ///
+    /// With a migration registry attached, outdated traits are migrated and written
+    /// back to the registry before the object is returned.
+    pub async fn get_object(&self, id: &str) -> Option<Object> {
+        let object = self.store.registry.read().await.get(id).cloned()?;
+        let Some(migrations) = &self.migrations else {
+            return Some(object);
+        };
+        if !object.traits.values().any(|t| migrations.needs_migration(t)) {
+            return Some(object);
+        }
+
+        let mut registry = self.store.registry.write().await;
+        let object = registry.get_mut(id)?;
+        for trait_obj in object.traits.values_mut() {
+            if let Err(e) = migrations.apply(trait_obj) {
+                tracing::warn!("Failed to migrate trait '{}' on object {}: {}", trait_obj.name, id, e);
+            }
+        }
+        object.updated_at = chrono::Utc::now();
+        Some(object.clone())
+    }
+
+    /// Serialize a registered object with the named serializer
+    pub async fn export_object(&self, id: &str, serializer_name: &str) -> Result<Vec<u8>> {
+        let object = self.get_object(id).await.ok_or_else(|| OatsError::object_not_found(id))?;
+        self.serializers.serialize(&object, serializer_name)
+    }
+
+    /// Get all objects that have not been soft-deleted
+    pub async fn get_all_objects(&self) -> Vec<Object> {
+        let registry = self.store.registry.read().await;
+        registry.values().filter(|obj| !obj.is_deleted()).cloned().collect()
+    }
+
+    /// Get all objects that have not been soft-deleted and convert to `T`
+    pub async fn get_all_as<T: for<'a> TryFrom<&'a Object>>(&self) -> TypedObjectCollection<T> {
+        TypedObjectCollection::from_objects(self.get_all_objects().await)
+    }
+
+    /// Get all objects, including soft-deleted ones
+    pub async fn get_all_objects_including_deleted(&self) -> Vec<Object> {
+        let registry = self.store.registry.read().await;
+        registry.values().cloned().collect()
+    }
+
+    /// Get all objects that have not been soft-deleted and were updated after `since`
+    pub async fn get_objects_updated_since(&self, since: chrono::DateTime<chrono::Utc>) -> Vec<Object> {
+        let registry = self.store.registry.read().await;
+        registry
+            .values()
+            .filter(|object| !object.is_deleted && object.updated_since(since))
+            .cloned()
+            .collect()
+    }
+
+    /// Get all objects that have not been soft-deleted and match a filter
+    pub async fn query_filter(&self, filter: &ObjectFilter) -> Vec<Object> {
+        let registry = self.store.registry.read().await;
+        registry
+            .values()
+            .filter(|obj| !obj.is_deleted() && filter.matches(obj))
+            .cloned()
+            .collect()
+    }
+
+    /// Compute type, trait and metadata statistics over all live objects in one pass
+    pub async fn object_stats(&self) -> ObjectStats {
+        let registry = self.store.registry.read().await;
+        ObjectStats::from_objects(registry.values().filter(|obj| !obj.is_deleted()))
+    }
+
+    /// Get object count
+    pub async fn object_count(&self) -> usize {
+        let registry = self.store.registry.read().await;
+        registry.len()
+    }
+
+    /// Clear all objects
+    pub async fn clear_objects(&self) {
+        self.drain_objects().await;
+    }
+
+    /// Remove and return every registered object, keyed by ID
+    pub async fn drain_objects(&self) -> HashMap<String, Object> {
+        self.store.write().await.replace_all(HashMap::new())
+    }
+
+    /// Replace every registered object with `objects`, keyed by ID
+    ///
+    /// Type counters and the spatial index are rebuilt; type and size limits are not checked.
+    pub async fn restore_objects(&self, objects: HashMap<String, Object>) {
+        self.store.write().await.replace_all(objects);
+    }
+
+    /// Run `f` against the manager, restoring every object if it returns an error
+    ///
+    /// `f` returns a boxed future, e.g. `|m| Box::pin(async move { ... })`, rather than a plain
+    /// `Result` because the registry methods it needs are async. Only registered objects are
+    /// rolled back; system state is not, and neither are objects if the transaction future is
+    /// cancelled or panics. Transactions cannot be nested.
+    pub async fn transaction<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: for<'a> FnOnce(&'a mut SystemManager) -> BoxFuture<'a, Result<T>>,
+    {
+        let Some(_depth) = TransactionDepthGuard::enter(&self.transaction_depth) else {
+            return Err(OatsError::invalid_state("Nested transactions are not supported"));
+        };
+        let snapshot = self.store.registry.read().await.clone();
+
+        let result = f(self).await;
+        if result.is_err() {
+            self.restore_objects(snapshot).await;
+        }
+        result
+    }
+
+    /// Reserve capacity for objects
+    pub async fn reserve_objects(&self, additional: usize) {
+        let mut registry = self.store.registry.write().await;
+        registry.reserve(additional);
+    }
+
+    /// Process all objects through all systems
+    pub async fn process_all(&mut self, priority: Priority) -> Result<Vec<ActionResult>> {
+        let started = chrono::Utc::now();
+        let results = self.process_systems(priority, RunOptions::default()).await;
+        self.last_process_time = Some(started);
+        results
+    }
+
+    /// Process all objects through all ready systems concurrently
+    ///
+    /// Results are ordered by system priority, highest first. Output pipes are not followed.
+    pub async fn process_all_parallel(&mut self, priority: Priority) -> Result<Vec<ActionResult>> {
+        let started = chrono::Utc::now();
+        let scope = self.run_scope();
+        let objects = self.store.prepare_run().await;
+
+        let filters = &self.system_filters;
+        let mut systems: Vec<_> = self.systems.iter_mut().filter(|(_, system)| system.is_ready()).collect();
+        systems.sort_by_key(|(_, system)| std::cmp::Reverse(system.priority()));
+
+        let runs = systems.into_iter().map(|(name, system)| {
+            let batch = objects_for_system(&objects, filters.get(name), system.as_ref());
+            async move { run_with_hooks(system.as_mut(), &batch, priority, None).await }
+        });
+
+        let mut all_results = Vec::new();
+        for outcome in scope.run(futures::future::join_all(runs)).await {
+            match outcome {
+                Ok(results) => all_results.extend(results),
+                Err(e) => all_results.push(ActionResult::failure(format!("System error: {}", e))),
+            }
+        }
+
+        self.last_process_time = Some(started);
+        Ok(all_results)
+    }
+
+    /// Process all objects through all systems, reporting progress after each system's batch
+    ///
+    /// The callback runs inline between systems, so it should return quickly.
+    pub async fn run_with_progress(
+        &mut self,
+        priority: Priority,
+        progress_cb: impl Fn(ProgressEvent) + Send + 'static,
+    ) -> Result<Vec<ActionResult>> {
+        let started = chrono::Utc::now();
+        let progress: ProgressCallback = std::sync::Mutex::new(Box::new(progress_cb));
+        let results = self
+            .process_systems(priority, RunOptions { progress: Some(&progress), ..RunOptions::default() })
+            .await;
+        self.last_process_time = Some(started);
+        results
+    }
+
+    /// Process only objects updated since the previous processing run over all objects
+    ///
+    /// The first call processes every object.
+    pub async fn process_since_last(&mut self, priority: Priority) -> Result<Vec<ActionResult>> {
+        let started = chrono::Utc::now();
+        let results = self.process_systems(priority, RunOptions { since: self.last_process_time, ..RunOptions::default() })
+            .await;
+        self.last_process_time = Some(started);
+        results
+    }
+
+    /// Get the time the last processing run over all objects, or a lane of them, started
+    pub fn last_process_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_process_time
+    }
+
+    /// Process all objects through all systems and apply the results to the registry
+    ///
+    /// Expired traits are purged first unless disabled with `with_auto_purge_expired`.
+    /// Successful results are applied to the object named by their `target_id`; results
+    /// without one are counted but not applied.
+    pub async fn process_and_apply(&mut self, priority: Priority) -> Result<ProcessingSummary> {
+        self.process_and_apply_results(priority).await.map(|(summary, _)| summary)
+    }
+
+    /// Predict what `process_and_apply` would change without changing any registered object
+    ///
+    /// Systems run against a shadow copy of the objects, which is then diffed against the
+    /// registry. Other tasks keep seeing the registry unchanged, and no object changes or
+    /// system events are published. System state such as stats is not rolled back, and
+    /// systems that write to a registry handle they hold, rather than through the manager,
+    /// still write to the real registry.
+    pub async fn dry_run_process_all(&mut self, priority: Priority) -> Result<DryRunReport> {
+        let shadow = self.store.shadow().await;
+        let guard = DryRunGuard::enter(self, shadow);
+        let outcome = guard.manager.process_and_apply_results(priority).await;
+        let predicted = guard.manager.store.registry.read().await.clone();
+        drop(guard);
+        let (_, results) = outcome?;
+
+        let original = self.store.registry.read().await;
+        let mut report = DryRunReport { results, ..DryRunReport::default() };
+        for (id, after) in &predicted {
+            let before = match original.get(id) {
+                Some(before) => before.clone(),
+                None => {
+                    report.added_objects.push(after.clone());
+                    Object::new(after.name.clone(), after.object_type.clone())
+                }
+            };
+            let diff = before.diff(after);
+            if !diff.is_empty() {
+                report.predicted_object_changes.insert(after.id, diff);
+            }
+        }
+        report.removed_objects = original
+            .iter()
+            .filter(|(id, _)| !predicted.contains_key(*id))
+            .map(|(_, object)| object.id)
+            .collect();
+        Ok(report)
+    }
+
+    /// Run `process_and_apply`, also returning the results it applied
+    async fn process_and_apply_results(&mut self, priority: Priority) -> Result<(ProcessingSummary, Vec<ActionResult>)> {
+        let expired_traits_purged = if self.auto_purge_expired {
+            self.purge_expired_traits().await
+        } else {
+            0
+        };
+        let mut results = self.process_all(priority).await?;
+        let mut summary = ProcessingSummary {
+            total_actions: results.len(),
+            expired_traits_purged,
+            ..ProcessingSummary::default()
+        };
+
+        for result in &mut results {
+            if result.is_failure() {
+                summary.total_errors += 1;
+                continue;
+            }
+            // Apply a deterministic last-write-wins state when a result updates a trait twice
+            result.deduplicate_trait_updates();
+            let Some(target_id) = result.target_id else {
+                continue;
+            };
+            match self.apply_action_result(&target_id.to_string(), result).await {
+                Ok(()) => summary.total_updates += result.trait_update_count(),
+                Err(e) => {
+                    tracing::warn!("Failed to apply result to object {}: {}", target_id, e);
+                    summary.total_errors += 1;
+                }
+            }
+        }
+
+        Ok((summary, results))
+    }
+
+    /// Remove expired traits from every registered object, returning how many were removed
+    pub async fn purge_expired_traits(&self) -> usize {
+        let mut registry = self.store.registry.write().await;
+        registry.values_mut().map(Object::purge_expired_traits).sum()
+    }
+
+    /// Process all objects through only the systems whose priority matches `priority`
+    pub async fn process_priority_lane(&mut self, priority: Priority) -> Result<Vec<ActionResult>> {
+        let started = chrono::Utc::now();
+        let results = self
+            .process_systems(priority, RunOptions { lane: Some(priority), ..RunOptions::default() })
+            .await;
+        self.last_process_time = Some(started);
+        results
+    }
+
+    /// Process all objects through all systems, starting every action context from `shared`
+    ///
+    /// Each context is a clone of `shared` with the processed object added as `"target"`,
+    /// so its parameters, metadata and other objects are visible to every action. A `shared`
+    /// context without services is given the manager's services.
+    pub async fn process_with_context(&mut self, priority: Priority, mut shared: ActionContext<'_>) -> Result<Vec<ActionResult>> {
+        if shared.services.is_empty() {
+            shared.services = self.services.clone();
+        }
+        let started = chrono::Utc::now();
+        let results = self
+            .process_systems(priority, RunOptions { shared: Some(&shared), ..RunOptions::default() })
+            .await;
+        self.last_process_time = Some(started);
+        results
+    }
+
+    /// Process objects through a specific system, starting every action context from `shared`
+    pub async fn process_with_context_system(
+        &mut self,
+        system_name: &str,
+        objects: Vec<Object>,
+        priority: Priority,
+        mut shared: ActionContext<'_>,
+    ) -> Result<Vec<ActionResult>> {
+        if shared.services.is_empty() {
+            shared.services = self.services.clone();
+        }
+        let system = self
+            .systems
+            .get_mut(system_name)
+            .ok_or_else(|| OatsError::system_error(format!("System '{}' not found", system_name)))?;
+
+        if !system.is_ready() {
+            return Err(OatsError::system_error("System is not ready"));
+        }
+
+        run_with_hooks(system.as_mut(), &objects, priority, Some(&shared)).await
+    }
+
+    /// Capture the services and journal for a processing run
+    fn run_scope(&self) -> RunScope {
+        RunScope {
+            services: self.services.clone(),
+            store: self.store.clone(),
+            task_id: tokio::task::try_id().map_or_else(|| uuid::Uuid::new_v4().to_string(), |id| id.to_string()),
+            limits: Arc::clone(&self.concurrency_limits),
+            #[cfg(feature = "snapshot")]
+            journal: self.journal_enabled.then(|| Arc::clone(&self.journal)),
+        }
+    }
+
+    /// Start or stop recording every action run through `Action::execute_scoped` while processing
+    ///
+    /// Entries recorded so far are kept when recording stops.
+    #[cfg(feature = "snapshot")]
+    pub fn journal_mode(&mut self, enabled: bool) {
+        self.journal_enabled = enabled;
+    }
+
+    /// Keep at most `capacity` journal entries, dropping the oldest first
+    ///
+    /// The default is `DEFAULT_JOURNAL_CAPACITY`; zero records nothing.
+    #[cfg(feature = "snapshot")]
+    pub fn with_journal_capacity(self, capacity: usize) -> Self {
+        self.journal.lock().unwrap_or_else(|e| e.into_inner()).set_capacity(capacity);
+        self
+    }
+
+    /// Get a copy of every recorded journal entry, oldest first
+    #[cfg(feature = "snapshot")]
+    pub fn export_journal(&self) -> Vec<crate::actions::JournalEntry> {
+        self.journal.lock().unwrap_or_else(|e| e.into_inner()).entries.iter().cloned().collect()
+    }
+
+    /// Discard every recorded journal entry
+    #[cfg(feature = "snapshot")]
+    pub fn clear_journal(&self) {
+        self.journal.lock().unwrap_or_else(|e| e.into_inner()).entries.clear();
+    }
+
+    /// Make `action` available to `replay` under its name
+    #[cfg(feature = "snapshot")]
+    pub fn register_replay_action(&mut self, action: Box<dyn crate::actions::Action>) {
+        self.replay_actions.insert(action.name().to_string(), action);
+    }
+
+    /// Re-execute journaled actions in order against a fresh set of objects
+    ///
+    /// Objects start from the first snapshot that contains them, and each result's trait
+    /// updates are applied via `ActionResult::apply_to_context` so later entries see earlier
+    /// changes. Registered objects are untouched. Every action must have been registered
+    /// with `register_replay_action`; validation and execution errors are returned as failed results.
+    #[cfg(feature = "snapshot")]
+    pub async fn replay(&self, journal: &[crate::actions::JournalEntry]) -> Result<Vec<ActionResult>> {
+        let mut objects: HashMap<ObjectId, Object> = HashMap::new();
+        let mut results = Vec::with_capacity(journal.len());
+
+        for entry in journal {
+            let action = self.replay_actions.get(&entry.action_name).ok_or_else(|| {
+                OatsError::invalid_state(format!("no replay action registered for '{}'", entry.action_name))
+            })?;
+            let mut context = entry.context.restore()?;
+            context.services = self.services.clone();
+            for object in context.objects.values_mut() {
+                match objects.get(&object.id) {
+                    Some(current) => *object = current.clone().into(),
+                    None => {
+                        objects.insert(object.id, (**object).clone());
+                    }
+                }
+            }
+
+            let run = async {
+                action.validate_context(&context).await?;
+                action.execute_scoped(context.clone()).await
+            };
+            let result = crate::actions::with_current_system_name(entry.system_name.clone(), run)
+                .await
+                .unwrap_or_else(|e| e.to_action_result());
+            result.apply_to_context(&mut context);
+            for object in context.objects.into_values() {
+                objects.insert(object.id, object.into_owned());
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// Run systems via `run_systems`, tagging contexts and results with the current task ID
+    ///
+    /// Outside a spawned tokio task, e.g. under `#[tokio::main]`, a fresh ID is generated
+    /// for the run instead. Contexts always receive the manager's services.
+    async fn process_systems(&mut self, priority: Priority, options: RunOptions<'_>) -> Result<Vec<ActionResult>> {
+        let scope = self.run_scope();
+        let task_id = scope.task_id.clone();
+        let mut results = scope.run(self.run_systems(priority, options)).await?;
+        RunScope::tag_results(&task_id, &mut results);
+        Ok(results)
+    }
+
+    /// Run ready systems highest priority first, as restricted and observed by `options`
+    async fn run_systems(&mut self, priority: Priority, options: RunOptions<'_>) -> Result<Vec<ActionResult>> {
+        let RunOptions { lane, since, shared, progress } = options;
+        let mut objects = self.store.prepare_run().await;
+        let mut all_results = Vec::new();
+
+        if let Some(since) = since {
+            objects.retain(|object| object.updated_since(since));
+        }
+
+        // Sort systems by priority (highest first)
+        let mut system_names: Vec<_> = self
+            .systems
+            .iter()
+            .filter(|(_, system)| lane.is_none_or(|lane| system.priority() == lane))
+            .map(|(name, _)| name.clone())
+            .collect();
+        system_names.sort_by(|a, b| {
+            let a_priority = self.systems.get(a).map(|s| s.priority()).unwrap_or(Priority::Normal);
+            let b_priority = self.systems.get(b).map(|s| s.priority()).unwrap_or(Priority::Normal);
+            b_priority.cmp(&a_priority)
+        });
+
+        // Pipe targets only run on the output of their source systems
+        let piped_targets: HashSet<String> = self
+            .pipes
+            .iter()
+            .filter(|pipe| system_names.contains(&pipe.from))
+            .map(|pipe| pipe.to.clone())
+            .collect();
+
+        system_names.retain(|name| !piped_targets.contains(name));
+
+        let total_objects = match progress {
+            Some(_) => system_names
+                .iter()
+                .filter_map(|name| self.systems.get(name).filter(|system| system.is_ready()).map(|system| (name, system)))
+                .map(|(name, system)| match system_filter(self.system_filters.get(name), system.as_ref()) {
+                    Some(filter) => objects.iter().filter(|o| filter.matches(o)).count(),
+                    None => objects.len(),
+                })
+                .sum(),
+            None => 0,
+        };
+        let mut objects_processed = 0;
+
+        for system_name in system_names {
+            if let Some(system) = self.systems.get_mut(&system_name) {
+                if system.is_ready() {
+                    let system_objects =
+                        objects_for_system(&objects, self.system_filters.get(&system_name), system.as_ref());
+                    let batch_size = system_objects.len();
+                    let outcome = run_with_hooks(system.as_mut(), &system_objects, priority, shared).await;
+                    #[cfg(feature = "reactive")]
+                    self.publish_events(&system_name, batch_size, &outcome);
+                    if let Some(progress) = progress {
+                        objects_processed += batch_size;
+                        let callback = progress.lock().unwrap_or_else(|e| e.into_inner());
+                        callback(ProgressEvent::new(system_name.clone(), objects_processed, total_objects));
+                    }
+                    match outcome {
+                        Ok(results) => {
+                            let piped = self.run_pipes(&system_name, &results, priority, shared).await;
+                            all_results.extend(results);
+                            all_results.extend(piped);
+                        }
+                        Err(e) => {
+                            let error_result = ActionResult::failure(format!("System error: {}", e));
+                            all_results.push(error_result);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(all_results)
+    }
+
+    /// Subscribe to events published while systems run
+    ///
+    /// Only events sent after subscribing are observed; a slow subscriber skips events once it
+    /// falls more than 1024 behind.
+    #[cfg(feature = "reactive")]
+    pub fn events(&self) -> crate::observable::Observable<crate::observable::SystemEvent> {
+        crate::observable::Observable::from_broadcast(self.events.subscribe())
+    }
+
+    /// Publish one event per result and a completion or failure event for the system
+    #[cfg(feature = "reactive")]
+    fn publish_events(&self, system_name: &str, objects_processed: usize, outcome: &Result<Vec<ActionResult>>) {
+        use crate::observable::SystemEvent;
+        if self.events.receiver_count() == 0 {
+            return;
+        }
+        match outcome {
+            Ok(results) => {
+                for result in results {
+                    let _ = self.events.send(SystemEvent::ActionCompleted {
+                        system: system_name.to_string(),
+                        result: result.clone(),
+                    });
+                }
+                let _ = self.events.send(SystemEvent::SystemCompleted {
+                    system: system_name.to_string(),
+                    objects_processed,
+                });
+            }
+            Err(e) => {
+                let _ = self.events.send(SystemEvent::SystemFailed {
+                    system: system_name.to_string(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Apply a system's results and run the systems it pipes into, following chained pipes
+    ///
+    /// Each system runs at most once per call, so cyclic pipes terminate.
+    async fn run_pipes(
+        &mut self,
+        from: &str,
+        results: &[ActionResult],
+        priority: Priority,
+        shared: Option<&ActionContext<'_>>,
+    ) -> Vec<ActionResult> {
+        let mut piped_results = Vec::new();
+        if !self.pipes.iter().any(|pipe| pipe.from == from) {
+            return piped_results;
+        }
+
+        let mut visited = HashSet::from([from.to_string()]);
+        let mut queue = vec![(from.to_string(), self.apply_piped_results(results).await)];
+        while let Some((source, updated)) = queue.pop() {
+            let pipes: Vec<OutputPipe> = self
+                .pipes
+                .iter()
+                .filter(|pipe| pipe.from == source && !visited.contains(&pipe.to))
+                .cloned()
+                .collect();
+
+            for pipe in pipes {
+                visited.insert(pipe.to.clone());
+                let Some(system) = self.systems.get_mut(&pipe.to) else { continue };
+                if !system.is_ready() {
+                    continue;
+                }
+
+                let mut results = Vec::new();
+                for object in objects_for_system(&updated, self.system_filters.get(&pipe.to), system.as_ref()).iter() {
+                    let mut context = shared.cloned().unwrap_or_default();
+                    context.add_object(pipe.object_name.clone(), object);
+                    match run_with_hooks(system.as_mut(), std::slice::from_ref(object), priority, Some(&context)).await {
+                        Ok(object_results) => results.extend(object_results),
+                        Err(e) => results.push(ActionResult::failure(format!("System error: {}", e))),
+                    }
+                }
+
+                if self.pipes.iter().any(|next| next.from == pipe.to) {
+                    queue.push((pipe.to.clone(), self.apply_piped_results(&results).await));
+                }
+                piped_results.extend(results);
+            }
+        }
+        piped_results
+    }
+
+    /// Apply successful targeted results to the registry, returning the updated objects
+    async fn apply_piped_results(&self, results: &[ActionResult]) -> Vec<Object> {
+        let mut updated_ids: Vec<String> = Vec::new();
+        for result in results {
+            let Some(target_id) = result.target_id.map(|id| id.to_string()) else { continue };
+            if result.is_failure() {
+                continue;
+            }
+            if let Err(e) = self.apply_action_result(&target_id, result).await {
+                tracing::warn!("Failed to apply piped result to object {}: {}", target_id, e);
+                continue;
+            }
+            if !updated_ids.contains(&target_id) {
+                updated_ids.push(target_id);
+            }
+        }
+
+        let registry = self.store.registry.read().await;
+        updated_ids.iter().filter_map(|id| registry.get(id).cloned()).collect()
+    }
+
+    /// Process objects through a specific system
+    pub async fn process_with_system(
+        &mut self,
+        system_name: &str,
+        objects: Vec<Object>,
+        priority: Priority,
+    ) -> Result<Vec<ActionResult>> {
+        let system = self
+            .systems
+            .get_mut(system_name)
+            .ok_or_else(|| OatsError::system_error(format!("System '{}' not found", system_name)))?;
+
+        if !system.is_ready() {
+            return Err(OatsError::system_error("System is not ready"));
+        }
+
+        run_with_hooks(system.as_mut(), &objects, priority, None).await
+    }
+
+    /// Warm up every system in sequence, recording the time each took in its stats
+    ///
+    /// Call before the first `process_all` so cold-start costs are not paid mid-run.
+    pub async fn warm_up_all(&mut self, sample_objects: &[Object]) -> Result<()> {
+        for (name, system) in &mut self.systems {
+            let start_time = std::time::Instant::now();
+            if let Err(e) = system.warm_up(sample_objects).await {
+                return Err(OatsError::system_error(format!(
+                    "Failed to warm up system '{}': {}",
+                    name, e
+                )));
+            }
+            self.warm_up_durations
+                .insert(name.clone(), start_time.elapsed().as_millis() as u64);
+        }
+        Ok(())
+    }
+
+    /// Initialize all systems
+    pub async fn initialize_all(&mut self) -> Result<()> {
+        for (name, system) in &mut self.systems {
+            if let Err(e) = system.initialize().await {
+                return Err(OatsError::system_error(format!(
+                    "Failed to initialize system '{}': {}",
+                    name, e
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reset the accumulated state of all systems
+    pub async fn drain_all(&mut self) -> Result<()> {
+        for (name, system) in &mut self.systems {
+            if let Err(e) = system.drain().await {
+                return Err(OatsError::system_error(format!(
+                    "Failed to drain system '{}': {}",
+                    name, e
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Shutdown all systems
+    pub async fn shutdown_all(&mut self) -> Result<()> {
+        for (name, system) in &mut self.systems {
+            if let Err(e) = system.shutdown().await {
+                return Err(OatsError::system_error(format!(
+                    "Failed to shutdown system '{}': {}",
+                    name, e
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get statistics for all systems
+    pub fn get_all_stats(&self) -> HashMap<String, SystemStats> {
+        self.systems
+            .iter()
+            .map(|(name, system)| {
+                let mut stats = system.get_stats();
+                if let Some(&duration_ms) = self.warm_up_durations.get(name) {
+                    stats.warm_up_completed = true;
+                    stats.warm_up_duration_ms = duration_ms;
+                }
+                (name.clone(), stats)
+            })
+            .collect()
+    }
+
+    /// Format the stats of every system as one OpenMetrics page, sorted by system name
+    pub fn openmetrics_page(&self) -> String {
+        let mut names: Vec<&String> = self.systems.keys().collect();
+        names.sort();
+        let stats: Vec<(&str, SystemStats)> =
+            names.into_iter().map(|name| (name.as_str(), self.systems[name].get_stats())).collect();
+        render_openmetrics(&stats, chrono::Utc::now().timestamp())
+    }
+
+    /// Format the stats of every system as InfluxDB line protocol, one line per system
+    #[cfg(feature = "influx_export")]
+    pub fn all_stats_to_influx(&self) -> String {
+        let timestamp = chrono::Utc::now();
+        let mut names: Vec<&String> = self.systems.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| self.systems[name].get_stats().to_influx_line_protocol(name, Some(timestamp)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Combine a system's assigned filter with its own
+fn system_filter(assigned: Option<&ObjectFilter>, system: &dyn System) -> Option<ObjectFilter> {
+    match (assigned.cloned(), system.object_filter()) {
+        (Some(assigned), Some(own)) => Some(assigned & own),
+        (assigned, own) => assigned.or(own),
+    }
+}
+
+/// Select the objects a system processes, combining its assigned filter with its own
+///
+/// Without a filter the objects are borrowed as-is rather than cloned.
+fn objects_for_system<'a>(
+    objects: &'a [Object],
+    assigned: Option<&ObjectFilter>,
+    system: &dyn System,
+) -> Cow<'a, [Object]> {
+    match system_filter(assigned, system) {
+        Some(filter) => Cow::Owned(objects.iter().filter(|o| filter.matches(o)).cloned().collect()),
+        None => Cow::Borrowed(objects),
+    }
+}
+
+/// Run a system's `process` wrapped in its `before_process` and `after_process` hooks
+pub(crate) async fn run_with_hooks(
+    system: &mut dyn System,
+    objects: &[Object],
+    priority: Priority,
+    shared: Option<&ActionContext<'_>>,
+) -> Result<Vec<ActionResult>> {
+    let name = system.name().to_string();
+    crate::actions::with_current_system_name(name, async move {
+        system.before_process(objects, priority).await?;
+        let results = match shared {
+            Some(shared) => system.process_with_context(objects, priority, shared).await?,
+            None => system.process(objects, priority).await?,
+        };
+        if let Err(e) = system.after_process(&results, priority).await {
+            tracing::warn!("after_process failed for system '{}': {}", system.name(), e);
+        }
+        Ok(results)
+    })
+    .await
+}
+
+/// Adapter that lets a typed system stored behind `Arc<Mutex<T>>` run as a `dyn System`
+struct TypedSystemAdapter<T: System> {
+    name: String,
+    description: String,
+    priority: Priority,
+    inner: Arc<Mutex<T>>,
+}
+
+#[async_trait]
+impl<T: System> System for TypedSystemAdapter<T> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        self.inner.lock().await.initialize().await
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        self.inner.lock().await.shutdown().await
+    }
+
+    async fn drain(&mut self) -> Result<()> {
+        self.inner.lock().await.drain().await
+    }
+
+    async fn warm_up(&mut self, sample_objects: &[Object]) -> Result<()> {
+        self.inner.lock().await.warm_up(sample_objects).await
+    }
+
+    async fn process(&mut self, objects: &[Object], priority: Priority) -> Result<Vec<ActionResult>> {
+        run_with_hooks(&mut *self.inner.lock().await, objects, priority, None).await
+    }
+
+    async fn process_with_context(
+        &mut self,
+        objects: &[Object],
+        priority: Priority,
+        shared: &ActionContext<'_>,
+    ) -> Result<Vec<ActionResult>> {
+        run_with_hooks(&mut *self.inner.lock().await, objects, priority, Some(shared)).await
+    }
+
+    fn object_filter(&self) -> Option<ObjectFilter> {
+        self.inner.try_lock().ok().and_then(|system| system.object_filter())
+    }
+
+    fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    fn is_ready(&self) -> bool {
+        // A held lock means a caller is using the system; process will wait for it
+        self.inner.try_lock().map(|system| system.is_ready()).unwrap_or(true)
+    }
+
+    fn get_stats(&self) -> SystemStats {
+        self.inner.try_lock().map(|system| system.get_stats()).unwrap_or_default()
+    }
+}
+
+impl Default for SystemManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A system manager that spreads per-object work of thread-safe systems across a rayon pool
+///
+/// Each `(system, object)` pair is a work item calling `System::process_object`, so one
+/// slow system no longer holds up the others and objects of one system run in parallel.
+/// Systems whose `is_thread_safe` returns false run afterwards on the sequential path,
+/// highest priority first. Work items see the manager's services, object store and task ID.
+#[cfg(feature = "work_stealing")]
+pub struct WorkStealingSystemManager {
+    manager: SystemManager,
+    pool: Arc<rayon::ThreadPool>,
+}
+
+#[cfg(feature = "work_stealing")]
+impl WorkStealingSystemManager {
+    /// Wrap a manager with a pool of `num_threads` threads, or one per core if zero
+    pub fn new(manager: SystemManager, num_threads: usize) -> Result<Self> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("oats-work-stealing-{}", i))
+            .build()
+            .map_err(|e| OatsError::system_error(format!("Failed to build work-stealing pool: {}", e)))?;
+        Ok(Self { manager, pool: Arc::new(pool) })
+    }
+
+    /// Get the wrapped manager
+    pub fn manager(&self) -> &SystemManager {
+        &self.manager
+    }
+
+    /// Get the wrapped manager (mutable)
+    pub fn manager_mut(&mut self) -> &mut SystemManager {
+        &mut self.manager
+    }
+
+    /// Unwrap the manager, dropping the pool
+    pub fn into_inner(self) -> SystemManager {
+        self.manager
+    }
+
+    /// Get the number of threads in the pool
+    pub fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Process all objects through all ready systems
+    ///
+    /// Thread-safe systems run first on the pool, one `process_object` call per object;
+    /// their `before_process` and `after_process` hooks see the whole batch.
+    pub async fn process_all(&mut self, priority: Priority) -> Result<Vec<ActionResult>> {
+        let scope = self.manager.run_scope();
+        let task_id = scope.task_id.clone();
+        let mut results = scope.clone().run(self.run_systems(priority, scope)).await?;
+        RunScope::tag_results(&task_id, &mut results);
+        Ok(results)
+    }
+
+    /// Run the pool and then the sequential systems inside `scope`
+    async fn run_systems(&mut self, priority: Priority, scope: RunScope) -> Result<Vec<ActionResult>> {
+        let started = chrono::Utc::now();
+        let manager = &mut self.manager;
+        let objects = manager.store.prepare_run().await;
+
+        let mut names: Vec<String> = manager
+            .systems
+            .iter()
+            .filter(|(_, system)| system.is_ready())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort_by_key(|name| std::cmp::Reverse(manager.systems[name].priority()));
+        let (parallel, sequential): (Vec<String>, Vec<String>) =
+            names.into_iter().partition(|name| manager.systems[name].is_thread_safe());
+
+        let objects: Arc<[Object]> = objects.into();
+        let mut all_results = Vec::new();
+        let mut taken = Vec::with_capacity(parallel.len());
+        let mut items = Vec::new();
+        for name in parallel {
+            let Some(mut system) = manager.systems.remove(&name) else { continue };
+            let batch = objects_for_system(&objects, manager.system_filters.get(&name), system.as_ref());
+            let before = crate::actions::with_current_system_name(name.clone(), system.before_process(&batch, priority));
+            if let Err(e) = before.await {
+                all_results.push(ActionResult::failure(format!("System error: {}", e)));
+                manager.systems.insert(name, system);
+                continue;
+            }
+            let filter = system_filter(manager.system_filters.get(&name), system.as_ref());
+            let system_index = taken.len();
+            items.extend(
+                (0..objects.len())
+                    .filter(|&i| filter.as_ref().is_none_or(|filter| filter.matches(&objects[i])))
+                    .map(|object_index| (system_index, object_index)),
+            );
+            taken.push((name, system));
+        }
+
+        let pool_objects = Arc::clone(&objects);
+        let (systems, system_results) = run_on_pool(&self.pool, scope, taken, pool_objects, items, priority).await?;
+        let manager = &mut self.manager;
+        for ((name, mut system), results) in systems.into_iter().zip(system_results) {
+            let after = crate::actions::with_current_system_name(name.clone(), system.after_process(&results, priority));
+            if let Err(e) = after.await {
+                tracing::warn!("after_process failed for system '{}': {}", name, e);
+            }
+            all_results.extend(results);
+            manager.systems.insert(name, system);
+        }
+
+        for name in sequential {
+            let Some(system) = manager.systems.get_mut(&name) else { continue };
+            let batch = objects_for_system(&objects, manager.system_filters.get(&name), system.as_ref());
+            match run_with_hooks(system.as_mut(), &batch, priority, None).await {
+                Ok(results) => all_results.extend(results),
+                Err(e) => all_results.push(ActionResult::failure(format!("System error: {}", e))),
+            }
+        }
+
+        manager.last_process_time = Some(started);
+        Ok(all_results)
+    }
+}
+
+/// Processed systems handed back from the pool, with their results per system
+#[cfg(feature = "work_stealing")]
+type PoolOutput = (Vec<(String, Box<dyn System>)>, Vec<Vec<ActionResult>>);
+
+/// Run every `(system, object)` work item on `pool`, returning the systems and their results
+///
+/// Items are taken in small chunks, entering the run scope once per chunk. The pool threads
+/// never block on the async runtime; the caller awaits a channel instead.
+#[cfg(feature = "work_stealing")]
+async fn run_on_pool(
+    pool: &rayon::ThreadPool,
+    scope: RunScope,
+    systems: Vec<(String, Box<dyn System>)>,
+    objects: Arc<[Object]>,
+    items: Vec<(usize, usize)>,
+    priority: Priority,
+) -> Result<PoolOutput> {
+    use rayon::prelude::*;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    pool.spawn(move || {
+        let outputs: Vec<Vec<(usize, Result<Vec<ActionResult>>)>> = items
+            .par_chunks(POOL_CHUNK_SIZE)
+            .map(|chunk| {
+                scope.run_sync(|| {
+                    chunk
+                        .chunk_by(|a, b| a.0 == b.0)
+                        .flat_map(|run| {
+                            let (name, system) = &systems[run[0].0];
+                            crate::actions::with_current_system_name_sync(name.clone(), || {
+                                run.iter()
+                                    .map(|&(system_index, object_index)| {
+                                        (system_index, system.process_object(&objects[object_index], priority))
+                                    })
+                                    .collect::<Vec<_>>()
+                            })
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+
+        let mut system_results = vec![Vec::new(); systems.len()];
+        for (system_index, output) in outputs.into_iter().flatten() {
+            match output {
+                Ok(results) => system_results[system_index].extend(results),
+                Err(e) => system_results[system_index].push(ActionResult::failure(format!("System error: {}", e))),
+            }
+        }
+        let _ = tx.send((systems, system_results));
+    });
+
+    rx.await
+        .map_err(|_| OatsError::system_error("Work-stealing pool stopped before returning its systems"))
+}
+
+/// Work items a pool thread takes at a time
+#[cfg(feature = "work_stealing")]
+const POOL_CHUNK_SIZE: usize = 32;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::Action;
+
+    #[test]
+    fn test_priority_ordering() {
+        assert!(Priority::Critical > Priority::High);
+        assert!(Priority::High > Priority::Normal);
+        assert!(Priority::Normal > Priority::Low);
+    }
+
+    #[test]
+    fn test_priority_serde_names_and_levels() {
+        assert_eq!(serde_json::to_string(&Priority::High).unwrap(), "\"High\"");
+        for priority in [Priority::Low, Priority::Normal, Priority::High, Priority::Critical] {
+            let json = serde_json::to_value(priority).unwrap();
+            assert_eq!(serde_json::from_value::<Priority>(json).unwrap(), priority);
+        }
+        assert_eq!(serde_json::from_str::<Priority>("4").unwrap(), Priority::Critical);
+        assert_eq!(serde_json::from_str::<Priority>("\"low\"").unwrap(), Priority::Low);
+        let error = |json: &str| serde_json::from_str::<Priority>(json).unwrap_err().to_string();
+        assert!(error("300").starts_with("priority level 300 is outside 0..=255"));
+        assert!(error("-1").starts_with("priority level -1 is outside 0..=255"));
+        assert!(error("\"urgent\"").starts_with("unknown priority 'urgent'"));
+        assert!(error("true").contains("a priority level from 0 to 255 or a priority name"));
+
+        assert_eq!("critical".parse::<Priority>(), Ok(Priority::Critical));
+        assert_eq!("2".parse::<Priority>(), Ok(Priority::Normal));
+        assert_eq!("urgent".parse::<Priority>(), Err("unknown priority 'urgent'".to_string()));
+    }
+
+    #[test]
+    fn test_priority_custom_round_trips_as_numeric_string() {
+        let custom = Priority::Custom(192);
+        assert_eq!(serde_json::to_string(&custom).unwrap(), "\"192\"");
+        assert_eq!(serde_json::from_str::<Priority>("\"192\"").unwrap(), custom);
+        assert_eq!(serde_json::from_str::<Priority>("192").unwrap(), custom);
+        assert_eq!(serde_json::from_str::<Priority>("9").unwrap(), Priority::Custom(9));
+        assert_eq!(custom.to_string(), "192");
+        assert_eq!(Priority::from(3), Priority::High);
+
+        assert!(Priority::Custom(192) > Priority::Critical);
+        assert!(Priority::Custom(0) < Priority::Low);
+        assert!(Priority::Custom(4) > Priority::Critical);
     }
 
     #[test]
@@ -316,4 +2518,1635 @@ mod tests {
         assert_eq!(stats.actions_executed, 0);
         assert_eq!(stats.errors, 0);
     }
+
+    #[cfg(feature = "influx_export")]
+    #[test]
+    fn test_influx_line_protocol() {
+        use chrono::TimeZone;
+
+        let mut stats = SystemStats {
+            objects_processed: 42,
+            actions_executed: 42,
+            avg_processing_time_ms: 1.2,
+            ..SystemStats::default()
+        };
+        let timestamp = chrono::Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        assert_eq!(
+            stats.to_influx_line_protocol("order processing", Some(timestamp)),
+            "oats_system,system=order\\ processing objects_processed=42i,actions_executed=42i,errors=0i,avg_latency_ms=1.2 1700000000000000000"
+        );
+
+        stats.last_processed = Some(timestamp);
+        let line = stats.to_influx_line_protocol("orders", None);
+        assert!(line.contains(",last_processed=1700000000000000000i "));
+    }
+
+    #[test]
+    fn test_openmetrics() {
+        let stats = SystemStats {
+            objects_processed: 42,
+            avg_processing_time_ms: 1.5,
+            ..SystemStats::default()
+        };
+        let other = SystemStats { errors: 3, ..SystemStats::default() };
+        let page = render_openmetrics(&[("orders", stats.clone()), ("say \"hi\"", other)], 1_700_000_000);
+        let lines: Vec<&str> = page.lines().collect();
+        assert_eq!(lines.len(), 5 * 3 + 1);
+        assert_eq!(lines[0], "# TYPE oats_objects_processed counter");
+        assert_eq!(lines[1], "oats_objects_processed_total{system=\"orders\"} 42 1700000000");
+        assert_eq!(lines[8], "oats_errors_total{system=\"say \\\"hi\\\"\"} 3 1700000000");
+        assert_eq!(lines[10], "oats_avg_processing_time_ms{system=\"orders\"} 1.5 1700000000");
+        assert_eq!(lines[15], "# EOF");
+
+        assert!(stats.to_openmetrics("orders").starts_with("# TYPE oats_objects_processed counter\noats_objects_processed_total{system=\"orders\"} 42 "));
+    }
+
+    #[test]
+    fn test_system_stats_derived_metrics() {
+        let mut stats = SystemStats::default();
+        assert_eq!(stats.success_rate(), 100.0);
+        assert_eq!(stats.actions_per_object(), 0.0);
+        assert_eq!(stats.efficiency_score(), 0.0);
+
+        stats.objects_processed = 10;
+        stats.actions_executed = 20;
+        stats.errors = 3;
+        assert_eq!(stats.success_rate(), 85.0);
+        assert_eq!(stats.actions_per_object(), 2.0);
+        assert_eq!(stats.efficiency_score(), 2.0 * (1.0 - 10.0 / 100.0));
+    }
+
+    struct DoubleHealthAction;
+
+    #[async_trait]
+    impl StreamingAction for DoubleHealthAction {
+        fn name(&self) -> &str {
+            "double_health"
+        }
+
+        fn description(&self) -> &str {
+            "Doubles health twice, one step at a time"
+        }
+
+        async fn stream_execute(&self, context: ActionContext<'_>) -> Result<crate::actions::ActionStream> {
+            let health = context
+                .get_object("target")
+                .and_then(|obj| obj.get_trait_data("health"))
+                .and_then(|data| data.as_number())
+                .unwrap_or(0.0);
+            let steps = [health * 2.0, health * 4.0].into_iter().map(|value| {
+                let mut result = ActionResult::success();
+                result.add_trait_update(crate::Trait::new("health", crate::TraitData::Number(value)));
+                Ok(result)
+            });
+            Ok(Box::pin(futures::stream::iter(steps)))
+        }
+
+        async fn validate_context(&self, context: &ActionContext<'_>) -> Result<()> {
+            let target = context.get_object("target").ok_or_else(|| OatsError::object_not_found("target"))?;
+            target.validate_required_traits(&["health"])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_system_applies_results() {
+        let mut manager = SystemManager::new();
+        let mut object = Object::new("hero", "player");
+        object.add_trait(crate::Trait::new("health", crate::TraitData::Number(10.0)));
+        let id = object.id.to_string();
+        manager.register_object(object).await.unwrap();
+
+        let mut system = StreamingSystem::new("streaming", "Streaming test system", manager.registry());
+        system.add_action(Box::new(DoubleHealthAction));
+        manager.add_system(Box::new(system));
+        let mut changes = manager.subscribe_to_object_type("player");
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results.len(), 2);
+        for _ in 0..2 {
+            assert!(matches!(changes.try_recv(), Ok(ObjectChange::Updated(_))));
+        }
+
+        let health = manager.get_object(&id).await.unwrap().get_trait_data("health").and_then(|d| d.as_number());
+        assert_eq!(health, Some(40.0));
+        assert_eq!(manager.get_all_stats()["streaming"].actions_executed, 2);
+
+        manager.drain_all().await.unwrap();
+        assert_eq!(manager.get_all_stats()["streaming"].actions_executed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_system_validates_before_streaming() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("ghost", "player")).await.unwrap();
+
+        let mut system = StreamingSystem::new("streaming", "Streaming test system", manager.registry());
+        system.add_action(Box::new(DoubleHealthAction));
+        manager.add_system(Box::new(system));
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_failure());
+        assert!(results[0].messages[0].contains("failed validation"));
+        let stats = &manager.get_all_stats()["streaming"];
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.actions_executed, 0);
+    }
+
+    struct EchoContextAction;
+
+    #[async_trait]
+    impl StreamingAction for EchoContextAction {
+        fn name(&self) -> &str {
+            "echo_context"
+        }
+
+        fn description(&self) -> &str {
+            "Reports the shared context seen for each target"
+        }
+
+        async fn stream_execute(&self, context: ActionContext<'_>) -> Result<crate::actions::ActionStream> {
+            let mut result = ActionResult::success();
+            result.add_data("target", serde_json::json!(context.get_object("target").map(|o| o.name())));
+            result.add_data("world", serde_json::json!(context.get_object("world").map(|o| o.name())));
+            result.add_data("tick", context.get_parameter("tick").cloned().unwrap_or_default());
+            result.add_data("request", serde_json::json!(context.get_metadata("request_id")));
+            Ok(Box::pin(futures::stream::iter([Ok(result)])))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_with_shared_context() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("hero", "player")).await.unwrap();
+        let mut system = StreamingSystem::new("echo", "Echoes shared context", manager.registry());
+        system.add_action(Box::new(EchoContextAction));
+        manager.add_system(Box::new(system));
+
+        let mut shared = ActionContext::new();
+        shared.add_object("world", Object::new("overworld", "map"));
+        shared.add_parameter("tick", serde_json::json!(7));
+        shared.add_metadata("request_id", "req-1");
+
+        let results = manager.process_with_context(Priority::Normal, shared.clone()).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].data["target"], serde_json::json!("hero"));
+        assert_eq!(results[0].data["world"], serde_json::json!("overworld"));
+        assert_eq!(results[0].data["tick"], serde_json::json!(7));
+        assert_eq!(results[0].data["request"], serde_json::json!("req-1"));
+
+        let objects = vec![Object::new("villain", "enemy")];
+        let results = manager
+            .process_with_context_system("echo", objects, Priority::Normal, shared)
+            .await
+            .unwrap();
+        assert_eq!(results[0].data["target"], serde_json::json!("villain"));
+        assert_eq!(results[0].data["tick"], serde_json::json!(7));
+    }
+
+    #[cfg(feature = "work_stealing")]
+    struct BatchSizeSystem {
+        name: &'static str,
+        thread_safe: bool,
+        calls: AtomicUsize,
+    }
+
+    #[cfg(feature = "work_stealing")]
+    impl BatchSizeSystem {
+        fn new(name: &'static str, thread_safe: bool) -> Self {
+            Self { name, thread_safe, calls: AtomicUsize::new(0) }
+        }
+
+        fn report(&self, batch_size: usize) -> ActionResult {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            let context = ActionContext::new();
+            let mut result = ActionResult::success();
+            result.add_data("system", serde_json::json!(self.name));
+            result.add_data("batch_size", serde_json::json!(batch_size));
+            result.add_data("current_system", serde_json::json!(crate::actions::current_system_name()));
+            result.add_data("has_store", serde_json::json!(ObjectStore::current().is_some()));
+            result.add_data("has_service", serde_json::json!(context.services.get::<u32>().is_some()));
+            result.add_data("context_task_id", serde_json::json!(context.task_id));
+            result
+        }
+    }
+
+    #[cfg(feature = "work_stealing")]
+    #[async_trait]
+    impl System for BatchSizeSystem {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Reports the size of each batch it processes"
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            Ok(vec![self.report(objects.len())])
+        }
+
+        fn is_thread_safe(&self) -> bool {
+            self.thread_safe
+        }
+
+        fn process_object(&self, _object: &Object, _priority: Priority) -> Result<Vec<ActionResult>> {
+            Ok(vec![self.report(1)])
+        }
+
+        fn get_stats(&self) -> SystemStats {
+            SystemStats { objects_processed: self.calls.load(Ordering::Relaxed) as u64, ..SystemStats::default() }
+        }
+    }
+
+    #[cfg(feature = "work_stealing")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_work_stealing_manager() {
+        let mut manager = SystemManager::new().with_services(ServiceLocator::new().with_service(7u32));
+        for i in 0..8 {
+            manager.register_object(Object::new(format!("item_{}", i), "product")).await.unwrap();
+        }
+        manager.add_system(Box::new(BatchSizeSystem::new("pricing", true)));
+        manager.add_system(Box::new(BatchSizeSystem::new("audit", false)));
+
+        let mut work_stealing = WorkStealingSystemManager::new(manager, 4).unwrap();
+        assert_eq!(work_stealing.num_threads(), 4);
+        let results = work_stealing.process_all(Priority::Normal).await.unwrap();
+
+        let batch_sizes = |system: &str| -> Vec<u64> {
+            results
+                .iter()
+                .filter(|r| r.data["system"] == serde_json::json!(system))
+                .map(|r| r.data["batch_size"].as_u64().unwrap())
+                .collect()
+        };
+        assert_eq!(batch_sizes("pricing"), vec![1; 8]);
+        assert_eq!(batch_sizes("audit"), vec![8]);
+        for result in &results {
+            assert_eq!(result.data["current_system"], result.data["system"]);
+            assert_eq!(result.data["has_store"], serde_json::json!(true));
+            assert_eq!(result.data["has_service"], serde_json::json!(true));
+            assert_eq!(result.data["context_task_id"], result.data["task_id"]);
+        }
+        assert_eq!(results[0].data["task_id"], results[8].data["task_id"]);
+
+        let stats = work_stealing.into_inner().get_all_stats();
+        assert_eq!(stats["pricing"].objects_processed, 8);
+        assert_eq!(stats["audit"].objects_processed, 1);
+    }
+
+    #[derive(Default)]
+    struct RateCacheSystem {
+        rates: Option<HashMap<String, f64>>,
+    }
+
+    #[async_trait]
+    impl System for RateCacheSystem {
+        fn name(&self) -> &str {
+            "rate_cache"
+        }
+
+        fn description(&self) -> &str {
+            "Prices products from cached exchange rates"
+        }
+
+        async fn warm_up(&mut self, sample_objects: &[Object]) -> Result<()> {
+            let rates = sample_objects.iter().map(|o| (o.object_type().to_string(), 1.1)).collect();
+            self.rates = Some(rates);
+            Ok(())
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let rates = self.rates.as_ref().ok_or_else(|| OatsError::invalid_state("Rates not loaded"))?;
+            Ok(objects
+                .iter()
+                .filter(|o| rates.contains_key(o.object_type()))
+                .map(|_| ActionResult::success())
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_all() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("laptop", "product")).await.unwrap();
+        manager.add_system(Box::new(RateCacheSystem::default()));
+        assert!(!manager.get_all_stats()["rate_cache"].warm_up_completed);
+
+        manager.warm_up_all(&[Object::new("sample", "product")]).await.unwrap();
+        assert!(manager.get_all_stats()["rate_cache"].warm_up_completed);
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_success());
+
+        manager.add_system(Box::new(RateCacheSystem::default()));
+        assert!(!manager.get_all_stats()["rate_cache"].warm_up_completed);
+    }
+
+    #[tokio::test]
+    async fn test_query_filter() {
+        let manager = SystemManager::new();
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(100.0)));
+        manager.register_object(hero).await.unwrap();
+        manager.register_object(Object::new("rock", "scenery")).await.unwrap();
+
+        let found = manager.query_filter(&ObjectFilter::has_trait("health")).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name(), "hero");
+    }
+
+    #[tokio::test]
+    async fn test_register_and_remove_objects_bulk() {
+        let manager = SystemManager::new();
+        let goblins: Vec<Object> = (0..3).map(|i| Object::new(format!("goblin_{}", i), "enemy")).collect();
+        let ids: Vec<ObjectId> = goblins.iter().map(|o| o.id()).collect();
+        manager.register_objects_bulk(goblins).await.unwrap();
+        assert_eq!(manager.object_count().await, 3);
+        assert_eq!(manager.object_count_by_type("enemy"), 3);
+
+        let removed = manager.remove_objects_bulk(&[ids[0], uuid::Uuid::new_v4(), ids[2]]).await;
+        assert_eq!(removed.iter().map(Option::is_some).collect::<Vec<_>>(), vec![true, false, true]);
+        assert_eq!(manager.object_count_by_type("enemy"), 1);
+
+        let mut strict = SystemManager::new().with_strict_type_checking(true);
+        strict.register_object_type("enemy");
+        let mixed = vec![Object::new("orc", "enemy"), Object::new("rock", "scenery")];
+        assert!(strict.register_objects_bulk(mixed).await.is_err());
+        assert_eq!(strict.object_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_object_stats() {
+        let manager = SystemManager::new();
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(100.0)));
+        hero.add_trait(crate::Trait::new("mana", crate::TraitData::Number(50.0)));
+        hero.set_metadata("faction", "alliance");
+        let mut goblin = Object::new("goblin", "enemy");
+        goblin.add_trait(crate::Trait::new("health", crate::TraitData::Number(30.0)));
+        manager.register_object(hero).await.unwrap();
+        manager.register_object(goblin).await.unwrap();
+        manager.register_object(Object::new("rock", "scenery")).await.unwrap();
+
+        let stats = manager.object_stats().await;
+        assert_eq!(stats.total_objects, 3);
+        assert_eq!(stats.total_traits, 3);
+        assert_eq!(stats.avg_traits_per_object, 1.0);
+        assert_eq!(stats.by_type["enemy"], 1);
+        assert_eq!(stats.trait_name_frequency["health"], 2);
+        assert_eq!(stats.metadata_key_frequency["faction"], 1);
+
+        let report = stats.to_report();
+        assert!(report.starts_with("Objects: 3 | Traits: 3 | Avg traits per object: 1.00"));
+        assert!(report.contains("health      2"));
+        assert_eq!(ObjectStats::default().to_report().lines().count(), 1);
+    }
+
+    struct ProductOnlySystem;
+
+    #[async_trait]
+    impl System for ProductOnlySystem {
+        fn name(&self) -> &str {
+            "product_only"
+        }
+
+        fn description(&self) -> &str {
+            "Only sees products"
+        }
+
+        fn object_filter(&self) -> Option<ObjectFilter> {
+            Some(ObjectFilter::object_type("product"))
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            assert!(objects.iter().all(|o| o.object_type() == "product"));
+            Ok(objects.iter().map(|_| ActionResult::success()).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_system_object_filter() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("john_doe", "customer")).await.unwrap();
+        let mut laptop = Object::new("laptop_pro", "product");
+        laptop.add_tag("featured");
+        manager.register_object(laptop).await.unwrap();
+        manager.register_object(Object::new("rust_book", "product")).await.unwrap();
+        manager.add_system(Box::new(ProductOnlySystem));
+
+        assert_eq!(manager.process_all(Priority::Normal).await.unwrap().len(), 2);
+
+        manager.assign_system_filter("product_only", ObjectFilter::tag("featured"));
+        assert_eq!(manager.process_all(Priority::Normal).await.unwrap().len(), 1);
+    }
+
+    struct LaneSystem(&'static str, Priority);
+
+    #[async_trait]
+    impl System for LaneSystem {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Reports its own name"
+        }
+
+        fn priority(&self) -> Priority {
+            self.1
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let mut result = ActionResult::success();
+            result.add_message(self.0);
+            Ok(vec![result])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_priority_lane() {
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(LaneSystem("physics", Priority::Critical)));
+        manager.add_system(Box::new(LaneSystem("ai", Priority::Low)));
+        manager.add_system(Box::new(LaneSystem("audio", Priority::Critical)));
+
+        let critical = manager.process_priority_lane(Priority::Critical).await.unwrap();
+        assert_eq!(critical.len(), 2);
+        assert!(critical.iter().all(|r| r.messages[0] != "ai"));
+
+        let low = manager.process_priority_lane(Priority::Low).await.unwrap();
+        assert_eq!(low.len(), 1);
+        assert_eq!(low[0].messages[0], "ai");
+
+        assert!(manager.process_priority_lane(Priority::High).await.unwrap().is_empty());
+        assert_eq!(manager.process_all(Priority::Normal).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_assign_system_filter() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("john_doe", "customer")).await.unwrap();
+        manager.register_object(Object::new("laptop_pro", "product")).await.unwrap();
+        manager.register_object(Object::new("rust_book", "product")).await.unwrap();
+        manager.add_system(Box::new(PricingSystem { discount: 0.1 }));
+
+        assert_eq!(manager.process_all(Priority::Normal).await.unwrap().len(), 3);
+
+        manager.assign_system_filter("pricing", ObjectFilter::object_type("product"));
+        assert_eq!(manager.process_all(Priority::Normal).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_object_migrates_traits() {
+        use crate::traits::TraitMigration;
+
+        let mut migrations = TraitMigrationRegistry::new();
+        migrations.register("health", TraitMigration::new(1, 2, |data| {
+            Ok(crate::TraitData::Number(data.as_number().unwrap_or(0.0) * 10.0))
+        }));
+        let manager = SystemManager::new().with_migration_registry(migrations);
+
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(10.0)));
+        let id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+
+        let hero = manager.get_object(&id).await.unwrap();
+        let health = hero.get_trait("health").unwrap();
+        assert_eq!(health.version(), 2);
+        assert_eq!(health.data().as_number(), Some(100.0));
+
+        let hero = manager.get_object(&id).await.unwrap();
+        assert_eq!(hero.get_trait_data("health").and_then(|d| d.as_number()), Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_spatial_index_tracks_registered_objects() {
+        let manager = SystemManager::new().with_spatial_index();
+        let mut position = HashMap::new();
+        position.insert("x".to_string(), serde_json::json!(1.0));
+        position.insert("y".to_string(), serde_json::json!(1.0));
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("position", crate::TraitData::Object(position)));
+        let hero_id = hero.id();
+        manager.register_object(hero).await.unwrap();
+        manager.register_object(Object::new("rock", "scenery")).await.unwrap();
+
+        let index = manager.spatial_index().unwrap();
+        assert_eq!(index.read().await.len(), 1);
+        assert_eq!(index.read().await.within_radius([0.0, 0.0], 2.0), vec![hero_id]);
+
+        manager.clear_objects().await;
+        assert!(index.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_action_result() {
+        let manager = SystemManager::new();
+        let hero = Object::new("hero", "player");
+        let id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+
+        let mut result = ActionResult::success();
+        result.add_trait_update(crate::Trait::new("health", crate::TraitData::Number(75.0)));
+        manager.apply_action_result(&id, &result).await.unwrap();
+
+        let hero = manager.get_object(&id).await.unwrap();
+        assert_eq!(hero.get_trait_data("health").and_then(|d| d.as_number()), Some(75.0));
+        assert!(matches!(
+            manager.apply_action_result("missing", &result).await,
+            Err(OatsError::ObjectNotFound { .. })
+        ));
+    }
+
+    struct RegenSystem;
+
+    #[async_trait]
+    impl System for RegenSystem {
+        fn name(&self) -> &str {
+            "regen"
+        }
+
+        fn description(&self) -> &str {
+            "Restores health"
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let mut results: Vec<ActionResult> = objects
+                .iter()
+                .map(|object| {
+                    let mut result = ActionResult::success();
+                    result.add_trait_update(crate::Trait::new("health", crate::TraitData::Number(100.0)));
+                    result.set_target(object.id());
+                    result
+                })
+                .collect();
+            results.push(ActionResult::failure("no mana"));
+            Ok(results)
+        }
+    }
+
+    struct HealthReportSystem;
+
+    #[async_trait]
+    impl System for HealthReportSystem {
+        fn name(&self) -> &str {
+            "health_report"
+        }
+
+        fn description(&self) -> &str {
+            "Reports the health of healed objects"
+        }
+
+        async fn process(&mut self, objects: &[Object], priority: Priority) -> Result<Vec<ActionResult>> {
+            self.process_with_context(objects, priority, &ActionContext::new()).await
+        }
+
+        async fn process_with_context(
+            &mut self,
+            objects: &[Object],
+            _priority: Priority,
+            shared: &ActionContext<'_>,
+        ) -> Result<Vec<ActionResult>> {
+            Ok(objects
+                .iter()
+                .map(|object| {
+                    let mut result = ActionResult::success();
+                    result.add_data("health", serde_json::json!(object.get_trait_data("health").and_then(|d| d.as_number())));
+                    result.add_data("healed", serde_json::json!(shared.get_object("healed").map(|o| o.name())));
+                    result
+                })
+                .collect())
+        }
+
+        fn priority(&self) -> Priority {
+            Priority::High
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_with_progress() {
+        let mut manager = SystemManager::new();
+        for name in ["hero", "healer", "rogue"] {
+            manager.register_object(Object::new(name, "player")).await.unwrap();
+        }
+        manager.add_system(Box::new(RegenSystem));
+        manager.add_system(Box::new(HealthReportSystem));
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let handle = tokio::spawn(async move {
+            manager
+                .run_with_progress(Priority::Normal, move |event| sink.lock().unwrap().push(event))
+                .await
+        });
+        assert!(handle.await.unwrap().is_ok());
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![ProgressEvent::new("health_report", 3, 6), ProgressEvent::new("regen", 6, 6)]
+        );
+        assert_eq!(events[0].percentage, 50.0);
+        assert_eq!(events[1].percentage, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_pipe_output() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("hero", "player")).await.unwrap();
+        manager.add_system(Box::new(RegenSystem));
+        manager.add_system(Box::new(HealthReportSystem));
+        assert!(manager.pipe_output("regen", "regen", "healed").is_err());
+        manager.pipe_output("regen", "health_report", "healed").unwrap();
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        let reports: Vec<_> = results.iter().filter(|r| r.data.contains_key("health")).collect();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].data["health"], serde_json::json!(100.0));
+        assert_eq!(reports[0].data["healed"], serde_json::json!("hero"));
+    }
+
+    #[tokio::test]
+    async fn test_process_and_apply() {
+        let mut manager = SystemManager::new();
+        let hero = Object::new("hero", "player");
+        let id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+        manager.register_object(Object::new("healer", "player")).await.unwrap();
+        manager.add_system(Box::new(RegenSystem));
+
+        let summary = manager.process_and_apply(Priority::Normal).await.unwrap();
+        assert_eq!(
+            summary,
+            ProcessingSummary { total_actions: 3, total_updates: 2, total_errors: 1, expired_traits_purged: 0 }
+        );
+
+        let hero = manager.get_object(&id).await.unwrap();
+        assert_eq!(hero.get_trait_data("health").and_then(|d| d.as_number()), Some(100.0));
+    }
+
+    struct TaxRate(f64);
+
+    struct TaxReportSystem;
+
+    #[async_trait]
+    impl System for TaxReportSystem {
+        fn name(&self) -> &str {
+            "tax_report"
+        }
+
+        fn description(&self) -> &str {
+            "Reports the tax rate service seen by new contexts"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let context = ActionContext::new();
+            let mut result = ActionResult::success();
+            result.add_data("tax_rate", serde_json::json!(context.services.get::<TaxRate>().map(|rate| rate.0)));
+            Ok(vec![result])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_services_reaches_new_contexts() {
+        let services = ServiceLocator::new().with_service(TaxRate(0.2));
+        let mut manager = SystemManager::new().with_services(services);
+        manager.add_system(Box::new(TaxReportSystem));
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results[0].data["tax_rate"], serde_json::json!(0.2));
+        let results = manager.process_all_parallel(Priority::Normal).await.unwrap();
+        assert_eq!(results[0].data["tax_rate"], serde_json::json!(0.2));
+        assert!(ActionContext::new().services.is_empty());
+    }
+
+    #[cfg(feature = "snapshot")]
+    struct BurnAction;
+
+    #[cfg(feature = "snapshot")]
+    #[async_trait]
+    impl Action for BurnAction {
+        fn name(&self) -> &str {
+            "burn"
+        }
+
+        fn description(&self) -> &str {
+            "Burns away a tenth of the target's health plus a little"
+        }
+
+        async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult> {
+            let health = context
+                .get_object("target")
+                .and_then(|object| object.get_trait("health"))
+                .and_then(|t| t.data().as_number())
+                .unwrap_or(0.0);
+            let mut result = ActionResult::success();
+            result.add_trait_update(crate::Trait::new("health", crate::TraitData::Number(health * 0.9 - 0.37)));
+            Ok(result)
+        }
+    }
+
+    #[cfg(feature = "snapshot")]
+    struct BurnSystem;
+
+    #[cfg(feature = "snapshot")]
+    #[async_trait]
+    impl System for BurnSystem {
+        fn name(&self) -> &str {
+            "burn"
+        }
+
+        fn description(&self) -> &str {
+            "Runs the burn action on every object"
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let mut results = Vec::new();
+            for object in objects {
+                let mut context = ActionContext::new();
+                context.add_object("target", object.clone());
+                let mut result = BurnAction.execute_scoped(context).await?;
+                result.set_target(object.id());
+                results.push(result);
+            }
+            Ok(results)
+        }
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[tokio::test]
+    async fn test_journal_replay() {
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(BurnSystem));
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(100.0)));
+        let hero_id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+
+        manager.process_all(Priority::Normal).await.unwrap();
+        assert!(manager.export_journal().is_empty());
+        manager.journal_mode(true);
+        for _ in 0..3 {
+            for result in manager.process_all(Priority::Normal).await.unwrap() {
+                manager.apply_action_result(&hero_id, &result).await.unwrap();
+            }
+        }
+        manager.journal_mode(false);
+        manager.process_all(Priority::Normal).await.unwrap();
+
+        let journal = manager.export_journal();
+        assert_eq!(journal.len(), 3);
+        assert_eq!(journal[0].system_name, "burn");
+        assert_eq!(journal[0].action_name, "burn");
+
+        let mut replayer = SystemManager::new();
+        assert!(replayer.replay(&journal).await.is_err());
+        replayer.register_replay_action(Box::new(BurnAction));
+        let replayed = replayer.replay(&journal).await.unwrap();
+        let health = |result: &ActionResult| result.trait_updates[0].data().as_number().unwrap();
+        for (entry, result) in journal.iter().zip(&replayed) {
+            assert!((health(&entry.result) - health(result)).abs() < 1e-9);
+        }
+
+        let recorded = manager.get_object(&hero_id).await.unwrap();
+        let recorded = recorded.get_trait("health").unwrap().data().as_number().unwrap();
+        assert!((health(&replayed[2]) - recorded).abs() < 1e-9);
+        assert_eq!(replayer.object_count().await, 0);
+
+        manager.clear_journal();
+        assert!(manager.export_journal().is_empty());
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[tokio::test]
+    async fn test_journal_capacity_keeps_newest_entries() {
+        let mut manager = SystemManager::new().with_journal_capacity(2);
+        manager.add_system(Box::new(BurnSystem));
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(100.0)));
+        let hero_id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+
+        manager.journal_mode(true);
+        for _ in 0..3 {
+            for result in manager.process_all(Priority::Normal).await.unwrap() {
+                manager.apply_action_result(&hero_id, &result).await.unwrap();
+            }
+        }
+        let journal = manager.export_journal();
+        assert_eq!(journal.len(), 2);
+        let health = |result: &ActionResult| result.trait_updates[0].data().as_number().unwrap();
+        let first_run = 100.0 * 0.9 - 0.37;
+        assert!((health(&journal[0].result) - (first_run * 0.9 - 0.37)).abs() < 1e-9);
+        assert!(health(&journal[1].result) < health(&journal[0].result));
+
+        let manager = manager.with_journal_capacity(1);
+        assert_eq!(manager.export_journal()[0].timestamp, journal[1].timestamp);
+    }
+
+    struct NameReportSystem;
+
+    #[async_trait]
+    impl System for NameReportSystem {
+        fn name(&self) -> &str {
+            "name_report"
+        }
+
+        fn description(&self) -> &str {
+            "Reports the system name seen by its processing task"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let mut result = ActionResult::success();
+            result.add_data("system", serde_json::json!(crate::current_system_name()));
+            Ok(vec![result])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_all_sets_current_system_name() {
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(NameReportSystem));
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results[0].data.get("system"), Some(&serde_json::json!("name_report")));
+        assert_eq!(crate::current_system_name(), None);
+    }
+
+    #[cfg(feature = "reactive")]
+    #[tokio::test]
+    async fn test_events_observable() {
+        use crate::observable::SystemEvent;
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(NameReportSystem));
+        manager.register_object(Object::new("hero", "player")).await.unwrap();
+
+        let events = manager.events();
+        let results = events.filter(SystemEvent::is_action_completed).map(SystemEvent::into_result);
+        manager.process_all(Priority::Normal).await.unwrap();
+        drop(manager);
+
+        let results: Vec<_> = futures::StreamExt::collect(results).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].as_ref().unwrap().data.get("system"), Some(&serde_json::json!("name_report")));
+    }
+
+    /// Never finishes processing
+    struct StallSystem;
+
+    #[async_trait]
+    impl System for StallSystem {
+        fn name(&self) -> &str {
+            "stall"
+        }
+
+        fn description(&self) -> &str {
+            "Never finishes"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            futures::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_dry_run_restores_registry() {
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(StallSystem));
+        let registry = manager.registry();
+        let cancelled =
+            tokio::time::timeout(std::time::Duration::from_millis(20), manager.dry_run_process_all(Priority::Normal)).await;
+        assert!(cancelled.is_err());
+
+        manager.register_object(Object::new("hero", "player")).await.unwrap();
+        assert_eq!(registry.read().await.len(), 1);
+        assert_eq!(manager.object_count_by_type("player"), 1);
+    }
+
+    #[cfg(feature = "reactive")]
+    #[tokio::test]
+    async fn test_dry_run_publishes_no_events_even_when_cancelled() {
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(NameReportSystem));
+        manager.register_object(Object::new("hero", "player")).await.unwrap();
+        let events = manager.events();
+
+        manager.dry_run_process_all(Priority::Normal).await.unwrap();
+        manager.add_system(Box::new(StallSystem));
+        let cancelled =
+            tokio::time::timeout(std::time::Duration::from_millis(20), manager.dry_run_process_all(Priority::Normal)).await;
+        assert!(cancelled.is_err());
+        manager.remove_system("stall");
+
+        manager.process_all(Priority::Normal).await.unwrap();
+        drop(manager);
+        let events: Vec<_> = futures::StreamExt::collect(events).await;
+        assert_eq!(events.iter().filter(|e| e.is_action_completed()).count(), 1);
+    }
+
+    /// Tracks how many executions are in flight, recording the highest count seen
+    struct InFlightAction {
+        in_flight: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Action for InFlightAction {
+        fn name(&self) -> &str {
+            "in_flight"
+        }
+
+        fn description(&self) -> &str {
+            "Records concurrent executions"
+        }
+
+        async fn execute(&self, _context: ActionContext<'_>) -> Result<ActionResult> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(ActionResult::success())
+        }
+    }
+
+    /// Dispatches one action per object, all at once
+    struct FanOutSystem {
+        action: Box<dyn Action>,
+    }
+
+    #[async_trait]
+    impl System for FanOutSystem {
+        fn name(&self) -> &str {
+            "fan_out"
+        }
+
+        fn description(&self) -> &str {
+            "Dispatches every object's action concurrently"
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let runs = objects.iter().map(|_| self.action.execute_scoped(ActionContext::new()));
+            futures::future::try_join_all(runs).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_system_concurrency_limit_applies_per_action_dispatch() {
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut manager = SystemManager::new();
+        for i in 0..8 {
+            manager.register_object(Object::new(format!("item_{}", i), "product")).await.unwrap();
+        }
+        let action = InFlightAction { in_flight: Arc::new(AtomicUsize::new(0)), peak: Arc::clone(&peak) };
+        manager.add_system(Box::new(FanOutSystem { action: Box::new(action) }));
+
+        manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(peak.swap(0, Ordering::SeqCst), 8);
+
+        assert!(manager.set_system_concurrency_limit("fan_out", 0).is_err());
+        manager.set_system_concurrency_limit("fan_out", 2).unwrap();
+        assert!(manager.system_concurrency_limiter("regen").is_none());
+        assert_eq!(manager.process_all(Priority::Normal).await.unwrap().len(), 8);
+        assert_eq!(peak.swap(0, Ordering::SeqCst), 2);
+        manager.process_all_parallel(Priority::Normal).await.unwrap();
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(manager.system_concurrency_limiter("fan_out").unwrap().available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_system_concurrency_limit_does_not_deadlock_nested_wrappers() {
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut manager = SystemManager::new();
+        for i in 0..4 {
+            manager.register_object(Object::new(format!("item_{}", i), "product")).await.unwrap();
+        }
+        let action = InFlightAction { in_flight: Arc::new(AtomicUsize::new(0)), peak: Arc::clone(&peak) }
+            .with_timeout(std::time::Duration::from_secs(5))
+            .with_fallback(Box::new(InFlightAction { in_flight: Arc::new(AtomicUsize::new(0)), peak: Arc::clone(&peak) }));
+        manager.add_system(Box::new(FanOutSystem { action: Box::new(action) }));
+        manager.set_system_concurrency_limit("fan_out", 1).unwrap();
+
+        let run = tokio::time::timeout(std::time::Duration::from_secs(5), manager.process_all(Priority::Normal));
+        assert_eq!(run.await.expect("nested wrappers deadlocked").unwrap().len(), 4);
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "snapshot")]
+    #[tokio::test]
+    async fn test_journal_records_only_the_outermost_action() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("item", "product")).await.unwrap();
+        let action = InFlightAction { in_flight: Arc::new(AtomicUsize::new(0)), peak: Arc::new(AtomicUsize::new(0)) }
+            .with_timeout(std::time::Duration::from_secs(5));
+        manager.add_system(Box::new(FanOutSystem { action: Box::new(action) }));
+
+        manager.journal_mode(true);
+        manager.process_all(Priority::Normal).await.unwrap();
+        let journal = manager.export_journal();
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal[0].action_name, "in_flight(timeout=5000ms)");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_process_all() {
+        let mut manager = SystemManager::new();
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(40.0)));
+        let id = hero.id();
+        manager.register_object(hero).await.unwrap();
+        manager.add_system(Box::new(RegenSystem));
+
+        let report = manager.dry_run_process_all(Priority::Normal).await.unwrap();
+        assert_eq!(report.total_trait_updates(), 1);
+        assert!(report.has_any_failures());
+        let diff = &report.predicted_object_changes[&id];
+        assert_eq!(diff.changed["health"], (crate::TraitData::Number(40.0), crate::TraitData::Number(100.0)));
+
+        let hero = manager.get_object(&id.to_string()).await.unwrap();
+        assert_eq!(hero.get_trait_data("health").and_then(|d| d.as_number()), Some(40.0));
+        assert!(manager.last_process_time.is_none());
+    }
+
+    /// Spawns one minion per processing run through the current object store
+    struct SpawnSystem;
+
+    #[async_trait]
+    impl System for SpawnSystem {
+        fn name(&self) -> &str {
+            "spawn"
+        }
+
+        fn description(&self) -> &str {
+            "Spawns a minion"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let mut minion = Object::new("minion", "npc");
+            minion.add_trait(crate::Trait::new("health", crate::TraitData::Number(10.0)));
+            ObjectStore::current().unwrap().write().await.insert(minion);
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_added_and_removed_objects_without_touching_registry() {
+        let mut manager = SystemManager::new().with_spatial_index();
+        let mut corpse = Object::new("corpse", "npc");
+        corpse.add_trait(crate::Trait::new("position", crate::TraitData::Object(HashMap::from([
+            ("x".to_string(), serde_json::json!(1.0)),
+            ("y".to_string(), serde_json::json!(1.0)),
+        ]))));
+        let corpse_id = corpse.id();
+        manager.register_object(corpse).await.unwrap();
+        let collector = manager
+            .garbage_collection_system("corpse_collector", std::time::Duration::ZERO, ObjectFilter::has_trait("position"))
+            .with_hard_delete(true);
+        manager.add_system(Box::new(collector));
+        manager.add_system(Box::new(SpawnSystem));
+        let mut changes = manager.object_changes();
+
+        let report = manager.dry_run_process_all(Priority::Low).await.unwrap();
+        assert_eq!(report.removed_objects, vec![corpse_id]);
+        assert_eq!(report.added_objects.len(), 1);
+        let minion = &report.added_objects[0];
+        assert_eq!(minion.name, "minion");
+        assert!(report.predicted_object_changes[&minion.id].added.contains_key("health"));
+
+        assert!(manager.get_object(&corpse_id.to_string()).await.is_some());
+        assert_eq!(manager.object_count().await, 1);
+        assert_eq!(manager.object_count_by_type("npc"), 1);
+        assert_eq!(manager.spatial_index().unwrap().read().await.len(), 1);
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_process_and_apply_purges_expired_traits() {
+        let mut buff = crate::Trait::new("haste", crate::TraitData::Number(2.0));
+        buff.set_expires_at(chrono::Utc::now() - chrono::Duration::seconds(1));
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(buff);
+        hero.add_trait(crate::Trait::new("shield", crate::TraitData::Number(1.0)).with_ttl(std::time::Duration::from_secs(3600)));
+        let id = hero.id().to_string();
+
+        let mut manual = SystemManager::new().with_auto_purge_expired(false);
+        manual.register_object(hero.clone()).await.unwrap();
+        assert_eq!(manual.process_and_apply(Priority::Normal).await.unwrap().expired_traits_purged, 0);
+        assert!(manual.get_object(&id).await.unwrap().has_trait("haste"));
+
+        let mut manager = SystemManager::new();
+        manager.register_object(hero).await.unwrap();
+        let summary = manager.process_and_apply(Priority::Normal).await.unwrap();
+        assert_eq!(summary.expired_traits_purged, 1);
+        let hero = manager.get_object(&id).await.unwrap();
+        assert!(!hero.has_trait("haste"));
+        assert!(hero.has_trait("shield"));
+    }
+
+    #[tokio::test]
+    async fn test_object_counts_by_type() {
+        let mut manager = SystemManager::new();
+        let customer = Object::new("john_doe", "customer");
+        let customer_id = customer.id().to_string();
+        manager.register_object(customer.clone()).await.unwrap();
+        manager.register_object(customer).await.unwrap();
+        manager.register_object(Object::new("laptop_pro", "product")).await.unwrap();
+        manager.register_object(Object::new("rust_book", "product")).await.unwrap();
+
+        assert_eq!(manager.object_count_by_type("customer"), 1);
+        assert_eq!(manager.object_count_by_type("product"), 2);
+        assert_eq!(manager.object_count_by_type("order"), 0);
+
+        assert!(manager.remove_object(&customer_id).await.is_some());
+        assert!(manager.remove_object(&customer_id).await.is_none());
+        let counts = manager.object_counts_by_type();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts["product"], 2);
+
+        manager.clear_objects().await;
+        assert!(manager.object_counts_by_type().is_empty());
+
+        let order = Object::new("order_1", "order");
+        manager.registry().write().await.insert(order.id().to_string(), order);
+        manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(manager.object_count_by_type("order"), 0);
+        manager.register_object(Object::new("order_2", "order")).await.unwrap();
+        assert_eq!(manager.object_count_by_type("order"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_strict_type_checking() {
+        let mut manager = SystemManager::new().with_strict_type_checking(true);
+        manager.register_object_type("product");
+
+        manager.register_object(Object::new("laptop_pro", "product")).await.unwrap();
+        assert!(matches!(
+            manager.register_object(Object::new("rust_book", "prodcut")).await,
+            Err(OatsError::ValidationError { .. })
+        ));
+        assert_eq!(manager.object_count().await, 1);
+
+        let lenient = SystemManager::new();
+        assert!(lenient.register_object(Object::new("rust_book", "prodcut")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_process_since_last() {
+        let mut manager = SystemManager::new();
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(50.0)));
+        let hero_id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+        manager.register_object(Object::new("rock", "scenery")).await.unwrap();
+        manager.add_system(Box::new(PricingSystem { discount: 0.0 }));
+
+        assert!(manager.last_process_time().is_none());
+        assert_eq!(manager.process_since_last(Priority::Normal).await.unwrap().len(), 2);
+        let since = manager.last_process_time().unwrap();
+        assert!(manager.process_since_last(Priority::Normal).await.unwrap().is_empty());
+
+        let mut result = ActionResult::success();
+        result.add_trait_update(crate::Trait::new("health", crate::TraitData::Number(60.0)));
+        manager.apply_action_result(&hero_id, &result).await.unwrap();
+
+        let updated = manager.get_objects_updated_since(since).await;
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].name(), "hero");
+        assert_eq!(manager.process_since_last(Priority::Normal).await.unwrap().len(), 1);
+
+        let since = manager.last_process_time().unwrap();
+        manager.process_priority_lane(Priority::Normal).await.unwrap();
+        let lane = manager.last_process_time().unwrap();
+        assert!(lane > since);
+        manager.process_with_context(Priority::Normal, ActionContext::new()).await.unwrap();
+        assert!(manager.last_process_time().unwrap() > lane);
+    }
+
+    #[tokio::test]
+    async fn test_max_objects() {
+        let manager = SystemManager::new().with_max_objects(2);
+        assert_eq!(manager.object_limit(), Some(2));
+
+        let first = Object::new("sensor_1", "sensor");
+        manager.register_object(first.clone()).await.unwrap();
+        manager.register_object(Object::new("sensor_2", "sensor")).await.unwrap();
+        assert!(matches!(
+            manager.register_object(Object::new("sensor_3", "sensor")).await,
+            Err(OatsError::ResourceExhausted { .. })
+        ));
+        // Replacing a registered object does not need a free slot
+        manager.register_object(first.clone()).await.unwrap();
+
+        let evicted = manager.evict_oldest_n(1).await;
+        assert_eq!(evicted[0].id(), first.id());
+        assert_eq!(manager.object_count_by_type("sensor"), 1);
+        manager.register_object(Object::new("sensor_3", "sensor")).await.unwrap();
+        assert_eq!(manager.object_count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_to_object_type() {
+        let manager = SystemManager::new();
+        let mut all_changes = manager.object_changes();
+        let mut customer_changes = manager.subscribe_to_object_type("customer");
+
+        let alice = Object::new("alice", "customer");
+        let widget = Object::new("widget", "product");
+        manager.register_objects_bulk(vec![alice.clone(), widget.clone()]).await.unwrap();
+        let mut applied = ActionResult::success();
+        applied.add_trait_update(crate::Trait::new("balance", crate::TraitData::Number(10.0)));
+        manager.apply_action_result(&alice.id().to_string(), &applied).await.unwrap();
+        manager.remove_object(&widget.id().to_string()).await.unwrap();
+        manager.remove_object(&alice.id().to_string()).await.unwrap();
+
+        let mut customer_kinds = Vec::new();
+        while let Ok(change) = customer_changes.try_recv() {
+            assert_eq!(change.object().id(), alice.id());
+            customer_kinds.push(change);
+        }
+        assert!(matches!(
+            customer_kinds.as_slice(),
+            [ObjectChange::Added(_), ObjectChange::Updated(updated), ObjectChange::Removed(_)] if updated.has_trait("balance")
+        ));
+
+        let mut total = 0;
+        while all_changes.try_recv().is_ok() {
+            total += 1;
+        }
+        assert_eq!(total, 5);
+    }
+
+    #[tokio::test]
+    async fn test_memory_limit_bytes() {
+        let sensor = Object::new("sensor_1", "sensor");
+        let size = sensor.estimated_memory_size();
+        let manager = SystemManager::new().with_memory_limit_bytes(size * 2);
+        manager.register_object(sensor.clone()).await.unwrap();
+        manager.register_object(Object::new("sensor_2", "sensor")).await.unwrap();
+        assert_eq!(manager.total_estimated_memory_bytes().await, size * 2);
+
+        assert!(matches!(
+            manager.register_object(Object::new("sensor_3", "sensor")).await,
+            Err(OatsError::ResourceExhausted { .. })
+        ));
+        assert!(manager.register_objects_bulk(vec![Object::new("sensor_4", "sensor")]).await.is_err());
+        // Replacing a registered object only counts its new size
+        let sensor_id = sensor.id().to_string();
+        manager.register_object(sensor).await.unwrap();
+        assert_eq!(manager.object_count().await, 2);
+
+        // The running total follows applied results, removals and restores
+        let mut result = ActionResult::success();
+        result.add_trait_update(crate::Trait::new("reading", crate::TraitData::Number(1.0)));
+        manager.apply_action_result(&sensor_id, &result).await.unwrap();
+        let grown = manager.get_object(&sensor_id).await.unwrap().estimated_memory_size();
+        assert_eq!(manager.total_estimated_memory_bytes().await, size + grown);
+        manager.remove_object(&sensor_id).await.unwrap();
+        assert_eq!(manager.total_estimated_memory_bytes().await, size);
+        assert_eq!(manager.evict_oldest_n(1).await.len(), 1);
+        assert_eq!(manager.total_estimated_memory_bytes().await, 0);
+        let objects = HashMap::from([(sensor_id, Object::new("sensor_1", "sensor"))]);
+        manager.restore_objects(objects).await;
+        assert_eq!(manager.total_estimated_memory_bytes().await, size);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_on_error() {
+        let mut manager = SystemManager::new();
+        let mut hero = Object::new("hero", "player");
+        hero.add_trait(crate::Trait::new("health", crate::TraitData::Number(50.0)));
+        let hero_id = hero.id().to_string();
+        manager.register_object(hero).await.unwrap();
+
+        let failed: Result<()> = manager
+            .transaction(|m| {
+                let hero_id = hero_id.clone();
+                Box::pin(async move {
+                    let mut result = ActionResult::success();
+                    result.add_trait_update(crate::Trait::new("health", crate::TraitData::Number(0.0)));
+                    m.apply_action_result(&hero_id, &result).await?;
+                    m.register_object(Object::new("goblin", "enemy")).await?;
+                    Err(OatsError::system_error("third system failed"))
+                })
+            })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(manager.object_count().await, 1);
+        assert_eq!(manager.object_count_by_type("enemy"), 0);
+        let health = manager.get_object(&hero_id).await.unwrap().get_trait_data("health").and_then(|d| d.as_number());
+        assert_eq!(health, Some(50.0));
+
+        let nested: Result<()> = manager
+            .transaction(|m| Box::pin(async move { m.transaction(|_| Box::pin(async { Ok(()) })).await }))
+            .await;
+        assert!(matches!(nested, Err(OatsError::InvalidState { .. })));
+
+        let cancelled = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            manager.transaction(|_| Box::pin(futures::future::pending::<Result<()>>())),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        let panicked = futures::FutureExt::catch_unwind(std::panic::AssertUnwindSafe(
+            manager.transaction::<(), _>(|_| Box::pin(async { panic!("system crashed") })),
+        ))
+        .await;
+        assert!(panicked.is_err());
+
+        let count = manager
+            .transaction(|m| {
+                Box::pin(async move {
+                    m.register_object(Object::new("goblin", "enemy")).await?;
+                    Ok(m.object_count_by_type("enemy"))
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(manager.object_count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_garbage_collection_system() {
+        let mut manager = SystemManager::new();
+        let mut completed = Object::new("order_1", "order");
+        completed.updated_at = chrono::Utc::now() - chrono::Duration::days(3);
+        let completed_id = completed.id().to_string();
+        let mut stale_enemy = Object::new("goblin", "enemy");
+        stale_enemy.updated_at = completed.updated_at;
+        manager.register_object(completed).await.unwrap();
+        manager.register_object(stale_enemy).await.unwrap();
+        manager.register_object(Object::new("order_2", "order")).await.unwrap();
+
+        let gc = manager.garbage_collection_system("order_gc", std::time::Duration::from_secs(3600), ObjectFilter::object_type("order"));
+        manager.add_system(Box::new(gc));
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(manager.get_object(&completed_id).await.unwrap().is_deleted());
+        assert_eq!(manager.get_all_objects().await.len(), 2);
+        assert_eq!(manager.get_all_stats()["order_gc"].gc_collected, 1);
+
+        let gc = manager
+            .garbage_collection_system("enemy_gc", std::time::Duration::from_secs(3600), ObjectFilter::object_type("enemy"))
+            .with_hard_delete(true);
+        manager.add_system(Box::new(gc));
+        manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(manager.object_count().await, 2);
+        assert_eq!(manager.object_count_by_type("enemy"), 0);
+        let stats = manager.get_all_stats();
+        assert_eq!(stats["order_gc"].gc_collected, 1);
+        assert_eq!(stats["enemy_gc"].gc_collected, 1);
+    }
+
+    struct TaskEchoSystem;
+
+    #[async_trait]
+    impl System for TaskEchoSystem {
+        fn name(&self) -> &str {
+            "task_echo"
+        }
+
+        fn description(&self) -> &str {
+            "Reports the task ID seen by new contexts"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            let mut result = ActionResult::success();
+            result.add_data("context_task_id", serde_json::json!(ActionContext::new().task_id));
+            Ok(vec![result])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_all_propagates_task_id() {
+        let handle = tokio::spawn(async {
+            let mut manager = SystemManager::new();
+            manager.add_system(Box::new(TaskEchoSystem));
+            let results = manager.process_all(Priority::Normal).await.unwrap();
+            (tokio::task::id().to_string(), results)
+        });
+        let (task_id, results) = handle.await.unwrap();
+        assert_eq!(results[0].data["task_id"], serde_json::json!(task_id));
+        assert_eq!(results[0].data["context_task_id"], serde_json::json!(task_id));
+        assert!(ActionContext::new().task_id.is_none());
+
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(TaskEchoSystem));
+        let first = manager.process_all(Priority::Normal).await.unwrap();
+        let second = manager.process_all(Priority::Normal).await.unwrap();
+        assert!(first[0].data["task_id"].is_string());
+        assert_eq!(first[0].data["context_task_id"], first[0].data["task_id"]);
+        assert_ne!(first[0].data["task_id"], second[0].data["task_id"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_object() {
+        let manager = SystemManager::new();
+        let order = Object::new("order_1001", "order");
+        let id = order.id().to_string();
+        manager.register_object(order).await.unwrap();
+
+        let bytes = manager.export_object(&id, "json").await.unwrap();
+        let exported: Object = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(exported.name(), "order_1001");
+        assert!(matches!(manager.export_object("missing", "json").await, Err(OatsError::ObjectNotFound { .. })));
+        assert!(manager.export_object(&id, "xml").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_soft_deleted_objects_are_hidden() {
+        let manager = SystemManager::new();
+        let mut defeated = Object::new("goblin", "enemy");
+        defeated.soft_delete();
+        manager.register_object(defeated).await.unwrap();
+        manager.register_object(Object::new("hero", "player")).await.unwrap();
+
+        assert_eq!(manager.get_all_objects().await.len(), 1);
+        assert_eq!(manager.get_all_objects_including_deleted().await.len(), 2);
+    }
+
+    struct PricingSystem {
+        discount: f64,
+    }
+
+    impl PricingSystem {
+        fn apply_manual_discount(&mut self, rate: f64) {
+            self.discount = rate;
+        }
+    }
+
+    #[async_trait]
+    impl System for PricingSystem {
+        fn name(&self) -> &str {
+            "pricing"
+        }
+
+        fn description(&self) -> &str {
+            "Applies discounts"
+        }
+
+        async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            Ok(objects
+                .iter()
+                .map(|_| {
+                    let mut result = ActionResult::success();
+                    result.add_data("discount", serde_json::json!(self.discount));
+                    result
+                })
+                .collect())
+        }
+    }
+
+    impl HotSwapable for PricingSystem {}
+
+    #[tokio::test]
+    async fn test_swap_system() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("order", "order")).await.unwrap();
+        manager.add_system(Box::new(PricingSystem { discount: 0.1 }));
+
+        let old = manager.swap_system("pricing", Box::new(PricingSystem { discount: 0.2 })).await.unwrap();
+        assert_eq!(old.name(), "pricing");
+        assert_eq!(manager.system_count(), 1);
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results[0].data["discount"], serde_json::json!(0.2));
+
+        assert!(manager.swap_system("billing", Box::new(PricingSystem { discount: 0.3 })).await.is_err());
+        assert_eq!(manager.system_count(), 1);
+    }
+
+    /// Fails every `initialize` call
+    struct BrokenInitSystem;
+
+    #[async_trait]
+    impl System for BrokenInitSystem {
+        fn name(&self) -> &str {
+            "broken"
+        }
+
+        fn description(&self) -> &str {
+            "Cannot be initialized"
+        }
+
+        async fn initialize(&mut self) -> Result<()> {
+            Err(OatsError::system_error("init failed"))
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    impl HotSwapable for BrokenInitSystem {}
+
+    #[tokio::test]
+    async fn test_swap_system_keeps_old_system_when_both_initializations_fail() {
+        let mut manager = SystemManager::new();
+        manager.add_system(Box::new(BrokenInitSystem));
+
+        let Err(err) = manager.swap_system("broken", Box::new(BrokenInitSystem)).await else {
+            panic!("swap should fail");
+        };
+        assert!(err.to_string().contains("re-initializing the old system also failed"));
+        assert!(manager.get_system("broken").is_some());
+    }
+
+    struct HookedSystem {
+        fail_before: bool,
+        processed: bool,
+        after_calls: usize,
+    }
+
+    #[async_trait]
+    impl System for HookedSystem {
+        fn name(&self) -> &str {
+            "hooked"
+        }
+
+        fn description(&self) -> &str {
+            "Records hook calls"
+        }
+
+        async fn before_process(&mut self, _objects: &[Object], _priority: Priority) -> Result<()> {
+            if self.fail_before {
+                return Err(OatsError::system_error("cache flush failed"));
+            }
+            Ok(())
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            self.processed = true;
+            Ok(vec![ActionResult::success()])
+        }
+
+        async fn after_process(&mut self, _results: &[ActionResult], _priority: Priority) -> Result<()> {
+            self.after_calls += 1;
+            Err(OatsError::system_error("journal unavailable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_hooks() {
+        let mut system = HookedSystem { fail_before: false, processed: false, after_calls: 0 };
+        let results = run_with_hooks(&mut system, &[], Priority::Normal, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(system.after_calls, 1);
+
+        let mut system = HookedSystem { fail_before: true, processed: false, after_calls: 0 };
+        assert!(run_with_hooks(&mut system, &[], Priority::Normal, None).await.is_err());
+        assert!(!system.processed);
+        assert_eq!(system.after_calls, 0);
+    }
+
+    #[tokio::test]
+    async fn test_typed_system_access() {
+        let mut manager = SystemManager::new();
+        manager.register_object(Object::new("order", "order")).await.unwrap();
+        manager.add_typed_system(PricingSystem { discount: 0.0 });
+
+        let pricing = manager.get_typed_system::<PricingSystem>().unwrap();
+        pricing.lock().await.apply_manual_discount(0.15);
+        assert!(manager.get_typed_system::<HookedSystem>().is_none());
+
+        let results = manager.process_all(Priority::Normal).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].data["discount"], serde_json::json!(0.15));
+
+        let results = manager.process_typed::<PricingSystem>(Priority::Normal).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(manager.system_count(), 1);
+    }
+
+    /// Reports itself under the name it was created with
+    struct LabelledSystem(&'static str);
+
+    #[async_trait]
+    impl System for LabelledSystem {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Does nothing under a given name"
+        }
+
+        async fn process(&mut self, _objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_typed_system_replaces_a_busy_system() {
+        let mut manager = SystemManager::new();
+        let first = manager.add_typed_system(LabelledSystem("first"));
+        let _busy = first.lock().await;
+
+        let second = manager.add_typed_system(LabelledSystem("second"));
+        assert_eq!(manager.system_count(), 1);
+        assert!(manager.get_system("first").is_none());
+        assert!(Arc::ptr_eq(&manager.get_typed_system::<LabelledSystem>().unwrap(), &second));
+    }
 } 
\ No newline at end of file
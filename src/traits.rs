@@ -1,11 +1,26 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use uuid::Uuid;
+use crate::{OatsError, Result};
 
 /// Trait identifier
 pub type TraitId = Uuid;
 
+/// Trait metadata key holding the RFC 3339 time after which the trait has expired
+pub const EXPIRES_AT_KEY: &str = "expires_at";
+
+/// Leading bytes of a gzip stream, used to recognise compressed binary data
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Relative tolerance used when comparing numbers in `TraitData::approx_eq`
+const NUMBER_EPSILON: f64 = 1e-9;
+
 /// A trait represents immutable domain state
+///
+/// Traits created by `new`, `try_new`, `set_data` or deserialization never hold a NaN or
+/// infinite number; writing through the public `data` field or `data_mut` is unchecked.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trait {
     /// Unique identifier for this trait
@@ -15,13 +30,49 @@ pub struct Trait {
     /// Version of the trait
     pub version: u32,
     /// The actual trait data
+    #[serde(deserialize_with = "deserialize_finite_data")]
     pub data: TraitData,
     /// Metadata about the trait
     pub metadata: HashMap<String, String>,
+    /// Name of the action that created this trait
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Human-readable explanation of the trait
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Tags used to categorize the trait
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Name shown to users in the default locale
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Names shown to users, keyed by locale code such as `"es"`
+    #[serde(default)]
+    pub localized_names: HashMap<String, String>,
+}
+
+/// Reject a NaN or infinite number as the data of the trait `name`
+fn check_finite(name: &str, data: &TraitData) -> Result<()> {
+    if data.is_number() && !data.is_finite_number() {
+        return Err(OatsError::validation_error(format!(
+            "Trait '{}' has a NaN or infinite value",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Deserialize trait data, rejecting NaN and infinite numbers
+fn deserialize_finite_data<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<TraitData, D::Error> {
+    let data = TraitData::deserialize(deserializer)?;
+    if data.is_number() && !data.is_finite_number() {
+        return Err(serde::de::Error::custom("trait data is a NaN or infinite number"));
+    }
+    Ok(data)
 }
 
 /// The actual data contained in a trait
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TraitData {
     /// Simple string value
     String(String),
@@ -39,45 +90,104 @@ pub enum TraitData {
 
 impl Trait {
     /// Create a new trait with the given name and data
+    ///
+    /// Panics if `data` is a NaN or infinite number; use `try_new` to handle that case.
     #[inline]
     pub fn new(name: impl Into<String>, data: TraitData) -> Self {
-        Self {
+        Self::try_new(name, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a trait from an environment variable via `TraitData::from_env`
+    pub fn from_env(trait_name: &str, env_key: &str) -> Result<Self> {
+        Self::try_new(trait_name, TraitData::from_env(env_key)?)
+    }
+
+    /// Check whether this trait has the same name and approximately the same data as `other`
+    ///
+    /// Numbers are compared with `TraitData::approx_eq`. This is what `==` compares; compare
+    /// `id` separately to tell instances apart.
+    pub fn content_eq(&self, other: &Trait) -> bool {
+        self.name == other.name && self.data.approx_eq(&other.data)
+    }
+
+    /// Create a new trait, rejecting NaN and infinite numbers
+    pub fn try_new(name: impl Into<String>, data: TraitData) -> Result<Self> {
+        let name = name.into();
+        check_finite(&name, &data)?;
+        Ok(Self {
             id: Uuid::new_v4(),
-            name: name.into(),
+            name,
             version: 1,
             data,
             metadata: HashMap::new(),
-        }
+            created_by: None,
+            description: None,
+            tags: Vec::new(),
+            display_name: None,
+            localized_names: HashMap::new(),
+        })
     }
 
     /// Create a new trait with metadata
+    ///
+    /// Panics like `new` on a NaN or infinite number.
     pub fn with_metadata(
         name: impl Into<String>,
         data: TraitData,
         metadata: HashMap<String, String>,
     ) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            name: name.into(),
-            version: 1,
-            data,
-            metadata,
-        }
+        let mut trait_obj = Self::new(name, data);
+        trait_obj.metadata = metadata;
+        trait_obj
+    }
+
+    /// Create a new trait with a human-readable description
+    ///
+    /// Panics like `new` on a NaN or infinite number.
+    pub fn with_description(name: impl Into<String>, data: TraitData, description: &str) -> Self {
+        let mut trait_obj = Self::new(name, data);
+        trait_obj.description = Some(description.to_string());
+        trait_obj
+    }
+
+    /// Create a new trait with a display name for one locale
+    ///
+    /// Panics like `new` on a NaN or infinite number.
+    pub fn with_localization(name: impl Into<String>, data: TraitData, locale: &str, display: &str) -> Self {
+        let mut trait_obj = Self::new(name, data);
+        trait_obj.set_localized_name(locale, display);
+        trait_obj
+    }
+
+    /// Create a new trait attributed to the action that created it
+    ///
+    /// Panics like `new` on a NaN or infinite number.
+    pub fn with_attribution(name: impl Into<String>, data: TraitData, created_by: &str) -> Self {
+        let mut trait_obj = Self::new(name, data);
+        trait_obj.created_by = Some(created_by.to_string());
+        trait_obj
     }
 
     /// Create a new trait with pre-allocated capacity
+    ///
+    /// Panics like `new` on a NaN or infinite number.
     pub fn with_capacity(
         name: impl Into<String>,
         data: TraitData,
         metadata_capacity: usize,
     ) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            name: name.into(),
-            version: 1,
-            data,
-            metadata: HashMap::with_capacity(metadata_capacity),
-        }
+        let mut trait_obj = Self::new(name, data);
+        trait_obj.metadata = HashMap::with_capacity(metadata_capacity);
+        trait_obj
+    }
+
+    /// Create a binary trait whose data is gzip-compressed on creation
+    ///
+    /// Binary data is never rejected, and compressing into memory cannot fail, so this does not panic.
+    #[cfg(feature = "compression")]
+    pub fn with_compressed_binary(name: impl Into<String>, data: Vec<u8>) -> Self {
+        let compressed = gzip(&data).expect("compressing into memory cannot fail");
+        Self::new(name, TraitData::Binary(compressed))
     }
 
     /// Get the trait name
@@ -92,12 +202,31 @@ impl Trait {
         &self.data
     }
 
-    /// Get mutable trait data
+    /// Get mutable trait data, without checking the numbers written; prefer `set_data`
     #[inline]
     pub fn data_mut(&mut self) -> &mut TraitData {
         &mut self.data
     }
 
+    /// Replace the trait data, rejecting NaN and infinite numbers
+    pub fn set_data(&mut self, data: TraitData) -> Result<()> {
+        check_finite(&self.name, &data)?;
+        self.data = data;
+        Ok(())
+    }
+
+    /// Borrow the trait data, cloning only if the caller later needs ownership
+    #[inline]
+    pub fn data_cow(&self) -> Cow<'_, TraitData> {
+        Cow::Borrowed(&self.data)
+    }
+
+    /// Take ownership of the trait data without cloning
+    #[inline]
+    pub fn into_data_cow(self) -> Cow<'static, TraitData> {
+        Cow::Owned(self.data)
+    }
+
     /// Get the trait ID
     #[inline]
     pub fn id(&self) -> TraitId {
@@ -122,15 +251,118 @@ impl Trait {
         self.metadata.insert(key.into(), value.into());
     }
 
-    /// Create a new version of this trait
-    pub fn new_version(&self, data: TraitData) -> Self {
-        Self {
+    /// Expire the trait `ttl` from now
+    pub fn with_ttl(mut self, ttl: std::time::Duration) -> Self {
+        let ttl = chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX);
+        let expires_at = chrono::Utc::now().checked_add_signed(ttl).unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+        self.set_expires_at(expires_at);
+        self
+    }
+
+    /// Set the time after which the trait has expired
+    pub fn set_expires_at(&mut self, expires_at: chrono::DateTime<chrono::Utc>) {
+        self.set_metadata(EXPIRES_AT_KEY, expires_at.to_rfc3339());
+    }
+
+    /// Get the time after which the trait has expired, if it expires
+    pub fn expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let expires_at = self.get_metadata(EXPIRES_AT_KEY)?;
+        chrono::DateTime::parse_from_rfc3339(expires_at)
+            .ok()
+            .map(|t| t.with_timezone(&chrono::Utc))
+    }
+
+    /// Check if the trait has expired at `now`
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at().is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Get the name of the action that created this trait
+    #[inline]
+    pub fn created_by(&self) -> Option<&str> {
+        self.created_by.as_deref()
+    }
+
+    /// Get the trait description
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Get the name to show for `locale`, falling back to `display_name` and then `name`
+    pub fn display_name_for(&self, locale: &str) -> &str {
+        self.localized_names
+            .get(locale)
+            .or(self.display_name.as_ref())
+            .unwrap_or(&self.name)
+    }
+
+    /// Set the name shown for `locale`
+    pub fn set_localized_name(&mut self, locale: impl Into<String>, display: impl Into<String>) {
+        self.localized_names.insert(locale.into(), display.into());
+    }
+
+    /// Get the trait tags
+    #[inline]
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Add a tag if it is not already present
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    /// Create a new version of this trait, rejecting NaN and infinite numbers
+    pub fn new_version(&self, data: TraitData) -> Result<Self> {
+        check_finite(&self.name, &data)?;
+        Ok(Self {
             id: Uuid::new_v4(),
             name: self.name.clone(),
             version: self.version + 1,
             data,
             metadata: self.metadata.clone(),
+            created_by: self.created_by.clone(),
+            description: self.description.clone(),
+            tags: self.tags.clone(),
+            display_name: self.display_name.clone(),
+            localized_names: self.localized_names.clone(),
+        })
+    }
+
+    /// Merge two versions of this trait with `merge_fn`, producing a newer version
+    ///
+    /// Metadata, tags and localized names are combined, with `other`'s entries winning on conflicts.
+    /// Fails if `merge_fn` produces a NaN or infinite number.
+    pub fn merge(self, other: Trait, merge_fn: impl Fn(TraitData, TraitData) -> TraitData) -> Result<Trait> {
+        let data = merge_fn(self.data, other.data);
+        check_finite(&self.name, &data)?;
+        let mut metadata = self.metadata;
+        metadata.extend(other.metadata);
+        let mut localized_names = self.localized_names;
+        localized_names.extend(other.localized_names);
+        let mut tags = self.tags;
+        for tag in other.tags {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
         }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            name: self.name,
+            version: self.version.max(other.version) + 1,
+            data,
+            metadata,
+            created_by: self.created_by.or(other.created_by),
+            description: self.description.or(other.description),
+            tags,
+            display_name: self.display_name.or(other.display_name),
+            localized_names,
+        })
     }
 
     /// Get metadata count
@@ -150,9 +382,34 @@ impl Trait {
     pub fn clear_metadata(&mut self) {
         self.metadata.clear();
     }
+
+    /// Approximate bytes used by this trait: struct size plus name, data, metadata, description and tags
+    pub fn estimated_memory_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.name.len()
+            + self.data.estimated_memory_size()
+            + self.metadata.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>()
+            + self.created_by.as_ref().map_or(0, String::len)
+            + self.description.as_ref().map_or(0, String::len)
+            + self.tags.iter().map(String::len).sum::<usize>()
+            + self.display_name.as_ref().map_or(0, String::len)
+            + self.localized_names.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>()
+    }
 }
 
 impl TraitData {
+    /// Approximate payload bytes: string and binary lengths, 8 per number, 1 per boolean
+    pub fn estimated_memory_size(&self) -> usize {
+        match self {
+            TraitData::String(s) => s.len(),
+            TraitData::Number(_) => 8,
+            TraitData::Boolean(_) => 1,
+            TraitData::Object(map) => map.iter().map(|(k, v)| k.len() + json_memory_size(v)).sum(),
+            TraitData::Array(items) => items.iter().map(json_memory_size).sum(),
+            TraitData::Binary(bytes) => bytes.len(),
+        }
+    }
+
     /// Check if this trait data is a string
     pub fn is_string(&self) -> bool {
         matches!(self, TraitData::String(_))
@@ -163,6 +420,27 @@ impl TraitData {
         matches!(self, TraitData::Number(_))
     }
 
+    /// Check if this trait data is a number that is neither NaN nor infinite
+    pub fn is_finite_number(&self) -> bool {
+        matches!(self, TraitData::Number(n) if n.is_finite())
+    }
+
+    /// Compare with other trait data, treating numbers within a small relative epsilon as equal
+    ///
+    /// Numbers nested in objects and arrays are compared the same way.
+    pub fn approx_eq(&self, other: &TraitData) -> bool {
+        match (self, other) {
+            (TraitData::Number(a), TraitData::Number(b)) => numbers_approx_eq(*a, *b),
+            (TraitData::Object(a), TraitData::Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| json_approx_eq(v, w)))
+            }
+            (TraitData::Array(a), TraitData::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(v, w)| json_approx_eq(v, w))
+            }
+            _ => self == other,
+        }
+    }
+
     /// Check if this trait data is a boolean
     pub fn is_boolean(&self) -> bool {
         matches!(self, TraitData::Boolean(_))
@@ -230,12 +508,698 @@ impl TraitData {
             _ => None,
         }
     }
+
+    /// Look up a nested value by dot-separated path, e.g. `"customer.address.zip"`
+    ///
+    /// Numeric segments index into arrays. Only object and array data have nested values.
+    pub fn get_path(&self, path: &str) -> Option<&serde_json::Value> {
+        let mut segments = path.split('.');
+        let first = segments.next()?;
+        let root = match self {
+            TraitData::Object(map) => map.get(first)?,
+            TraitData::Array(items) => items.get(first.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+        segments.try_fold(root, |value, segment| match value {
+            serde_json::Value::Object(map) => map.get(segment),
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    /// Set a nested value by dot-separated path, creating intermediate objects as needed
+    ///
+    /// Fails if the data is not an object, a segment is empty, or the path runs through a
+    /// value that is neither an object nor an array with that index.
+    pub fn set_path(&mut self, path: &str, value: serde_json::Value) -> Result<()> {
+        if path.split('.').any(str::is_empty) {
+            return Err(OatsError::validation_error(format!("Invalid trait data path '{}'", path)));
+        }
+        let TraitData::Object(map) = self else {
+            return Err(OatsError::invalid_state("Trait data is not an object"));
+        };
+        let Some((parents, last)) = path.rsplit_once('.') else {
+            map.insert(path.to_string(), value);
+            return Ok(());
+        };
+
+        let mut segments = parents.split('.');
+        let first = segments.next().unwrap_or_default();
+        let mut current = map
+            .entry(first.to_string())
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+        for segment in segments {
+            current = match current {
+                serde_json::Value::Object(map) => map
+                    .entry(segment)
+                    .or_insert_with(|| serde_json::Value::Object(Default::default())),
+                serde_json::Value::Array(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get_mut(index))
+                    .ok_or_else(|| OatsError::invalid_state(format!("No array item '{}' in path '{}'", segment, path)))?,
+                _ => return Err(OatsError::invalid_state(format!("Path '{}' runs through a non-object value", path))),
+            };
+        }
+        match current {
+            serde_json::Value::Object(map) => {
+                map.insert(last.to_string(), value);
+            }
+            serde_json::Value::Array(items) => {
+                let slot = last
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get_mut(index))
+                    .ok_or_else(|| OatsError::invalid_state(format!("No array item '{}' in path '{}'", last, path)))?;
+                *slot = value;
+            }
+            _ => return Err(OatsError::invalid_state(format!("Path '{}' runs through a non-object value", path))),
+        }
+        Ok(())
+    }
+
+    /// Append an item to array data
+    pub fn push_array_item(&mut self, item: serde_json::Value) -> Result<()> {
+        match self {
+            TraitData::Array(a) => {
+                a.push(item);
+                Ok(())
+            }
+            _ => Err(OatsError::invalid_state("Trait data is not an array")),
+        }
+    }
+
+    /// Remove and return the last item of array data
+    pub fn pop_array_item(&mut self) -> Result<Option<serde_json::Value>> {
+        match self {
+            TraitData::Array(a) => Ok(a.pop()),
+            _ => Err(OatsError::invalid_state("Trait data is not an array")),
+        }
+    }
+
+    /// Get the length of array data
+    pub fn array_len(&self) -> Option<usize> {
+        self.as_array().map(Vec::len)
+    }
+
+    /// Get an item of array data by index
+    pub fn array_get(&self, index: usize) -> Option<&serde_json::Value> {
+        self.as_array().and_then(|a| a.get(index))
+    }
+
+    /// Iterate over the items of array data
+    pub fn array_iter(&self) -> Option<impl Iterator<Item = &serde_json::Value>> {
+        self.as_array().map(|a| a.iter())
+    }
+
+    /// Deserialize a field of object data
+    pub fn get_field<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        let value = self
+            .as_object()
+            .ok_or_else(|| OatsError::invalid_state("Trait data is not an object"))?
+            .get(key)
+            .ok_or_else(|| OatsError::object_not_found(key))?;
+        Ok(serde_json::from_value(value.clone())?)
+    }
+
+    /// Serialize a value into a field of object data
+    pub fn set_field<T: Serialize>(&mut self, key: &str, value: T) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        self.object_fields_mut()?.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    /// Shallow-merge fields into object data, returning how many were merged
+    pub fn merge_fields(&mut self, fields: &HashMap<String, serde_json::Value>) -> Result<usize> {
+        let object = self.object_fields_mut()?;
+        object.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Ok(fields.len())
+    }
+
+    /// Insert a field into object data unless it is already set, returning whether it was inserted
+    pub fn set_field_if_absent(&mut self, key: &str, value: serde_json::Value) -> Result<bool> {
+        match self.object_fields_mut()?.entry(key.to_string()) {
+            std::collections::hash_map::Entry::Occupied(_) => Ok(false),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Remove a field of object data, returning whether it existed
+    pub fn remove_field(&mut self, key: &str) -> Result<bool> {
+        Ok(self.object_fields_mut()?.remove(key).is_some())
+    }
+
+    /// Get the field names of object data
+    pub fn field_keys(&self) -> Result<Vec<&String>> {
+        self.as_object()
+            .map(|o| o.keys().collect())
+            .ok_or_else(|| OatsError::invalid_state("Trait data is not an object"))
+    }
+
+    fn object_fields_mut(&mut self) -> Result<&mut HashMap<String, serde_json::Value>> {
+        match self {
+            TraitData::Object(o) => Ok(o),
+            _ => Err(OatsError::invalid_state("Trait data is not an object")),
+        }
+    }
+
+    /// Merge strategy keeping whichever value was written last; ties keep `a`
+    pub fn last_write_wins(
+        a: TraitData,
+        b: TraitData,
+        a_ts: chrono::DateTime<chrono::Utc>,
+        b_ts: chrono::DateTime<chrono::Utc>,
+    ) -> TraitData {
+        if b_ts > a_ts { b } else { a }
+    }
+
+    /// Merge strategy keeping the larger of two numbers; non-numeric data keeps `a`
+    pub fn numeric_max(a: TraitData, b: TraitData) -> TraitData {
+        match (&a, &b) {
+            (TraitData::Number(x), TraitData::Number(y)) => TraitData::Number(x.max(*y)),
+            _ => a,
+        }
+    }
+
+    /// Merge strategy adding two numbers; non-numeric data keeps `a`
+    pub fn numeric_sum(a: TraitData, b: TraitData) -> TraitData {
+        match (&a, &b) {
+            (TraitData::Number(x), TraitData::Number(y)) => TraitData::Number(x + y),
+            _ => a,
+        }
+    }
+
+    /// Logical AND of two boolean values
+    pub fn and(&self, other: &TraitData) -> Result<TraitData> {
+        let (a, b) = Self::boolean_pair(self, other, "and")?;
+        Ok(TraitData::Boolean(a && b))
+    }
+
+    /// Logical OR of two boolean values
+    pub fn or(&self, other: &TraitData) -> Result<TraitData> {
+        let (a, b) = Self::boolean_pair(self, other, "or")?;
+        Ok(TraitData::Boolean(a || b))
+    }
+
+    /// Logical XOR of two boolean values
+    pub fn xor(&self, other: &TraitData) -> Result<TraitData> {
+        let (a, b) = Self::boolean_pair(self, other, "xor")?;
+        Ok(TraitData::Boolean(a ^ b))
+    }
+
+    /// Logical NOT of a boolean value
+    pub fn not(&self) -> Result<TraitData> {
+        self.as_boolean()
+            .map(|b| TraitData::Boolean(!b))
+            .ok_or_else(|| OatsError::invalid_state(format!("Cannot apply 'not' to {}", self.type_name())))
+    }
+
+    /// Get a lowercase name of the variant, e.g. `"number"`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            TraitData::String(_) => "string",
+            TraitData::Number(_) => "number",
+            TraitData::Boolean(_) => "boolean",
+            TraitData::Object(_) => "object",
+            TraitData::Array(_) => "array",
+            TraitData::Binary(_) => "binary",
+        }
+    }
+
+    /// Extract two booleans for a logical operator
+    fn boolean_pair(a: &TraitData, b: &TraitData, op: &str) -> Result<(bool, bool)> {
+        match (a, b) {
+            (TraitData::Boolean(a), TraitData::Boolean(b)) => Ok((*a, *b)),
+            _ => Err(OatsError::invalid_state(format!(
+                "Cannot apply '{}' to {} and {}",
+                op,
+                a.type_name(),
+                b.type_name()
+            ))),
+        }
+    }
+
+    /// Convert this trait data into a JSON value
+    ///
+    /// Binary data becomes an array of byte numbers, which converts back as `Array`, not `Binary`.
+    pub fn as_json_value(&self) -> serde_json::Value {
+        self.clone().into()
+    }
+
+    /// Create trait data from a JSON value
+    pub fn from_json_value(value: serde_json::Value) -> Result<TraitData> {
+        TraitData::try_from(value)
+    }
+
+    /// Read trait data from an environment variable, detecting its type
+    ///
+    /// `true` and `false` become booleans, finite numbers become numbers and anything
+    /// else stays a string. Fails with `ObjectNotFound` if the variable is not set.
+    pub fn from_env(key: &str) -> Result<TraitData> {
+        let value = match std::env::var(key) {
+            Ok(value) => value,
+            Err(std::env::VarError::NotPresent) => return Err(OatsError::object_not_found(key)),
+            Err(std::env::VarError::NotUnicode(_)) => {
+                return Err(OatsError::validation_error(format!("Environment variable '{}' is not valid unicode", key)))
+            }
+        };
+
+        Ok(match value.as_str() {
+            "true" => TraitData::Boolean(true),
+            "false" => TraitData::Boolean(false),
+            _ => match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => TraitData::Number(number),
+                _ => TraitData::String(value),
+            },
+        })
+    }
+
+    /// Check if this is binary data carrying a gzip header
+    pub fn is_compressed(&self) -> bool {
+        matches!(self, TraitData::Binary(b) if b.starts_with(&GZIP_MAGIC))
+    }
+
+    /// Gzip-compress binary data, returning a new `Binary` value
+    #[cfg(feature = "compression")]
+    pub fn compress(&self) -> Result<TraitData> {
+        match self {
+            TraitData::Binary(b) => Ok(TraitData::Binary(gzip(b)?)),
+            _ => Err(OatsError::validation_error("Only binary trait data can be compressed")),
+        }
+    }
+
+    /// Inflate gzip-compressed binary data, returning a new `Binary` value
+    #[cfg(feature = "compression")]
+    pub fn decompress(&self) -> Result<TraitData> {
+        use std::io::Read;
+
+        match self {
+            TraitData::Binary(b) if self.is_compressed() => {
+                let mut inflated = Vec::new();
+                flate2::read::GzDecoder::new(b.as_slice()).read_to_end(&mut inflated)?;
+                Ok(TraitData::Binary(inflated))
+            }
+            TraitData::Binary(_) => Err(OatsError::validation_error("Binary trait data is not compressed")),
+            _ => Err(OatsError::validation_error("Only binary trait data can be decompressed")),
+        }
+    }
+}
+
+/// Migrates trait data from one schema version to the next
+pub struct TraitMigration {
+    /// Version the migration applies to
+    pub from_version: u32,
+    /// Version the trait has after migrating
+    pub to_version: u32,
+    /// Transformation applied to the trait data
+    pub migrate: Box<dyn Fn(TraitData) -> Result<TraitData> + Send + Sync>,
+}
+
+impl TraitMigration {
+    /// Create a new migration between two versions
+    pub fn new(
+        from_version: u32,
+        to_version: u32,
+        migrate: impl Fn(TraitData) -> Result<TraitData> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            from_version,
+            to_version,
+            migrate: Box::new(migrate),
+        }
+    }
+}
+
+impl std::fmt::Debug for TraitMigration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraitMigration")
+            .field("from_version", &self.from_version)
+            .field("to_version", &self.to_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Registry of trait migrations keyed by trait name
+#[derive(Debug, Default)]
+pub struct TraitMigrationRegistry {
+    migrations: HashMap<String, Vec<TraitMigration>>,
+}
+
+impl TraitMigrationRegistry {
+    /// Create an empty migration registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a migration for the named trait
+    pub fn register(&mut self, trait_name: impl Into<String>, migration: TraitMigration) {
+        let migrations = self.migrations.entry(trait_name.into()).or_default();
+        migrations.push(migration);
+        migrations.sort_by_key(|m| m.from_version);
+    }
+
+    /// Check whether the trait has a migration available for its current version
+    pub fn needs_migration(&self, trait_obj: &Trait) -> bool {
+        self.migrations
+            .get(&trait_obj.name)
+            .is_some_and(|migrations| migrations.iter().any(|m| m.from_version == trait_obj.version))
+    }
+
+    /// Apply every applicable migration to the trait in version order
+    ///
+    /// The trait is only changed if every step succeeds and the migrated data has no NaN or
+    /// infinite number.
+    pub fn apply(&self, trait_obj: &mut Trait) -> Result<()> {
+        let Some(migrations) = self.migrations.get(&trait_obj.name) else {
+            return Ok(());
+        };
+
+        let mut data = trait_obj.data.clone();
+        let mut version = trait_obj.version;
+        while let Some(migration) = migrations.iter().find(|m| m.from_version == version) {
+            if migration.to_version <= migration.from_version {
+                return Err(OatsError::invalid_state(format!(
+                    "Migration for trait '{}' from version {} does not move forward",
+                    trait_obj.name, migration.from_version
+                )));
+            }
+            data = (migration.migrate)(data)?;
+            check_finite(&trait_obj.name, &data)?;
+            version = migration.to_version;
+        }
+        trait_obj.data = data;
+        trait_obj.version = version;
+        Ok(())
+    }
+}
+
+/// Numbers are ordered by value; other data is only comparable to an equal value
+impl PartialOrd for TraitData {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (TraitData::Number(a), TraitData::Number(b)) => a.partial_cmp(b),
+            _ if self == other => Some(std::cmp::Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+/// Binary data becomes an array of byte numbers; JSON has no bytes type, so this is lossy
+impl From<TraitData> for serde_json::Value {
+    fn from(data: TraitData) -> Self {
+        match data {
+            TraitData::String(s) => serde_json::Value::String(s),
+            TraitData::Number(n) => serde_json::json!(n),
+            TraitData::Boolean(b) => serde_json::Value::Bool(b),
+            TraitData::Object(o) => serde_json::Value::Object(o.into_iter().collect()),
+            TraitData::Array(a) => serde_json::Value::Array(a),
+            TraitData::Binary(b) => serde_json::json!(b),
+        }
+    }
+}
+
+impl TryFrom<serde_json::Value> for TraitData {
+    type Error = OatsError;
+
+    fn try_from(value: serde_json::Value) -> Result<Self> {
+        use serde::de::Error;
+
+        match value {
+            serde_json::Value::String(s) => Ok(TraitData::String(s)),
+            serde_json::Value::Number(n) => n
+                .as_f64()
+                .map(TraitData::Number)
+                .ok_or_else(|| serde_json::Error::custom(format!("number {} is not representable as f64", n)).into()),
+            serde_json::Value::Bool(b) => Ok(TraitData::Boolean(b)),
+            serde_json::Value::Object(o) => Ok(TraitData::Object(o.into_iter().collect())),
+            serde_json::Value::Array(a) => Ok(TraitData::Array(a)),
+            serde_json::Value::Null => Err(serde_json::Error::custom("null has no trait data representation").into()),
+        }
+    }
+}
+
+/// Conversion between a Rust value and trait data, used by `#[oats_object]` fields
+pub trait TraitValue: Sized {
+    /// Read the value, returning `None` if the data has a different shape
+    fn from_trait_data(data: &TraitData) -> Option<Self>;
+
+    /// Convert the value into trait data
+    fn to_trait_data(&self) -> TraitData;
+}
+
+impl TraitValue for f64 {
+    fn from_trait_data(data: &TraitData) -> Option<Self> {
+        data.as_number()
+    }
+
+    fn to_trait_data(&self) -> TraitData {
+        TraitData::Number(*self)
+    }
+}
+
+impl TraitValue for f32 {
+    fn from_trait_data(data: &TraitData) -> Option<Self> {
+        data.as_number().map(|n| n as f32)
+    }
+
+    fn to_trait_data(&self) -> TraitData {
+        TraitData::Number(f64::from(*self))
+    }
+}
+
+/// Integers are stored as numbers and only read back when whole and in range
+macro_rules! impl_integer_trait_value {
+    ($($int:ty),*) => {
+        $(
+            impl TraitValue for $int {
+                fn from_trait_data(data: &TraitData) -> Option<Self> {
+                    let n = data.as_number()?;
+                    (n.fract() == 0.0 && n >= <$int>::MIN as f64 && n <= <$int>::MAX as f64).then(|| n as $int)
+                }
+
+                fn to_trait_data(&self) -> TraitData {
+                    TraitData::Number(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_trait_value!(i32, i64, u32, u64, usize);
+
+impl TraitValue for bool {
+    fn from_trait_data(data: &TraitData) -> Option<Self> {
+        data.as_boolean()
+    }
+
+    fn to_trait_data(&self) -> TraitData {
+        TraitData::Boolean(*self)
+    }
+}
+
+impl TraitValue for String {
+    fn from_trait_data(data: &TraitData) -> Option<Self> {
+        data.as_string().cloned()
+    }
+
+    fn to_trait_data(&self) -> TraitData {
+        TraitData::String(self.clone())
+    }
+}
+
+/// Strings longer than this are truncated when displayed
+const DISPLAY_STRING_LIMIT: usize = 80;
+
+impl std::fmt::Display for TraitData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraitData::String(s) if s.chars().count() > DISPLAY_STRING_LIMIT => {
+                let truncated: String = s.chars().take(DISPLAY_STRING_LIMIT).collect();
+                write!(f, "{}...", truncated)
+            }
+            TraitData::String(s) => write!(f, "{}", s),
+            TraitData::Number(n) => write!(f, "{}", n),
+            TraitData::Boolean(b) => write!(f, "{}", b),
+            TraitData::Object(o) => {
+                let mut keys: Vec<_> = o.keys().collect();
+                keys.sort();
+                let fields: Vec<String> = keys.iter().map(|k| format!("{}={}", k, o[*k])).collect();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+            TraitData::Array(a) => {
+                let items: Vec<String> = a.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            TraitData::Binary(b) => write!(f, "<{} bytes>", b.len()),
+        }
+    }
+}
+
+/// Check whether two numbers are equal within `NUMBER_EPSILON`, relative to their magnitude
+fn numbers_approx_eq(a: f64, b: f64) -> bool {
+    a == b || (a - b).abs() <= NUMBER_EPSILON * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Compare JSON values recursively, treating close numbers as equal
+fn json_approx_eq(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => numbers_approx_eq(x, y),
+            _ => x == y,
+        },
+        (Value::Array(x), Value::Array(y)) => x.len() == y.len() && x.iter().zip(y).all(|(v, w)| json_approx_eq(v, w)),
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| json_approx_eq(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Approximate payload bytes of a JSON value, matching `TraitData::estimated_memory_size`
+fn json_memory_size(value: &serde_json::Value) -> usize {
+    use serde_json::Value;
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 8,
+        Value::String(s) => s.len(),
+        Value::Array(items) => items.iter().map(json_memory_size).sum(),
+        Value::Object(map) => map.iter().map(|(k, v)| k.len() + json_memory_size(v)).sum(),
+    }
+}
+
+/// Traits are equal when their names and data match; IDs, versions and metadata are ignored
+impl PartialEq for Trait {
+    fn eq(&self, other: &Self) -> bool {
+        self.content_eq(other)
+    }
+}
+
+impl std::fmt::Display for Trait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Trait({}={})", self.name, self.data)
+    }
+}
+
+#[cfg(feature = "compression")]
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_localized_display_names() {
+        let mut balance = Trait::with_localization("balance", TraitData::Number(10.0), "es", "Saldo");
+        assert_eq!(balance.display_name_for("es"), "Saldo");
+        assert_eq!(balance.display_name_for("fr"), "balance");
+
+        balance.display_name = Some("Balance".to_string());
+        balance.set_localized_name("fr", "Solde");
+        assert_eq!(balance.display_name_for("fr"), "Solde");
+        assert_eq!(balance.display_name_for("de"), "Balance");
+        assert_eq!(balance.new_version(TraitData::Number(5.0)).unwrap().display_name_for("es"), "Saldo");
+    }
+
+    #[test]
+    fn test_from_env() {
+        std::env::set_var("OATS_TEST_FROM_ENV_NUMBER", "12.5");
+        std::env::set_var("OATS_TEST_FROM_ENV_BOOL", "true");
+        std::env::set_var("OATS_TEST_FROM_ENV_STRING", "sk-live");
+        std::env::set_var("OATS_TEST_FROM_ENV_NAN", "NaN");
+
+        assert_eq!(TraitData::from_env("OATS_TEST_FROM_ENV_NUMBER").unwrap(), TraitData::Number(12.5));
+        assert_eq!(TraitData::from_env("OATS_TEST_FROM_ENV_BOOL").unwrap(), TraitData::Boolean(true));
+        assert_eq!(TraitData::from_env("OATS_TEST_FROM_ENV_STRING").unwrap(), TraitData::String("sk-live".to_string()));
+        assert_eq!(TraitData::from_env("OATS_TEST_FROM_ENV_NAN").unwrap(), TraitData::String("NaN".to_string()));
+        assert!(matches!(
+            TraitData::from_env("OATS_TEST_FROM_ENV_MISSING"),
+            Err(OatsError::ObjectNotFound { .. })
+        ));
+
+        let discount = Trait::from_env("discount_percentage", "OATS_TEST_FROM_ENV_NUMBER").unwrap();
+        assert_eq!(discount.name(), "discount_percentage");
+        assert_eq!(discount.data().as_number(), Some(12.5));
+    }
+
+    #[test]
+    fn test_trait_content_equality() {
+        let a = Trait::new("health", TraitData::Number(0.1 + 0.2));
+        let mut b = Trait::new("health", TraitData::Number(0.3));
+        b.version = 4;
+        assert_ne!(a.id, b.id);
+        assert_eq!(a, b);
+        assert!(a.content_eq(&b));
+        assert_ne!(a, Trait::new("mana", TraitData::Number(0.3)));
+        assert_ne!(a, Trait::new("health", TraitData::Number(0.31)));
+
+        let mut position = HashMap::new();
+        position.insert("x".to_string(), serde_json::json!(0.1 + 0.2));
+        let mut rounded = HashMap::new();
+        rounded.insert("x".to_string(), serde_json::json!(0.3));
+        assert!(TraitData::Object(position).approx_eq(&TraitData::Object(rounded)));
+        assert!(!TraitData::Array(vec![serde_json::json!(1.0)]).approx_eq(&TraitData::Array(vec![])));
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let yes = TraitData::Boolean(true);
+        let no = TraitData::Boolean(false);
+        assert_eq!(yes.and(&no).unwrap(), no);
+        assert_eq!(yes.or(&no).unwrap(), yes);
+        assert_eq!(yes.xor(&yes).unwrap(), no);
+        assert_eq!(no.not().unwrap(), yes);
+
+        let err = yes.and(&TraitData::Number(1.0)).unwrap_err();
+        assert!(matches!(err, OatsError::InvalidState { .. }));
+        assert!(TraitData::String("on".to_string()).not().is_err());
+    }
+
+    #[test]
+    fn test_try_new_rejects_non_finite_numbers() {
+        assert!(TraitData::Number(1.5).is_finite_number());
+        assert!(!TraitData::Number(f64::NAN).is_finite_number());
+        assert!(!TraitData::Boolean(true).is_finite_number());
+
+        assert!(Trait::try_new("health", TraitData::Number(100.0)).is_ok());
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = Trait::try_new("health", TraitData::Number(value)).unwrap_err();
+            assert!(matches!(err, OatsError::ValidationError { .. }));
+        }
+        assert!(std::panic::catch_unwind(|| Trait::new("health", TraitData::Number(f64::NAN))).is_err());
+
+        let mut health = Trait::new("health", TraitData::Number(100.0));
+        assert!(health.set_data(TraitData::Number(f64::INFINITY)).is_err());
+        assert_eq!(health.data(), &TraitData::Number(100.0));
+        health.set_data(TraitData::Number(50.0)).unwrap();
+        assert!(matches!(
+            health.new_version(TraitData::Number(f64::NAN)),
+            Err(OatsError::ValidationError { .. })
+        ));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_deserialize_rejects_non_finite_numbers() {
+        let mut health = Trait::new("health", TraitData::Number(100.0));
+        let bytes = rmp_serde::to_vec_named(&health).unwrap();
+        assert!(rmp_serde::from_slice::<Trait>(&bytes).is_ok());
+
+        *health.data_mut() = TraitData::Number(f64::NAN);
+        let bytes = rmp_serde::to_vec_named(&health).unwrap();
+        assert!(rmp_serde::from_slice::<Trait>(&bytes).is_err());
+    }
+
     #[test]
     fn test_trait_creation() {
         let trait_data = TraitData::String("test".to_string());
@@ -246,6 +1210,19 @@ mod tests {
         assert!(trait_obj.data.is_string());
     }
 
+    #[test]
+    fn test_trait_data_cow() {
+        let trait_obj = Trait::new("inventory", TraitData::Array(vec![serde_json::json!("sword")]));
+        assert!(matches!(trait_obj.data_cow(), Cow::Borrowed(_)));
+
+        let mut data = trait_obj.data_cow();
+        data.to_mut().push_array_item(serde_json::json!("shield")).unwrap();
+        assert_eq!(data.array_len(), Some(2));
+        assert_eq!(trait_obj.data().array_len(), Some(1));
+
+        assert!(matches!(trait_obj.into_data_cow(), Cow::Owned(_)));
+    }
+
     #[test]
     fn test_trait_data_methods() {
         let string_data = TraitData::String("hello".to_string());
@@ -269,4 +1246,208 @@ mod tests {
         assert_eq!(trait_obj.get_metadata("key"), Some(&"value".to_string()));
         assert_eq!(trait_obj.get_metadata("nonexistent"), None);
     }
+
+    #[test]
+    fn test_trait_rich_metadata() {
+        let mut health = Trait::with_description("health", TraitData::Number(100.0), "Hit points");
+        health.add_tag("combat");
+        health.add_tag("combat");
+        assert_eq!(health.description(), Some("Hit points"));
+        assert_eq!(health.tags(), ["combat".to_string()]);
+
+        let damage = Trait::with_attribution("damage", TraitData::Number(5.0), "combat");
+        assert_eq!(damage.created_by(), Some("combat"));
+
+        let json = serde_json::to_string(&health).unwrap();
+        let restored: Trait = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.description(), Some("Hit points"));
+        assert_eq!(restored.new_version(TraitData::Number(90.0)).unwrap().tags(), ["combat".to_string()]);
+
+        let legacy: Trait = serde_json::from_str(
+            r#"{"id":"6f0c1a7e-8d4b-4e7a-9a36-2b8f5d0c9e11","name":"health","version":1,"data":{"Number":1.0},"metadata":{}}"#,
+        ).unwrap();
+        assert!(legacy.created_by().is_none() && legacy.tags().is_empty());
+    }
+
+    #[test]
+    fn test_get_and_set_path() {
+        let mut data = TraitData::Object(HashMap::new());
+        data.set_path("customer.address.zip", serde_json::json!("10001")).unwrap();
+        data.set_path("customer.tags", serde_json::json!(["vip", "new"])).unwrap();
+        data.set_path("customer.tags.1", serde_json::json!("returning")).unwrap();
+        data.set_path("status", serde_json::json!("processing")).unwrap();
+
+        assert_eq!(data.get_path("customer.address.zip"), Some(&serde_json::json!("10001")));
+        assert_eq!(data.get_path("customer.tags.1"), Some(&serde_json::json!("returning")));
+        assert_eq!(data.get_path("status"), Some(&serde_json::json!("processing")));
+        assert_eq!(data.get_path("customer.address.city"), None);
+        assert_eq!(data.get_path("status.code"), None);
+
+        assert!(data.set_path("status.code", serde_json::json!(1)).is_err());
+        assert!(data.set_path("customer.tags.5", serde_json::json!("x")).is_err());
+        assert!(data.set_path("customer..zip", serde_json::json!("x")).is_err());
+        assert!(TraitData::Number(1.0).set_path("a", serde_json::json!(1)).is_err());
+        assert_eq!(TraitData::Array(vec![serde_json::json!({"id": 7})]).get_path("0.id"), Some(&serde_json::json!(7)));
+    }
+
+    #[test]
+    fn test_array_helpers() {
+        let mut items = TraitData::Array(Vec::new());
+        items.push_array_item(serde_json::json!("item1")).unwrap();
+        items.push_array_item(serde_json::json!("item2")).unwrap();
+
+        assert_eq!(items.array_len(), Some(2));
+        assert_eq!(items.array_get(0), Some(&serde_json::json!("item1")));
+        assert_eq!(items.array_iter().map(|i| i.count()), Some(2));
+        assert_eq!(items.pop_array_item().unwrap(), Some(serde_json::json!("item2")));
+
+        let mut stock = TraitData::Number(15.0);
+        assert!(stock.push_array_item(serde_json::json!(1)).is_err());
+        assert!(stock.pop_array_item().is_err());
+        assert_eq!(stock.array_len(), None);
+    }
+
+    #[test]
+    fn test_object_field_accessors() {
+        let mut data = TraitData::Object(HashMap::new());
+        data.set_field("level", 7u32).unwrap();
+        data.set_field("class", "ranger").unwrap();
+
+        assert_eq!(data.get_field::<u32>("level").unwrap(), 7);
+        assert_eq!(data.get_field::<String>("class").unwrap(), "ranger");
+        assert!(matches!(data.get_field::<u32>("mana"), Err(OatsError::ObjectNotFound { .. })));
+        assert!(data.get_field::<u32>("class").is_err());
+        assert_eq!(data.field_keys().unwrap().len(), 2);
+
+        let mut fields = HashMap::new();
+        fields.insert("level".to_string(), serde_json::json!(8));
+        fields.insert("guild".to_string(), serde_json::json!("wardens"));
+        assert_eq!(data.merge_fields(&fields).unwrap(), 2);
+        assert_eq!(data.get_field::<u32>("level").unwrap(), 8);
+        assert!(!data.set_field_if_absent("guild", serde_json::json!("rangers")).unwrap());
+        assert!(data.set_field_if_absent("mana", serde_json::json!(10)).unwrap());
+        assert_eq!(data.get_field::<String>("guild").unwrap(), "wardens");
+
+        assert!(data.remove_field("class").unwrap());
+        assert!(!data.remove_field("class").unwrap());
+        assert!(TraitData::Number(1.0).set_field("level", 1).is_err());
+    }
+
+    #[test]
+    fn test_trait_data_ordering() {
+        assert!(TraitData::Number(1.0) < TraitData::Number(2.5));
+        assert_eq!(TraitData::Number(f64::NAN).partial_cmp(&TraitData::Number(1.0)), None);
+        assert_eq!(TraitData::Number(1.0).partial_cmp(&TraitData::Boolean(true)), None);
+        assert_eq!(
+            TraitData::String("a".to_string()).partial_cmp(&TraitData::String("a".to_string())),
+            Some(std::cmp::Ordering::Equal)
+        );
+    }
+
+    #[test]
+    fn test_trait_merge() {
+        let mut local = Trait::new("visits", TraitData::Number(3.0));
+        local.set_metadata("node", "eu-1");
+        local.add_tag("counter");
+        let mut remote = local.new_version(TraitData::Number(4.0)).unwrap();
+        remote.set_metadata("node", "us-1");
+        remote.add_tag("replicated");
+
+        let merged = local.clone().merge(remote.clone(), TraitData::numeric_sum).unwrap();
+        assert_eq!(merged.version, 3);
+        assert_eq!(merged.data.as_number(), Some(7.0));
+        assert_eq!(merged.get_metadata("node").map(String::as_str), Some("us-1"));
+        assert_eq!(merged.tags, ["counter", "replicated"]);
+
+        assert_eq!(local.clone().merge(remote.clone(), TraitData::numeric_max).unwrap().data.as_number(), Some(4.0));
+        let err = local.clone().merge(remote.clone(), |_, _| TraitData::Number(f64::NAN)).unwrap_err();
+        assert!(matches!(err, OatsError::ValidationError { .. }));
+        let earlier = chrono::Utc::now() - chrono::Duration::seconds(1);
+        let merged = local.merge(remote, |a, b| TraitData::last_write_wins(a, b, chrono::Utc::now(), earlier)).unwrap();
+        assert_eq!(merged.data.as_number(), Some(3.0));
+    }
+
+    #[test]
+    fn test_trait_display() {
+        assert_eq!(Trait::new("health", TraitData::Number(100.0)).to_string(), "Trait(health=100)");
+        assert_eq!(TraitData::Number(0.000001).to_string(), "0.000001");
+        assert_eq!(TraitData::Boolean(false).to_string(), "false");
+        assert_eq!(TraitData::String("x".repeat(100)).to_string(), format!("{}...", "x".repeat(80)));
+
+        let mut position = HashMap::new();
+        position.insert("y".to_string(), serde_json::json!(2.5));
+        position.insert("x".to_string(), serde_json::json!(1));
+        assert_eq!(TraitData::Object(position).to_string(), "{x=1, y=2.5}");
+        assert_eq!(TraitData::Binary(vec![0; 4]).to_string(), "<4 bytes>");
+    }
+
+    #[test]
+    fn test_json_value_conversion() {
+        let value = serde_json::json!({"order_id": "ORD-001", "total": 99.99});
+        let data = TraitData::from_json_value(value.clone()).unwrap();
+        assert!(data.is_object());
+        assert_eq!(data.as_json_value(), value);
+
+        assert_eq!(TraitData::try_from(serde_json::json!(42)).unwrap().as_number(), Some(42.0));
+        assert_eq!(serde_json::Value::from(TraitData::Boolean(true)), serde_json::json!(true));
+
+        let bytes = TraitData::Binary(vec![1, 255]);
+        assert_eq!(bytes.as_json_value(), serde_json::json!([1, 255]));
+        let round_tripped = TraitData::from_json_value(bytes.as_json_value()).unwrap();
+        assert_eq!(round_tripped, TraitData::Array(vec![serde_json::json!(1), serde_json::json!(255)]));
+        assert!(round_tripped.as_binary().is_none());
+        assert!(matches!(
+            TraitData::try_from(serde_json::Value::Null),
+            Err(OatsError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_trait_migration_chain() {
+        let mut registry = TraitMigrationRegistry::new();
+        registry.register("current_order", TraitMigration::new(2, 3, |data| {
+            let mut order = data.as_object().cloned().unwrap_or_default();
+            order.insert("status".to_string(), serde_json::json!("pending"));
+            Ok(TraitData::Object(order))
+        }));
+        registry.register("current_order", TraitMigration::new(1, 2, |data| {
+            let mut order = data.as_object().cloned().unwrap_or_default();
+            if let Some(id) = order.remove("order_id") {
+                order.insert("id".to_string(), id);
+            }
+            Ok(TraitData::Object(order))
+        }));
+
+        let mut order = HashMap::new();
+        order.insert("order_id".to_string(), serde_json::json!("ORD-001"));
+        let mut trait_obj = Trait::new("current_order", TraitData::Object(order));
+        assert!(registry.needs_migration(&trait_obj));
+
+        registry.apply(&mut trait_obj).unwrap();
+        let order = trait_obj.data.as_object().unwrap();
+        assert_eq!(trait_obj.version, 3);
+        assert_eq!(order.get("id"), Some(&serde_json::json!("ORD-001")));
+        assert_eq!(order.get("status"), Some(&serde_json::json!("pending")));
+        assert!(!registry.needs_migration(&trait_obj));
+
+        registry.register("health", TraitMigration::new(1, 2, |data| Ok(TraitData::Number(data.as_number().unwrap_or(0.0) * 2.0))));
+        registry.register("health", TraitMigration::new(2, 3, |_| Ok(TraitData::Number(f64::INFINITY))));
+        let mut health = Trait::new("health", TraitData::Number(50.0));
+        assert!(matches!(registry.apply(&mut health), Err(OatsError::ValidationError { .. })));
+        assert_eq!(health.version, 1);
+        assert_eq!(health.data(), &TraitData::Number(50.0));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_binary_compression_round_trip() {
+        let level_map = vec![7u8; 4096];
+        let trait_obj = Trait::with_compressed_binary("level_map", level_map.clone());
+
+        assert!(trait_obj.data.is_compressed());
+        assert!(trait_obj.data.as_binary().unwrap().len() < level_map.len());
+        assert_eq!(trait_obj.data.decompress().unwrap().as_binary(), Some(&level_map));
+        assert!(!TraitData::Binary(level_map).is_compressed());
+        assert!(TraitData::Number(1.0).compress().is_err());
+    }
 } 
\ No newline at end of file
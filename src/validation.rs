@@ -0,0 +1,291 @@
+//! Multi-field validation of objects against declarative schemas
+
+use std::collections::HashMap;
+use crate::actions::error_codes;
+use crate::{Object, TraitData};
+
+pub use regex::Regex;
+
+/// A single schema violation found by `ObjectSchema::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Field that failed: `"name"`, `"object_type"`, `"traits"` or a trait name
+    pub field: String,
+    /// Human-readable description of the violation
+    pub message: String,
+    /// Machine-readable code, see `error_codes`
+    pub code: u32,
+}
+
+impl ValidationError {
+    /// Create a validation error
+    pub fn new(field: impl Into<String>, message: impl Into<String>, code: u32) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+            code,
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} (code {})", self.field, self.message, self.code)
+    }
+}
+
+/// Constraints on a single trait
+#[derive(Debug, Clone, Default)]
+pub struct TraitSchema {
+    /// Expected `TraitData::type_name`, or any type if unset
+    pub data_type: Option<&'static str>,
+    /// Whether the trait must be present
+    pub required: bool,
+    /// Inclusive lower bound for number traits
+    pub min: Option<f64>,
+    /// Inclusive upper bound for number traits
+    pub max: Option<f64>,
+}
+
+impl TraitSchema {
+    /// Require a trait of any type
+    pub fn any() -> Self {
+        Self { required: true, ..Self::default() }
+    }
+
+    /// Require a number trait
+    pub fn number() -> Self {
+        Self::of_type("number")
+    }
+
+    /// Require a string trait
+    pub fn string() -> Self {
+        Self::of_type("string")
+    }
+
+    /// Require a boolean trait
+    pub fn boolean() -> Self {
+        Self::of_type("boolean")
+    }
+
+    /// Require an object trait
+    pub fn object() -> Self {
+        Self::of_type("object")
+    }
+
+    /// Require an array trait
+    pub fn array() -> Self {
+        Self::of_type("array")
+    }
+
+    /// Require a binary trait
+    pub fn binary() -> Self {
+        Self::of_type("binary")
+    }
+
+    fn of_type(data_type: &'static str) -> Self {
+        Self { data_type: Some(data_type), ..Self::any() }
+    }
+
+    /// Restrict number values to the inclusive range `min..=max`
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Allow the trait to be missing; it is still checked when present
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// Check `data` against this schema, pushing any violations for `trait_name`
+    fn validate(&self, trait_name: &str, data: &TraitData, errors: &mut Vec<ValidationError>) {
+        if let Some(expected) = self.data_type {
+            if data.type_name() != expected {
+                errors.push(ValidationError::new(
+                    trait_name,
+                    format!("expected {}, found {}", expected, data.type_name()),
+                    error_codes::VALIDATION_FAILED,
+                ));
+                return;
+            }
+        }
+
+        if let Some(value) = data.as_number() {
+            if self.min.is_some_and(|min| value < min) || self.max.is_some_and(|max| value > max) {
+                errors.push(ValidationError::new(
+                    trait_name,
+                    format!(
+                        "value {} is outside {}..={}",
+                        value,
+                        self.min.map_or("".to_string(), |min| min.to_string()),
+                        self.max.map_or("".to_string(), |max| max.to_string())
+                    ),
+                    error_codes::VALIDATION_FAILED,
+                ));
+            }
+        }
+    }
+}
+
+/// Declarative constraints on an object's name, type and traits
+#[derive(Debug, Clone, Default)]
+pub struct ObjectSchema {
+    /// Pattern the object's name must match
+    pub name_pattern: Option<Regex>,
+    /// Required object type
+    pub type_constraint: Option<String>,
+    /// Constraints on individual traits, keyed by trait name
+    pub trait_schemas: HashMap<String, TraitSchema>,
+    /// Minimum number of traits
+    pub min_traits: Option<usize>,
+    /// Maximum number of traits
+    pub max_traits: Option<usize>,
+}
+
+impl ObjectSchema {
+    /// Start building a schema
+    pub fn builder() -> ObjectSchemaBuilder {
+        ObjectSchemaBuilder::default()
+    }
+
+    /// Check `object` against every constraint, collecting all violations
+    pub fn validate(&self, object: &Object) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if let Some(pattern) = &self.name_pattern {
+            if !pattern.is_match(object.name()) {
+                errors.push(ValidationError::new(
+                    "name",
+                    format!("'{}' does not match /{}/", object.name(), pattern.as_str()),
+                    error_codes::VALIDATION_FAILED,
+                ));
+            }
+        }
+
+        if let Some(object_type) = &self.type_constraint {
+            if object.object_type() != object_type {
+                errors.push(ValidationError::new(
+                    "object_type",
+                    format!("expected '{}', found '{}'", object_type, object.object_type()),
+                    error_codes::VALIDATION_FAILED,
+                ));
+            }
+        }
+
+        let trait_count = object.trait_count();
+        if self.min_traits.is_some_and(|min| trait_count < min) || self.max_traits.is_some_and(|max| trait_count > max) {
+            errors.push(ValidationError::new(
+                "traits",
+                format!(
+                    "{} traits is outside {}..={}",
+                    trait_count,
+                    self.min_traits.map_or("".to_string(), |min| min.to_string()),
+                    self.max_traits.map_or("".to_string(), |max| max.to_string())
+                ),
+                error_codes::VALIDATION_FAILED,
+            ));
+        }
+
+        let mut trait_names: Vec<&String> = self.trait_schemas.keys().collect();
+        trait_names.sort();
+        for name in trait_names {
+            let schema = &self.trait_schemas[name];
+            match object.get_trait_data(name) {
+                Some(data) => schema.validate(name, data, &mut errors),
+                None if schema.required => {
+                    errors.push(ValidationError::new(name.as_str(), "missing required trait", error_codes::TRAIT_NOT_FOUND));
+                }
+                None => {}
+            }
+        }
+
+        errors
+    }
+}
+
+/// Fluent builder for an `ObjectSchema`
+#[derive(Debug, Clone, Default)]
+pub struct ObjectSchemaBuilder {
+    schema: ObjectSchema,
+}
+
+impl ObjectSchemaBuilder {
+    /// Require the object's name to match `pattern`
+    pub fn with_name_pattern(mut self, pattern: Regex) -> Self {
+        self.schema.name_pattern = Some(pattern);
+        self
+    }
+
+    /// Require the object to have the given type
+    pub fn with_type(mut self, object_type: impl Into<String>) -> Self {
+        self.schema.type_constraint = Some(object_type.into());
+        self
+    }
+
+    /// Constrain the named trait
+    pub fn with_trait(mut self, name: impl Into<String>, schema: TraitSchema) -> Self {
+        self.schema.trait_schemas.insert(name.into(), schema);
+        self
+    }
+
+    /// Require at least `min` traits
+    pub fn with_min_traits(mut self, min: usize) -> Self {
+        self.schema.min_traits = Some(min);
+        self
+    }
+
+    /// Allow at most `max` traits
+    pub fn with_max_traits(mut self, max: usize) -> Self {
+        self.schema.max_traits = Some(max);
+        self
+    }
+
+    /// Build the schema
+    pub fn build(self) -> ObjectSchema {
+        self.schema
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Trait;
+
+    fn character_schema() -> ObjectSchema {
+        ObjectSchema::builder()
+            .with_name_pattern(Regex::new(r"^[a-z_0-9]+$").unwrap())
+            .with_type("enemy")
+            .with_trait("health", TraitSchema::number().with_range(0.0, 1000.0))
+            .with_trait("position", TraitSchema::object())
+            .with_trait("title", TraitSchema::string().optional())
+            .with_max_traits(3)
+            .build()
+    }
+
+    #[test]
+    fn test_valid_object_has_no_errors() {
+        let mut goblin = Object::new("goblin_1", "enemy");
+        goblin.add_trait(Trait::new("health", TraitData::Number(30.0)));
+        goblin.add_trait(Trait::new("position", TraitData::Object(HashMap::new())));
+
+        assert!(goblin.validate_with_schema(&character_schema()).is_empty());
+    }
+
+    #[test]
+    fn test_collects_every_violation() {
+        let mut boss = Object::new("Dragon King", "boss");
+        boss.add_trait(Trait::new("health", TraitData::Number(5000.0)));
+        boss.add_trait(Trait::new("title", TraitData::Number(1.0)));
+        boss.add_trait(Trait::new("mana", TraitData::Number(10.0)));
+        boss.add_trait(Trait::new("armor", TraitData::Number(10.0)));
+
+        let errors = boss.validate_with_schema(&character_schema());
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "object_type", "traits", "health", "position", "title"]);
+        assert_eq!(errors[4].code, error_codes::TRAIT_NOT_FOUND);
+        assert_eq!(errors[5].message, "expected string, found number");
+    }
+}
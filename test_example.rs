@@ -25,7 +25,7 @@ impl Action for TestIncrementAction {
         "Test action that increments a trait"
     }
 
-    async fn execute(&self, context: ActionContext) -> Result<ActionResult, oats_framework::OatsError> {
+    async fn execute(&self, context: ActionContext<'_>) -> Result<ActionResult, oats_framework::OatsError> {
         let target_object = context
             .get_object("target")
             .ok_or_else(|| oats_framework::OatsError::action_failed("Target object not found"))?;
@@ -80,7 +80,7 @@ impl System for TestHealthSystem {
         &self.description
     }
 
-    async fn process(&mut self, objects: Vec<Object>, _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
+    async fn process(&mut self, objects: &[Object], _priority: Priority) -> Result<Vec<ActionResult>, oats_framework::OatsError> {
         let mut results = Vec::new();
         let start_time = std::time::Instant::now();
 
@@ -88,9 +88,9 @@ impl System for TestHealthSystem {
             if object.has_trait("health") {
                 let health_action = TestIncrementAction::new("health", -1.0);
                 let mut context = ActionContext::new();
-                context.add_object("target", object);
+                context.add_object("target", object.clone());
 
-                match health_action.execute(context).await {
+                match health_action.execute_scoped(context).await {
                     Ok(result) => {
                         results.push(result);
                         self.stats.actions_executed += 1;
@@ -147,7 +147,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("3. Testing system processing...");
     let mut health_system = TestHealthSystem::new();
     let objects = vec![player];
-    let results = health_system.process(objects, Priority::Normal).await?;
+    let results = health_system.process(&objects, Priority::Normal).await?;
     assert!(!results.is_empty());
     println!("   ✅ System processing works");
 
@@ -161,7 +161,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "test_type",
         vec![Trait::new("health", TraitData::Number(50.0))],
     );
-    manager.register_object(test_object).await;
+    manager.register_object(test_object).await?;
     
     let results = manager.process_all(Priority::Normal).await?;
     assert!(!results.is_empty());